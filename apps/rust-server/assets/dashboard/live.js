@@ -0,0 +1,31 @@
+// 取り込みで新しい閲覧・会社が現れたら、再読み込みを促すバナーを出す
+(function () {
+  "use strict";
+
+  var banner = document.getElementById("live-banner");
+  if (!banner || !window.EventSource) {
+    return;
+  }
+
+  var counts = { impression: 0, company: 0 };
+  var source = new EventSource("/live/events");
+
+  function render() {
+    var parts = [];
+    if (counts.impression > 0) {
+      parts.push("新しい閲覧 " + counts.impression + " 件");
+    }
+    if (counts.company > 0) {
+      parts.push("新しい会社 " + counts.company + " 社");
+    }
+    banner.querySelector(".live-message").textContent = parts.join("・");
+    banner.hidden = false;
+  }
+
+  Object.keys(counts).forEach(function (kind) {
+    source.addEventListener(kind, function () {
+      counts[kind] += 1;
+      render();
+    });
+  });
+})();
@@ -0,0 +1,25 @@
+-- 閲覧ユーザーの所属履歴（同じ userId で companyPageUrl / shortDescription が変わるたびに区切る）
+CREATE TYPE viewer_affiliation_kind AS ENUM ('company', 'freeform', 'unknown');
+
+CREATE TABLE wantedly_viewer_affiliations (
+    id                  BIGSERIAL PRIMARY KEY,
+    viewer_id           BIGINT NOT NULL REFERENCES wantedly_viewers(id),
+    kind                viewer_affiliation_kind NOT NULL, -- company: companyPageUrl あり / freeform: shortDescription のみ / unknown: どちらもなし
+    company_id          BIGINT REFERENCES wantedly_companies(id), -- kind = company のときのみ
+    short_description   TEXT,                                   -- GQL node.shortDescription
+    first_seen_at       TIMESTAMPTZ NOT NULL,                   -- この所属で観測した最初の閲覧日時
+    last_seen_at        TIMESTAMPTZ NOT NULL,                   -- この所属で観測した最後の閲覧日時
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CHECK ((kind = 'company') = (company_id IS NOT NULL)),
+    CHECK (first_seen_at <= last_seen_at)
+);
+
+CREATE INDEX wantedly_viewer_affiliations_viewer_time_idx
+    ON wantedly_viewer_affiliations (viewer_id, first_seen_at);
+
+-- 会社ページのない閲覧もインプレッションとして残し、閲覧時点の所属を紐付ける
+ALTER TABLE wantedly_impressions
+    ALTER COLUMN company_id_at_view DROP NOT NULL,
+    ADD COLUMN affiliation_id BIGINT REFERENCES wantedly_viewer_affiliations(id),
+    ADD CONSTRAINT wantedly_impressions_raw_uniq UNIQUE (raw_profile_view_id);
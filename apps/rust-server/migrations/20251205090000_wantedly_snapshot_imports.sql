@@ -0,0 +1,9 @@
+-- 取り込んだスナップショットファイルの台帳（同じ内容のファイルは 1 行）
+CREATE TABLE wantedly_snapshot_imports (
+    id              BIGSERIAL PRIMARY KEY,
+    source_file     TEXT NOT NULL,          -- 取り込んだファイル名
+    file_sha256     TEXT NOT NULL UNIQUE,   -- ファイル内容のハッシュ（hex）
+    snapshot_at     TIMESTAMPTZ NOT NULL,   -- ファイル名から得たスナップショット日時
+    record_count    INTEGER NOT NULL,       -- 取り込んだ node 数
+    imported_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
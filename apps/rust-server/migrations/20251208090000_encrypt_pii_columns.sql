@@ -0,0 +1,86 @@
+-- 個人情報カラムの暗号化（pgcrypto）
+--
+-- 鍵はセッション設定 app.pii_key（アプリが接続時に PII_ENCRYPTION_KEY から設定）。
+-- 鍵が未設定のセッションでは従来どおり平文で保存する。
+-- 鍵が設定されている場合:
+--   viewer_user_id          : 鍵付き HMAC のブラインドインデックス（upsert / 検索用の決定的な値）
+--   *_enc                   : pgp_sym_encrypt による暗号文（平文側のカラムは NULL）
+--   pii_key_id              : 暗号化に使った鍵の指紋（鍵ローテーションの対象判定用）
+
+CREATE FUNCTION pii_key_fingerprint(key TEXT) RETURNS TEXT
+LANGUAGE sql IMMUTABLE STRICT AS $$
+    SELECT left(encode(digest(key, 'sha256'), 'hex'), 16)
+$$;
+
+-- ランダムな IV を使うため VOLATILE
+CREATE FUNCTION pii_encrypt_with(value TEXT, key TEXT) RETURNS BYTEA
+LANGUAGE sql VOLATILE STRICT AS $$
+    SELECT pgp_sym_encrypt(value, key, 'cipher-algo=aes256')
+$$;
+
+CREATE FUNCTION pii_decrypt_with(value BYTEA, key TEXT) RETURNS TEXT
+LANGUAGE sql IMMUTABLE STRICT AS $$
+    SELECT pgp_sym_decrypt(value, key)
+$$;
+
+CREATE FUNCTION pii_blind_index_with(value TEXT, key TEXT) RETURNS TEXT
+LANGUAGE sql IMMUTABLE STRICT AS $$
+    SELECT 'bidx:' || encode(hmac(value, 'blind-index:' || key, 'sha256'), 'hex')
+$$;
+
+-- 以下はセッションの鍵を使う版
+
+CREATE FUNCTION pii_key() RETURNS TEXT
+LANGUAGE sql STABLE AS $$
+    SELECT NULLIF(current_setting('app.pii_key', true), '')
+$$;
+
+CREATE FUNCTION pii_key_id() RETURNS TEXT
+LANGUAGE sql STABLE AS $$
+    SELECT pii_key_fingerprint(pii_key())
+$$;
+
+CREATE FUNCTION pii_encrypt(value TEXT) RETURNS BYTEA
+LANGUAGE sql VOLATILE AS $$
+    SELECT pii_encrypt_with(value, pii_key())
+$$;
+
+CREATE FUNCTION pii_decrypt(value BYTEA) RETURNS TEXT
+LANGUAGE plpgsql STABLE AS $$
+BEGIN
+    IF value IS NULL THEN
+        RETURN NULL;
+    END IF;
+    IF pii_key() IS NULL THEN
+        RAISE EXCEPTION 'app.pii_key is not set; cannot decrypt personal data';
+    END IF;
+    RETURN pii_decrypt_with(value, pii_key());
+END
+$$;
+
+-- 鍵がなければ平文をそのまま返す（平文の行との互換用）
+CREATE FUNCTION pii_blind_index(value TEXT) RETURNS TEXT
+LANGUAGE sql STABLE AS $$
+    SELECT CASE WHEN pii_key() IS NULL THEN value ELSE pii_blind_index_with(value, pii_key()) END
+$$;
+
+-- 鍵があれば NULL（平文を残さない）
+CREATE FUNCTION pii_plaintext(value TEXT) RETURNS TEXT
+LANGUAGE sql STABLE AS $$
+    SELECT CASE WHEN pii_key() IS NULL THEN value END
+$$;
+
+ALTER TABLE wantedly_profile_view_raw
+    ALTER COLUMN raw_json DROP NOT NULL,
+    ADD COLUMN viewer_user_id_enc          BYTEA, -- 暗号化した node.userId
+    ADD COLUMN viewer_company_name_raw_enc BYTEA, -- 暗号化した会社名文字列
+    ADD COLUMN raw_json_enc                BYTEA, -- 暗号化した GQL node 全体
+    ADD COLUMN pii_key_id                  TEXT,  -- 暗号化に使った鍵の指紋（平文なら NULL）
+    ADD CONSTRAINT wantedly_profile_view_raw_pii_check CHECK (
+        (pii_key_id IS NULL) = (viewer_user_id_enc IS NULL)
+        AND (raw_json IS NOT NULL OR raw_json_enc IS NOT NULL)
+    );
+
+-- 所属履歴の shortDescription も raw と同じ扱い（正規化のたびに現在の鍵で作り直される）
+ALTER TABLE wantedly_viewer_affiliations
+    ADD COLUMN short_description_enc BYTEA;
@@ -0,0 +1,24 @@
+-- 削除要求（閲覧者 userId / 会社 slug 単位）の記録
+CREATE TYPE erasure_subject_kind AS ENUM ('viewer', 'company');
+
+-- 削除済みの対象。再取り込み時にこの対象の node はスキップする（値そのものは持たずハッシュだけ）
+CREATE TABLE erasure_suppressions (
+    id              BIGSERIAL PRIMARY KEY,
+    subject_kind    erasure_subject_kind NOT NULL,
+    subject_hash    TEXT NOT NULL,          -- hmac_sha256(ERASURE_HASH_KEY, "<kind>:<userId または slug>") の hex
+    key_id          TEXT NOT NULL,          -- ハッシュに使った鍵の指紋（sha256(鍵) の先頭 16 文字）
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT erasure_suppressions_uniq UNIQUE (subject_kind, subject_hash)
+);
+
+-- 削除の監査ログ（誰が・いつ・何件消したか）
+CREATE TABLE erasure_audit_log (
+    id              BIGSERIAL PRIMARY KEY,
+    subject_kind    erasure_subject_kind NOT NULL,
+    subject_hash    TEXT NOT NULL,
+    key_id          TEXT NOT NULL,
+    actor           TEXT NOT NULL,          -- cli / admin-api
+    deleted_counts  JSONB NOT NULL,         -- テーブルごとの削除件数
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
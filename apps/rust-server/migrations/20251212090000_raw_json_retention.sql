@@ -0,0 +1,18 @@
+-- raw_json の保持期間ポリシー
+-- 一定期間を過ぎた raw_json は、正規化をやり直せるだけの項目に削る
+
+ALTER TABLE wantedly_profile_view_raw
+    ADD COLUMN raw_json_compacted_at TIMESTAMPTZ;   -- 削った日時（NULL ならフルの node）
+
+-- 残す項目（WantedlyProfileViewNode が読む項目）
+CREATE FUNCTION wantedly_compact_raw_json(payload jsonb) RETURNS jsonb
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT jsonb_strip_nulls(jsonb_build_object(
+        'userId', payload -> 'userId',
+        'shortDescription', payload -> 'shortDescription',
+        'companyPageUrl', payload -> 'companyPageUrl',
+        'profileImpressionMeta', jsonb_build_object(
+            'impressedDateTime', payload #> '{profileImpressionMeta,impressedDateTime}'
+        )
+    ))
+$$;
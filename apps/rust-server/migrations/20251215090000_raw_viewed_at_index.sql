@@ -0,0 +1,3 @@
+-- raw 一覧（/raw-views）のキーセットページング用
+CREATE INDEX wantedly_profile_view_raw_viewed_at_idx
+    ON wantedly_profile_view_raw (viewed_at DESC, id DESC);
@@ -0,0 +1,27 @@
+-- 仮説 → 検証 → 修正 のループ用：プロフィールの変更（介入）と、その効果の仮説
+
+-- 効果を測る指標（wantedly_impressions の JST 日次集計）
+CREATE TYPE hypothesis_metric AS ENUM ('views', 'distinct_viewers', 'distinct_companies');
+
+CREATE TYPE hypothesis_expected_effect AS ENUM ('increase', 'decrease', 'no_change');
+
+CREATE TABLE profile_hypotheses (
+    id               BIGSERIAL PRIMARY KEY,
+    title            TEXT NOT NULL,
+    description      TEXT,
+    metric           hypothesis_metric NOT NULL DEFAULT 'views',
+    expected_effect  hypothesis_expected_effect NOT NULL,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 実際に行ったプロフィールの変更
+CREATE TABLE profile_interventions (
+    id              BIGSERIAL PRIMARY KEY,
+    hypothesis_id   BIGINT NOT NULL REFERENCES profile_hypotheses(id),
+    description     TEXT NOT NULL,
+    changed_on      DATE NOT NULL,          -- 変更した日（JST）。この日から「変更後」として扱う
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX profile_interventions_hypothesis_idx
+    ON profile_interventions (hypothesis_id, changed_on);
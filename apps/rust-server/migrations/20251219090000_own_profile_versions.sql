@@ -0,0 +1,16 @@
+-- 自分の Wantedly プロフィールのスナップショット（保存した JSON / HTML から取り込む）
+CREATE TABLE wantedly_own_profile_versions (
+    id               BIGSERIAL PRIMARY KEY,
+    captured_at      TIMESTAMPTZ NOT NULL,          -- スナップショットを保存した日時
+    source_file      TEXT NOT NULL,
+    source_format    TEXT NOT NULL CHECK (source_format IN ('json', 'html')),
+    file_sha256      TEXT NOT NULL UNIQUE,          -- 同じファイルの再取り込みを防ぐ
+    content_sha256   TEXT NOT NULL,                 -- 見出し・スキル・職歴を正規化した内容のハッシュ
+    headline         TEXT,
+    skills           JSONB NOT NULL DEFAULT '[]',   -- ["Rust", "PostgreSQL", ...]
+    experiences      JSONB NOT NULL DEFAULT '[]',   -- [{"company", "title", "started_on", "ended_on", "description"}, ...]
+    imported_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX wantedly_own_profile_versions_captured_at_idx
+    ON wantedly_own_profile_versions (captured_at);
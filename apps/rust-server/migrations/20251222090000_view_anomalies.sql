@@ -0,0 +1,24 @@
+-- 日次の閲覧数の急増・急減（検出ジョブが期間ごとに作り直す）
+CREATE TYPE wantedly_anomaly_kind AS ENUM ('spike', 'drop');
+
+CREATE TABLE wantedly_view_anomalies (
+    id                BIGSERIAL PRIMARY KEY,
+    day               DATE NOT NULL UNIQUE,       -- JST の暦日
+    kind              wantedly_anomaly_kind NOT NULL,
+    views             BIGINT NOT NULL,
+    baseline_median   DOUBLE PRECISION NOT NULL,  -- 直前 window_days 日の中央値
+    baseline_scale    DOUBLE PRECISION NOT NULL,  -- 同じ期間のばらつき（1.4826 × MAD、下限あり）
+    score             DOUBLE PRECISION NOT NULL,  -- (views - baseline_median) / baseline_scale
+    window_days       INT NOT NULL,
+    detected_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- その日に多く閲覧した会社（会社の削除に合わせて消える）
+CREATE TABLE wantedly_view_anomaly_companies (
+    id           BIGSERIAL PRIMARY KEY,
+    anomaly_id   BIGINT NOT NULL REFERENCES wantedly_view_anomalies(id) ON DELETE CASCADE,
+    company_id   BIGINT NOT NULL REFERENCES wantedly_companies(id) ON DELETE CASCADE,
+    views        BIGINT NOT NULL,
+    rank         INT NOT NULL,
+    UNIQUE (anomaly_id, company_id)
+);
@@ -0,0 +1,18 @@
+-- 業種・規模・資金調達段階・所在地で閲覧を分けて見るための会社属性
+
+ALTER TYPE company_attribute_source ADD VALUE IF NOT EXISTS 'manual';
+
+-- 従業員数の区分
+CREATE TYPE company_employee_band AS ENUM (
+    '1-10', '11-50', '51-200', '201-500', '501-1000', '1001-5000', '5001+'
+);
+
+CREATE TYPE company_funding_stage AS ENUM (
+    'bootstrapped', 'seed', 'series_a', 'series_b', 'series_c_plus', 'public', 'acquired'
+);
+
+ALTER TABLE wantedly_company_attributes
+    ADD COLUMN industry       TEXT,
+    ADD COLUMN employee_band  company_employee_band,
+    ADD COLUMN funding_stage  company_funding_stage,
+    ADD COLUMN location       TEXT;   -- 本社の都道府県・国など
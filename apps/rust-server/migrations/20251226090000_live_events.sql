@@ -0,0 +1,15 @@
+-- 取り込みで新しく現れた閲覧・会社のイベント（SSE の再接続時に Last-Event-ID 以降を再送する）
+CREATE TYPE wantedly_live_event_kind AS ENUM ('impression', 'company');
+
+CREATE TABLE wantedly_live_events (
+    id             BIGSERIAL PRIMARY KEY,
+    kind           wantedly_live_event_kind NOT NULL,
+    -- 削除（purge）に合わせて消えるよう、元の行を参照する
+    impression_id  BIGINT REFERENCES wantedly_impressions(id) ON DELETE CASCADE,
+    company_id     BIGINT REFERENCES wantedly_companies(id) ON DELETE CASCADE,
+    payload        JSONB NOT NULL,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((kind = 'impression') = (impression_id IS NOT NULL))
+);
+
+CREATE INDEX wantedly_live_events_created_at_idx ON wantedly_live_events (created_at);
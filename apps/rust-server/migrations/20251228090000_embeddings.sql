@@ -0,0 +1,33 @@
+-- 会社・閲覧者の自己紹介（shortDescription）の埋め込み（backfill-embeddings が作る）
+-- 次元は storage::wantedly::EMBEDDING_DIMENSIONS と合わせる（HNSW インデックスのため固定）
+
+CREATE TABLE wantedly_company_embeddings (
+    company_id      BIGINT PRIMARY KEY REFERENCES wantedly_companies(id) ON DELETE CASCADE,
+    model           TEXT NOT NULL,           -- 埋め込みモデル（変わったら作り直す）
+    content_sha256  TEXT NOT NULL,           -- 埋め込んだ文書の SHA-256（変わったら作り直す）
+    embedding       vector(1536) NOT NULL,
+    embedded_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 自己紹介の変更検知に使うハッシュ
+-- 平文の sha256 だと、候補の文を持っている人がその自己紹介の有無を確かめられる（本文は暗号化しているのに）。
+-- PII の鍵があるセッションでは HMAC、鍵がない（平文で保存している）ときだけ sha256 にする。
+-- 鍵が変わると値も変わるので、rotate-pii-key の後は backfill-embeddings で作り直す。
+CREATE FUNCTION pii_digest(value TEXT) RETURNS TEXT
+LANGUAGE sql STABLE STRICT AS $$
+    SELECT CASE
+        WHEN pii_key() IS NULL THEN encode(sha256(convert_to(value, 'UTF8')), 'hex')
+        ELSE 'hmac:' || encode(hmac(value, 'content-digest:' || pii_key(), 'sha256'), 'hex')
+    END
+$$;
+
+-- 本文は所属履歴（暗号化されうる）にだけ置き、ここには持たない
+CREATE TABLE wantedly_viewer_description_embeddings (
+    id              BIGSERIAL PRIMARY KEY,
+    viewer_id       BIGINT NOT NULL REFERENCES wantedly_viewers(id) ON DELETE CASCADE,
+    content_digest  TEXT NOT NULL,           -- pii_digest(自己紹介)
+    model           TEXT NOT NULL,
+    embedding       vector(1536) NOT NULL,
+    embedded_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (viewer_id, content_digest)
+);
@@ -0,0 +1,3 @@
+-- 類似企業の検索（コサイン距離の近い順）用
+CREATE INDEX wantedly_company_embeddings_hnsw_idx
+    ON wantedly_company_embeddings USING hnsw (embedding vector_cosine_ops);
@@ -0,0 +1,27 @@
+-- RAG の検索対象にする、手元の文書（受け取ったスカウト・自分のメモ）
+CREATE TYPE insight_document_kind AS ENUM ('scout_message', 'note');
+
+CREATE TABLE insight_documents (
+    id           BIGSERIAL PRIMARY KEY,
+    kind         insight_document_kind NOT NULL,
+    title        TEXT NOT NULL,
+    body         TEXT NOT NULL,
+    company_id   BIGINT REFERENCES wantedly_companies(id) ON DELETE CASCADE, -- スカウトの送り主・メモの対象の会社
+    occurred_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),                       -- 受け取った日時・書いた日時
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE insight_document_embeddings (
+    document_id     BIGINT PRIMARY KEY REFERENCES insight_documents(id) ON DELETE CASCADE,
+    model           TEXT NOT NULL,
+    content_sha256  TEXT NOT NULL,
+    embedding       vector(1536) NOT NULL,
+    embedded_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- RAG の検索（コサイン距離の近い順）用
+CREATE INDEX insight_document_embeddings_hnsw_idx
+    ON insight_document_embeddings USING hnsw (embedding vector_cosine_ops);
+
+CREATE INDEX wantedly_viewer_description_embeddings_hnsw_idx
+    ON wantedly_viewer_description_embeddings USING hnsw (embedding vector_cosine_ops);
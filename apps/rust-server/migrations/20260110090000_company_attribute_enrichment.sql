@@ -0,0 +1,15 @@
+-- AI による会社属性の推定を、どのプロンプト・モデルで作ったか追えるようにする（手入力の行は NULL）
+ALTER TABLE wantedly_company_attributes
+    ADD COLUMN prompt_version  TEXT,
+    ADD COLUMN model           TEXT;
+
+-- 応答を却下した会社（次の推定まで間を空ける。書き込めたら消す）
+CREATE TABLE wantedly_company_enrichment_failures (
+    company_id         BIGINT PRIMARY KEY REFERENCES wantedly_companies(id),
+    prompt_version     TEXT NOT NULL,
+    model              TEXT NOT NULL,
+    failures           INTEGER NOT NULL,
+    last_error         TEXT NOT NULL,
+    last_attempted_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    next_attempt_at    TIMESTAMPTZ NOT NULL
+);
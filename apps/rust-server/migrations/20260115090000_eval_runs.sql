@@ -0,0 +1,18 @@
+-- プロンプト・モデルのオフライン評価の結果（プロンプトの版ごとに比べて退行を見つける）
+CREATE TYPE eval_suite AS ENUM ('rag', 'enrichment');
+
+CREATE TABLE eval_runs (
+    id                BIGSERIAL PRIMARY KEY,
+    suite             eval_suite NOT NULL,
+    prompt_version    TEXT NOT NULL,
+    model             TEXT NOT NULL,                -- LLM のモデル
+    embedding_model   TEXT,                         -- rag のみ
+    cases_sha256      TEXT NOT NULL,                -- 評価ケースの版（同じケース同士でだけ比べる）
+    case_count        INTEGER NOT NULL,
+    retrieval_recall  DOUBLE PRECISION,             -- rag のみ。期待した資料を引けた割合の平均
+    score             DOUBLE PRECISION,             -- rag: 回答の正しさ / enrichment: 項目の一致率（ケースごとの平均）
+    results           JSONB NOT NULL,               -- ケースごとの結果
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX eval_runs_suite_cases_idx ON eval_runs (suite, cases_sha256, id DESC);
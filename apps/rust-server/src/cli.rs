@@ -0,0 +1,534 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use storage::wantedly::WantedlyExportFilter;
+
+use crate::infra::analytics::period::{JstPeriod, PeriodError};
+use crate::infra::erasure::ErasureSubject;
+use crate::infra::export::{
+    ExportDataset, ExportFormat,
+    anonymize::{AnonymizeOptions, CompanyMode, DescriptionMode},
+};
+use crate::infra::report::ReportFormat;
+use crate::infra::usecase::analyze_repeat_viewers::{
+    RepeatViewerOptions, RepeatViewerOptionsError,
+};
+use crate::infra::usecase::backfill_embeddings::DEFAULT_BATCH_SIZE;
+use crate::infra::usecase::build_weekly_report::resolve_report_period;
+use crate::infra::usecase::compact_wantedly_raw_json::RawJsonRetentionPolicy;
+use crate::infra::usecase::detect_view_anomalies::{ViewAnomalyOptions, ViewAnomalyOptionsError};
+use crate::infra::usecase::enrich_company_attributes::DEFAULT_ENRICHMENT_LIMIT;
+
+/// 自分のプロフィールのスナップショットの置き場所（serve 時にも取り込む）
+pub const OWN_PROFILE_DIR: &str = "local_data/profile_sources/wantedly/own_profile";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "rust-server",
+    about = "profile-insights server and maintenance commands"
+)]
+pub struct Cli {
+    /// 省略時は serve
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// ローカルのスナップショットを取り込み、HTTP サーバを起動する
+    Serve,
+
+    /// raw / 正規化済みデータをファイルに書き出す
+    Export(ExportArgs),
+
+    /// 全テーブルと取り込み台帳をアーカイブ（tar.gz）に書き出す
+    Backup(BackupArgs),
+
+    /// backup で作ったアーカイブを、マイグレーション直後の空の DB に書き戻す
+    Restore(RestoreArgs),
+
+    /// 個人情報カラムを PII_ENCRYPTION_KEY で暗号化し直す（旧鍵は PII_ENCRYPTION_KEY_PREVIOUS）
+    RotatePiiKey,
+
+    /// 閲覧者または会社に関わるデータを削除し、以後の取り込みからも除外する
+    Purge(PurgeArgs),
+
+    /// 保持期間を過ぎた raw_json を、正規化に必要な項目だけに削る
+    CompactRawJson(CompactRawJsonArgs),
+
+    /// 繰り返し閲覧した閲覧者・会社と、初回閲覧週ごとのコホートを JSON で表示する
+    RepeatViewers(RepeatViewersArgs),
+
+    /// 保存した自分のプロフィール（JSON / HTML）を版として取り込む
+    ImportOwnProfile(ImportOwnProfileArgs),
+
+    /// 日次の閲覧数の急増・急減を検出して記録し、JSON で表示する
+    DetectAnomalies(DetectAnomaliesArgs),
+
+    /// 期間の閲覧状況を前の期間と比べたレポートを Markdown / HTML で書き出す
+    Report(ReportArgs),
+
+    /// 閲覧のスナップショットを 1 件取り込んで正規化する（起動中のサーバのライブフィードに流れる）
+    ImportSnapshot(ImportSnapshotArgs),
+
+    /// 埋め込みがない、または古い会社・閲覧者の自己紹介・スカウトとメモを埋め込む（EMBEDDING_PROVIDER）
+    BackfillEmbeddings(BackfillEmbeddingsArgs),
+
+    /// 資料を引用して質問に答える（EMBEDDING_PROVIDER / LLM_PROVIDER）
+    Ask(AskArgs),
+
+    /// 属性のない会社の会社名・ドメイン・業種を LLM で推定する（LLM_PROVIDER）
+    EnrichCompanies(EnrichCompaniesArgs),
+
+    /// RAG と会社属性推定のプロンプトを評価ケースで採点し、eval_runs に残す
+    Eval(EvalArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// 書き出すデータセット（複数指定可、省略時はすべて）
+    #[arg(long = "dataset", value_enum)]
+    pub datasets: Vec<ExportDataset>,
+
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+
+    #[arg(long, default_value = "local_data/exports")]
+    pub out_dir: PathBuf,
+
+    /// JST の暦日（この日を含む）
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+
+    /// JST の暦日（この日を含む）
+    #[arg(long)]
+    pub to: Option<NaiveDate>,
+
+    /// 会社 slug で絞り込む
+    #[arg(long)]
+    pub company: Option<String>,
+
+    /// 閲覧者 ID などを仮名化して書き出す（EXPORT_ANONYMIZE_KEY が必要）
+    #[arg(long)]
+    pub anonymize: bool,
+
+    /// 匿名化時の shortDescription の扱い
+    #[arg(long, value_enum, default_value_t = DescriptionMode::Strip)]
+    pub description_mode: DescriptionMode,
+
+    /// 匿名化時の会社 slug / URL / 会社名の扱い（bucket は業種・従業員規模の区分に置き換える）
+    #[arg(long, value_enum, default_value_t = CompanyMode::Hash)]
+    pub company_mode: CompanyMode,
+}
+
+#[derive(Debug, Args)]
+pub struct BackupArgs {
+    /// 省略時は local_data/backups/insights-<UTC 日時>.tar.gz
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+impl BackupArgs {
+    pub fn output(&self) -> PathBuf {
+        self.output.clone().unwrap_or_else(|| {
+            PathBuf::from(format!(
+                "local_data/backups/insights-{}.tar.gz",
+                chrono::Utc::now().format("%Y%m%d%H%M%S")
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct RestoreArgs {
+    #[arg(long)]
+    pub input: PathBuf,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("subject").required(true).args(["viewer", "company"])))]
+pub struct PurgeArgs {
+    /// 閲覧者の userId
+    #[arg(long)]
+    pub viewer: Option<String>,
+
+    /// 会社 slug
+    #[arg(long)]
+    pub company: Option<String>,
+}
+
+impl PurgeArgs {
+    pub fn subject(&self) -> Result<ErasureSubject, &'static str> {
+        self.viewer
+            .as_deref()
+            .map(ErasureSubject::viewer)
+            .or_else(|| self.company.as_deref().map(ErasureSubject::company))
+            .flatten()
+            .ok_or("purge target must not be blank")
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct CompactRawJsonArgs {
+    /// 閲覧日時からこの日数を過ぎた行を削る（省略時は RAW_JSON_RETENTION_DAYS）
+    #[arg(long)]
+    pub older_than_days: Option<u32>,
+
+    /// 対象件数と削減量の見積もりだけを表示する
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// 削った後に VACUUM する
+    #[arg(long)]
+    pub vacuum: bool,
+}
+
+impl CompactRawJsonArgs {
+    pub fn policy(
+        &self,
+        default_days: Option<u32>,
+    ) -> Result<RawJsonRetentionPolicy, &'static str> {
+        let older_than_days = self
+            .older_than_days
+            .or(default_days)
+            .ok_or("specify --older-than-days or RAW_JSON_RETENTION_DAYS")?;
+
+        Ok(RawJsonRetentionPolicy { older_than_days })
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct RepeatViewersArgs {
+    /// JST の暦日（省略時は to の 365 日前から）
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+
+    /// JST の暦日（省略時は今日）
+    #[arg(long)]
+    pub to: Option<NaiveDate>,
+
+    /// 何日以上閲覧したら繰り返しとみなすか（既定 2）
+    #[arg(long)]
+    pub min_view_days: Option<i64>,
+
+    /// 閲覧者・会社の一覧の最大件数（既定 100）
+    #[arg(long)]
+    pub limit: Option<i64>,
+}
+
+impl RepeatViewersArgs {
+    pub fn options(
+        &self,
+        today: NaiveDate,
+    ) -> Result<RepeatViewerOptions, RepeatViewerOptionsError> {
+        RepeatViewerOptions::resolve(self.from, self.to, self.min_view_days, self.limit, today)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct DetectAnomaliesArgs {
+    /// 判定する最初の日（JST、省略時は to の 90 日前から）
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+
+    /// 判定する最後の日（JST、省略時は今日）
+    #[arg(long)]
+    pub to: Option<NaiveDate>,
+
+    /// 基準にする直前の日数（既定 28）
+    #[arg(long)]
+    pub window_days: Option<u32>,
+
+    /// 異常とみなす |score| の下限（既定 3.5）
+    #[arg(long)]
+    pub threshold: Option<f64>,
+}
+
+impl DetectAnomaliesArgs {
+    pub fn options(&self, today: NaiveDate) -> Result<ViewAnomalyOptions, ViewAnomalyOptionsError> {
+        ViewAnomalyOptions::resolve(self.from, self.to, self.window_days, self.threshold, today)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// JST の暦日（省略時は to の 6 日前から）
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+
+    /// JST の暦日（省略時は今日）
+    #[arg(long)]
+    pub to: Option<NaiveDate>,
+
+    #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+    pub format: ReportFormat,
+
+    /// 省略時は標準出力
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+impl ReportArgs {
+    pub fn period(&self, today: NaiveDate) -> Result<JstPeriod, PeriodError> {
+        resolve_report_period(self.from, self.to, today)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ImportSnapshotArgs {
+    /// <JST の YYYYMMDDhhmmss>.json という名前のスナップショット
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct BackfillEmbeddingsArgs {
+    /// 1 回の埋め込みリクエストに入れる文書数
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE)]
+    pub batch_size: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct AskArgs {
+    pub question: String,
+
+    /// 資料にする件数
+    #[arg(long, default_value_t = 8)]
+    pub top_k: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct EnrichCompaniesArgs {
+    /// 1 回に推定する会社数
+    #[arg(long, default_value_t = DEFAULT_ENRICHMENT_LIMIT)]
+    pub limit: i64,
+
+    /// 別のプロンプトの版・モデルで推定した会社も推定し直す
+    #[arg(long)]
+    pub refresh_stale: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct EvalArgs {
+    /// 評価ケース（JSON）
+    #[arg(long, default_value = "local_data/eval/cases.json")]
+    pub cases: PathBuf,
+
+    /// LLM の応答の記録（JSON）
+    #[arg(long, default_value = "local_data/eval/recordings.json")]
+    pub recordings: PathBuf,
+
+    /// LLM_PROVIDER の LLM に問い合わせて応答を記録する（省略時は記録を再生する）
+    #[arg(long)]
+    pub record: bool,
+
+    /// RAG の資料にする件数
+    #[arg(long, default_value_t = 8)]
+    pub top_k: usize,
+
+    /// 前回より下がったスコアがあれば失敗で終わる
+    #[arg(long)]
+    pub fail_on_regression: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportOwnProfileArgs {
+    /// <JST の YYYYMMDDhhmmss>.json / .html を置いたディレクトリ
+    #[arg(long, default_value = OWN_PROFILE_DIR)]
+    pub dir: PathBuf,
+}
+
+impl ExportArgs {
+    pub fn datasets(&self) -> Vec<ExportDataset> {
+        if self.datasets.is_empty() {
+            ExportDataset::ALL.to_vec()
+        } else {
+            self.datasets.clone()
+        }
+    }
+
+    pub fn anonymize_options(
+        &self,
+        key: Option<Vec<u8>>,
+    ) -> Result<Option<AnonymizeOptions>, &'static str> {
+        if !self.anonymize {
+            return Ok(None);
+        }
+        let key = key.ok_or("--anonymize requires EXPORT_ANONYMIZE_KEY")?;
+
+        Ok(Some(AnonymizeOptions {
+            key,
+            description: self.description_mode,
+            company: self.company_mode,
+        }))
+    }
+
+    pub fn filter(&self) -> WantedlyExportFilter {
+        WantedlyExportFilter {
+            from: self.from,
+            to: self.to,
+            company_slug: self.company.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_subcommand_means_serve() {
+        let cli = Cli::try_parse_from(["rust-server"]).unwrap();
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn export_defaults_to_all_datasets_as_csv() {
+        let cli = Cli::try_parse_from(["rust-server", "export"]).unwrap();
+        let Some(Command::Export(args)) = cli.command else {
+            panic!("expected export subcommand");
+        };
+
+        assert_eq!(args.datasets(), ExportDataset::ALL.to_vec());
+        assert_eq!(args.format, ExportFormat::Csv);
+    }
+
+    #[test]
+    fn export_parses_filters() {
+        let cli = Cli::try_parse_from([
+            "rust-server",
+            "export",
+            "--dataset",
+            "impressions",
+            "--dataset",
+            "daily-views",
+            "--format",
+            "parquet",
+            "--from",
+            "2025-11-01",
+            "--company",
+            "company_xyz",
+        ])
+        .unwrap();
+        let Some(Command::Export(args)) = cli.command else {
+            panic!("expected export subcommand");
+        };
+
+        assert_eq!(
+            args.datasets(),
+            vec![ExportDataset::Impressions, ExportDataset::DailyViews]
+        );
+        assert_eq!(args.format, ExportFormat::Parquet);
+        assert_eq!(args.filter().from, NaiveDate::from_ymd_opt(2025, 11, 1));
+        assert_eq!(args.filter().company_slug.as_deref(), Some("company_xyz"));
+    }
+
+    #[test]
+    fn restore_requires_input() {
+        assert!(Cli::try_parse_from(["rust-server", "restore"]).is_err());
+
+        let cli =
+            Cli::try_parse_from(["rust-server", "restore", "--input", "backup.tar.gz"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Restore(_))));
+    }
+
+    #[test]
+    fn anonymize_requires_key() {
+        let cli = Cli::try_parse_from([
+            "rust-server",
+            "export",
+            "--anonymize",
+            "--description-mode",
+            "generalize",
+        ])
+        .unwrap();
+        let Some(Command::Export(args)) = cli.command else {
+            panic!("expected export subcommand");
+        };
+
+        assert!(args.anonymize_options(None).is_err());
+
+        let options = args
+            .anonymize_options(Some(b"k".to_vec()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.description, DescriptionMode::Generalize);
+        assert_eq!(options.company, CompanyMode::Hash);
+    }
+
+    #[test]
+    fn rotate_pii_key_takes_no_arguments() {
+        let cli = Cli::try_parse_from(["rust-server", "rotate-pii-key"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::RotatePiiKey)));
+    }
+
+    #[test]
+    fn compact_raw_json_falls_back_to_env_days() {
+        let cli = Cli::try_parse_from(["rust-server", "compact-raw-json", "--dry-run"]).unwrap();
+        let Some(Command::CompactRawJson(args)) = cli.command else {
+            panic!("expected compact-raw-json subcommand");
+        };
+
+        assert!(args.dry_run);
+        assert!(args.policy(None).is_err());
+        assert_eq!(args.policy(Some(90)).unwrap().older_than_days, 90);
+    }
+
+    #[test]
+    fn repeat_viewers_parses_period() {
+        let cli = Cli::try_parse_from([
+            "rust-server",
+            "repeat-viewers",
+            "--from",
+            "2025-09-01",
+            "--to",
+            "2025-11-30",
+            "--min-view-days",
+            "3",
+        ])
+        .unwrap();
+        let Some(Command::RepeatViewers(args)) = cli.command else {
+            panic!("expected repeat-viewers subcommand");
+        };
+
+        let options = args
+            .options(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap())
+            .unwrap();
+        assert_eq!(options.period.days(), 91);
+        assert_eq!(options.min_view_days, 3);
+    }
+
+    #[test]
+    fn purge_requires_exactly_one_subject() {
+        assert!(Cli::try_parse_from(["rust-server", "purge"]).is_err());
+        assert!(
+            Cli::try_parse_from(["rust-server", "purge", "--viewer", "1", "--company", "c"])
+                .is_err()
+        );
+
+        let cli =
+            Cli::try_parse_from(["rust-server", "purge", "--company", "company_xyz"]).unwrap();
+        let Some(Command::Purge(args)) = cli.command else {
+            panic!("expected purge subcommand");
+        };
+        assert_eq!(
+            args.subject().unwrap(),
+            ErasureSubject::Company("company_xyz".into())
+        );
+    }
+
+    #[test]
+    fn report_defaults_to_markdown_for_the_last_week() {
+        let cli = Cli::try_parse_from(["rust-server", "report", "--format", "html"]).unwrap();
+        let Some(Command::Report(args)) = cli.command else {
+            panic!("expected report subcommand");
+        };
+
+        assert_eq!(args.format, ReportFormat::Html);
+        assert!(args.output.is_none());
+        let period = args
+            .period(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap())
+            .unwrap();
+        assert_eq!(period.days(), 7);
+    }
+}
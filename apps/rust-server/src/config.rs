@@ -1,4 +1,15 @@
-use std::env;
+use std::{env, time::Duration};
+
+use crate::infra::{
+    embedding::{EmbedderConfig, EmbeddingProvider},
+    erasure::{ErasureKey, ErasureKeys},
+    llm::{LlmConfig, LlmProvider},
+};
+
+const DEFAULT_EMBEDDING_API_BASE: &str = "https://api.openai.com/v1";
+const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+const DEFAULT_LLM_API_BASE: &str = "https://api.openai.com/v1";
+const DEFAULT_LLM_MODEL: &str = "gpt-4o-mini";
 
 pub fn build_database_url_from_env() -> Result<String, env::VarError> {
     dotenvy::dotenv().ok();
@@ -14,3 +25,178 @@ pub fn build_database_url_from_env() -> Result<String, env::VarError> {
         database_user, database_password, database_host, database_port, database_name
     ))
 }
+
+/// 匿名化エクスポートで閲覧者 ID などを仮名化する HMAC 鍵（EXPORT_ANONYMIZE_KEY）
+pub fn export_anonymize_key_from_env() -> Option<Vec<u8>> {
+    dotenvy::dotenv().ok();
+
+    env::var("EXPORT_ANONYMIZE_KEY")
+        .ok()
+        .filter(|k| !k.is_empty())
+        .map(String::into_bytes)
+}
+
+/// 個人情報カラムを暗号化する鍵（PII_ENCRYPTION_KEY）。未設定なら平文で保存する
+pub fn pii_encryption_key_from_env() -> Option<String> {
+    dotenvy::dotenv().ok();
+
+    env::var("PII_ENCRYPTION_KEY")
+        .ok()
+        .filter(|k| !k.is_empty())
+}
+
+/// 鍵ローテーション時の旧鍵（PII_ENCRYPTION_KEY_PREVIOUS）
+pub fn previous_pii_encryption_key_from_env() -> Option<String> {
+    dotenvy::dotenv().ok();
+
+    env::var("PII_ENCRYPTION_KEY_PREVIOUS")
+        .ok()
+        .filter(|k| !k.is_empty())
+}
+
+/// 削除要求の対象をハッシュにする HMAC 鍵（ERASURE_HASH_KEY）と、ローテーション前の鍵（ERASURE_HASH_KEY_PREVIOUS）
+pub fn erasure_keys_from_env() -> ErasureKeys {
+    dotenvy::dotenv().ok();
+
+    let key = |name: &str| {
+        env::var(name)
+            .ok()
+            .filter(|k| !k.is_empty())
+            .map(ErasureKey::new)
+    };
+    ErasureKeys {
+        current: key("ERASURE_HASH_KEY"),
+        previous: key("ERASURE_HASH_KEY_PREVIOUS"),
+    }
+}
+
+/// 管理用エンドポイント（/admin/*）の Bearer トークン（ADMIN_TOKEN）。未設定なら管理 API は無効
+pub fn admin_token_from_env() -> Option<String> {
+    dotenvy::dotenv().ok();
+
+    env::var("ADMIN_TOKEN").ok().filter(|k| !k.is_empty())
+}
+
+/// raw_json を削るまでの日数（RAW_JSON_RETENTION_DAYS）。未設定なら serve 時には削らない
+///
+/// 数として読めない値（`30d` など）はエラーにする（黙って無期限に残さないため）。
+pub fn raw_json_retention_days_from_env() -> Result<Option<u32>, String> {
+    dotenvy::dotenv().ok();
+
+    parse_retention_days(env::var("RAW_JSON_RETENTION_DAYS").ok().as_deref())
+}
+
+fn parse_retention_days(value: Option<&str>) -> Result<Option<u32>, String> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(v) => v.parse().map(Some).map_err(|_| {
+            format!("invalid RAW_JSON_RETENTION_DAYS: {v} (expected a number of days)")
+        }),
+    }
+}
+
+/// 埋め込みの設定
+///
+/// EMBEDDING_PROVIDER=local（既定）|openai、openai のときは EMBEDDING_API_BASE（既定 https://api.openai.com/v1）/
+/// EMBEDDING_API_KEY / EMBEDDING_MODEL（既定 text-embedding-3-small）/ EMBEDDING_TIMEOUT_SECS（既定 60）
+pub fn embedder_config_from_env() -> Result<EmbedderConfig, String> {
+    dotenvy::dotenv().ok();
+
+    let provider = match env::var("EMBEDDING_PROVIDER") {
+        Ok(v) if !v.trim().is_empty() => v.parse()?,
+        _ => EmbeddingProvider::default(),
+    };
+    let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+    Ok(EmbedderConfig {
+        provider,
+        api_base: var("EMBEDDING_API_BASE").unwrap_or_else(|| DEFAULT_EMBEDDING_API_BASE.into()),
+        api_key: var("EMBEDDING_API_KEY"),
+        model: var("EMBEDDING_MODEL").unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.into()),
+        timeout: parse_timeout_secs("EMBEDDING_TIMEOUT_SECS", var("EMBEDDING_TIMEOUT_SECS"))?,
+    })
+}
+
+/// 秒数の設定（未設定なら None、0 や数として読めない値はエラー）
+fn parse_timeout_secs(name: &str, value: Option<String>) -> Result<Option<Duration>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    match value.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Ok(Some(Duration::from_secs(secs))),
+        _ => Err(format!(
+            "invalid {name}: {value} (expected a positive number of seconds)"
+        )),
+    }
+}
+
+/// LLM の設定
+///
+/// LLM_PROVIDER=local（既定）|openai、openai のときは LLM_API_BASE（既定 https://api.openai.com/v1）/
+/// LLM_API_KEY / LLM_MODEL（既定 gpt-4o-mini）/ LLM_READ_TIMEOUT_SECS（既定 60）
+pub fn llm_config_from_env() -> Result<LlmConfig, String> {
+    dotenvy::dotenv().ok();
+
+    let provider = match env::var("LLM_PROVIDER") {
+        Ok(v) if !v.trim().is_empty() => v.parse()?,
+        _ => LlmProvider::default(),
+    };
+    let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+    Ok(LlmConfig {
+        provider,
+        api_base: var("LLM_API_BASE").unwrap_or_else(|| DEFAULT_LLM_API_BASE.into()),
+        api_key: var("LLM_API_KEY"),
+        model: var("LLM_MODEL").unwrap_or_else(|| DEFAULT_LLM_MODEL.into()),
+        read_timeout: parse_timeout_secs("LLM_READ_TIMEOUT_SECS", var("LLM_READ_TIMEOUT_SECS"))?,
+    })
+}
+
+/// DB 接続以外の実行時設定（未設定の機能は None）
+#[derive(Debug, Clone, Default)]
+pub struct AppConfig {
+    pub export_anonymize_key: Option<Vec<u8>>,
+    pub admin_token: Option<String>,
+    pub erasure_keys: ErasureKeys,
+    /// 自由文からの類似企業検索などで、問い合わせ文を埋め込む設定
+    pub embedder: EmbedderConfig,
+    /// RAG の回答を生成する LLM の設定
+    pub llm: LlmConfig,
+}
+
+impl AppConfig {
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Self {
+            export_anonymize_key: export_anonymize_key_from_env(),
+            admin_token: admin_token_from_env(),
+            erasure_keys: erasure_keys_from_env(),
+            embedder: embedder_config_from_env()?,
+            llm: llm_config_from_env()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retention_days_must_be_a_number_when_set() {
+        assert_eq!(parse_retention_days(None), Ok(None));
+        assert_eq!(parse_retention_days(Some(" ")), Ok(None));
+        assert_eq!(parse_retention_days(Some(" 30 ")), Ok(Some(30)));
+        assert!(parse_retention_days(Some("30d")).is_err());
+        assert!(parse_retention_days(Some("-1")).is_err());
+    }
+
+    #[test]
+    fn timeouts_must_be_positive_seconds() {
+        assert_eq!(parse_timeout_secs("T", None), Ok(None));
+        assert_eq!(
+            parse_timeout_secs("T", Some(" 30 ".into())),
+            Ok(Some(Duration::from_secs(30)))
+        );
+        assert!(parse_timeout_secs("T", Some("0".into())).is_err());
+        assert!(parse_timeout_secs("T", Some("30s".into())).is_err());
+    }
+}
@@ -5,15 +5,38 @@ use axum::{
 };
 use serde::Serialize;
 use std::fmt::{self, Display, Formatter};
+use storage::documents::InsightDocumentError;
+use storage::hypotheses::HypothesisError;
+use storage::wantedly::{
+    WantedlyBreakdownError, WantedlyCompanyError, WantedlyLeaderboardError, WantedlyLiveEventError,
+    WantedlyOwnProfileError, WantedlyRepeatViewError, WantedlySnapshotImportError,
+    WantedlyTimeSeriesError, WantedlyViewQueryError, WantedlyViewerAffiliationError,
+    WantedlyViewerError,
+};
+
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize)]
-struct ErrorBody {
+use crate::infra::{
+    export::ExportError,
+    usecase::{
+        analyze_profile_hypothesis::HypothesisAnalysisError, answer_question::RagError,
+        build_weekly_report::WeeklyReportError, detect_view_anomalies::ViewAnomalyError,
+        find_similar_companies::SimilarCompanyError, purge_erasure_subject::ErasurePurgeError,
+    },
+};
+
+/// エラー時の本文。ステータスに関わらず同じ形
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
     message: String,
 }
 
 #[derive(Debug)]
 pub enum AppError {
     BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
     Internal(String),
 }
 
@@ -23,6 +46,9 @@ impl Display for AppError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             AppError::BadRequest(m) => write!(f, "{}", m),
+            AppError::Unauthorized(m) => write!(f, "{}", m),
+            AppError::Forbidden(m) => write!(f, "{}", m),
+            AppError::NotFound(m) => write!(f, "{}", m),
             AppError::Internal(m) => write!(f, "{}", m),
         }
     }
@@ -33,6 +59,9 @@ impl IntoResponse for AppError {
         // ここだけ HTTP 用の処理
         let (status, message) = match self {
             AppError::BadRequest(m) => (StatusCode::BAD_REQUEST, m),
+            AppError::Unauthorized(m) => (StatusCode::UNAUTHORIZED, m),
+            AppError::Forbidden(m) => (StatusCode::FORBIDDEN, m),
+            AppError::NotFound(m) => (StatusCode::NOT_FOUND, m),
             AppError::Internal(m) => (StatusCode::INTERNAL_SERVER_ERROR, m),
         };
 
@@ -42,4 +71,137 @@ impl IntoResponse for AppError {
     }
 }
 
+impl From<WantedlyViewerError> for AppError {
+    fn from(e: WantedlyViewerError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyViewerAffiliationError> for AppError {
+    fn from(e: WantedlyViewerAffiliationError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyLeaderboardError> for AppError {
+    fn from(e: WantedlyLeaderboardError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyRepeatViewError> for AppError {
+    fn from(e: WantedlyRepeatViewError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyLiveEventError> for AppError {
+    fn from(e: WantedlyLiveEventError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyTimeSeriesError> for AppError {
+    fn from(e: WantedlyTimeSeriesError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyViewQueryError> for AppError {
+    fn from(e: WantedlyViewQueryError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlySnapshotImportError> for AppError {
+    fn from(e: WantedlySnapshotImportError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyOwnProfileError> for AppError {
+    fn from(e: WantedlyOwnProfileError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<ViewAnomalyError> for AppError {
+    fn from(e: ViewAnomalyError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyCompanyError> for AppError {
+    fn from(e: WantedlyCompanyError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WantedlyBreakdownError> for AppError {
+    fn from(e: WantedlyBreakdownError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<ExportError> for AppError {
+    fn from(e: ExportError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<ErasurePurgeError> for AppError {
+    fn from(e: ErasurePurgeError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<HypothesisError> for AppError {
+    fn from(e: HypothesisError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<HypothesisAnalysisError> for AppError {
+    fn from(e: HypothesisAnalysisError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<SimilarCompanyError> for AppError {
+    fn from(e: SimilarCompanyError) -> Self {
+        match e {
+            SimilarCompanyError::CompanyNotFound(_) | SimilarCompanyError::NotEmbedded { .. } => {
+                AppError::NotFound(e.to_string())
+            }
+            _ => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<InsightDocumentError> for AppError {
+    fn from(e: InsightDocumentError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<RagError> for AppError {
+    fn from(e: RagError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<WeeklyReportError> for AppError {
+    fn from(e: WeeklyReportError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
 pub type AppResult<T> = Result<T, AppError>;
+
+/// リクエストの文字列項目が空白だけでないことを確かめる（空なら 400）
+pub fn require_text(field: &str, value: &str) -> AppResult<()> {
+    if value.trim().is_empty() {
+        return Err(AppError::BadRequest(format!("`{field}` must not be empty")));
+    }
+    Ok(())
+}
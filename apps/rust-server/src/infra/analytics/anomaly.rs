@@ -0,0 +1,197 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use storage::wantedly::{
+    NewWantedlyViewAnomaly, WantedlyAnomalyKind, WantedlyViewAnomaly, WantedlyViewAnomalyCompany,
+    WantedlyViewBucket,
+};
+use utoipa::ToSchema;
+
+use crate::infra::analytics::period::JstPeriod;
+
+/// 正規分布なら MAD × 1.4826 が標準偏差の推定値になる
+const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// 直前の期間の中央値とばらつき
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Baseline {
+    pub median: f64,
+    pub scale: f64,
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// 外れ値に引きずられないよう、平均・標準偏差ではなく中央値と MAD を使う。
+/// 閲覧数は少ないことが多く MAD が 0 になりやすいので、ばらつきの下限をポアソン分布の
+/// 標準偏差（√中央値、最低 1）にする
+pub fn baseline(history: &[f64]) -> Option<Baseline> {
+    let median_value = median(&mut history.to_vec())?;
+    let mut deviations: Vec<f64> = history.iter().map(|v| (v - median_value).abs()).collect();
+    let mad = median(&mut deviations)?;
+
+    Some(Baseline {
+        median: median_value,
+        scale: (mad * MAD_TO_SIGMA).max(median_value.max(1.0).sqrt()),
+    })
+}
+
+/// daily は detect_from より window_days 日前から始まる、日ごとに連続した系列（0 埋め済み）。
+/// detect_from 以降の各日を、直前 window_days 日（半分以上そろっている場合のみ）と比べる
+pub fn detect_anomalies(
+    daily: &[WantedlyViewBucket],
+    detect_from: NaiveDate,
+    window_days: usize,
+    threshold: f64,
+) -> Vec<NewWantedlyViewAnomaly> {
+    let values: Vec<f64> = daily.iter().map(|b| b.views as f64).collect();
+    let min_history = window_days.div_ceil(2);
+
+    daily
+        .iter()
+        .enumerate()
+        .filter(|(_, bucket)| bucket.bucket_start >= detect_from)
+        .filter_map(|(index, bucket)| {
+            let history = &values[index.saturating_sub(window_days)..index];
+            if history.len() < min_history {
+                return None;
+            }
+            let Baseline { median, scale } = baseline(history)?;
+            let score = (bucket.views as f64 - median) / scale;
+
+            let kind = if score >= threshold {
+                WantedlyAnomalyKind::Spike
+            } else if score <= -threshold {
+                WantedlyAnomalyKind::Drop
+            } else {
+                return None;
+            };
+
+            Some(NewWantedlyViewAnomaly {
+                day: bucket.bucket_start,
+                kind,
+                views: bucket.views,
+                baseline_median: median,
+                baseline_scale: scale,
+                score,
+                window_days: window_days as i32,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ViewAnomaly {
+    #[serde(flatten)]
+    pub anomaly: WantedlyViewAnomaly,
+    pub top_companies: Vec<WantedlyViewAnomalyCompany>,
+}
+
+pub fn attach_top_companies(
+    anomalies: Vec<WantedlyViewAnomaly>,
+    companies: Vec<WantedlyViewAnomalyCompany>,
+) -> Vec<ViewAnomaly> {
+    anomalies
+        .into_iter()
+        .map(|anomaly| ViewAnomaly {
+            top_companies: companies
+                .iter()
+                .filter(|c| c.anomaly_id == anomaly.id)
+                .cloned()
+                .collect(),
+            anomaly,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewAnomalyReport {
+    pub period: JstPeriod,
+    pub window_days: u32,
+    pub threshold: f64,
+    pub anomalies: Vec<ViewAnomaly>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Days;
+
+    fn series(start: NaiveDate, views: &[i64]) -> Vec<WantedlyViewBucket> {
+        views
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let day = start + Days::new(i as u64);
+                WantedlyViewBucket {
+                    bucket_start: day,
+                    bucket_end: day,
+                    views: *v,
+                    distinct_viewers: *v,
+                    distinct_companies: *v,
+                }
+            })
+            .collect()
+    }
+
+    fn start() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 11, 1).unwrap()
+    }
+
+    #[test]
+    fn baseline_uses_median_and_mad() {
+        let b = baseline(&[10.0, 12.0, 11.0, 50.0, 9.0]).unwrap();
+
+        assert_eq!(b.median, 11.0);
+        // MAD = 1、下限 √11 の方が大きい
+        assert!((b.scale - 11f64.sqrt()).abs() < 1e-9);
+
+        let b = baseline(&[0.0, 0.0, 0.0]).unwrap();
+        assert_eq!(
+            b,
+            Baseline {
+                median: 0.0,
+                scale: 1.0
+            }
+        );
+    }
+
+    #[test]
+    fn flags_spikes_and_drops_against_trailing_window() {
+        let mut views = vec![20, 22, 19, 21, 20, 23, 18, 20, 21, 19];
+        views.extend([60, 20, 1]);
+        let daily = series(start(), &views);
+
+        let anomalies = detect_anomalies(&daily, start() + Days::new(10), 10, 3.5);
+
+        assert_eq!(anomalies.len(), 2);
+        assert_eq!(anomalies[0].kind, WantedlyAnomalyKind::Spike);
+        assert_eq!(anomalies[0].day, start() + Days::new(10));
+        assert_eq!(anomalies[0].baseline_median, 20.0);
+        assert_eq!(anomalies[1].kind, WantedlyAnomalyKind::Drop);
+        assert_eq!(anomalies[1].views, 1);
+    }
+
+    #[test]
+    fn small_counts_on_quiet_profiles_are_not_anomalies() {
+        let daily = series(start(), &[0, 0, 1, 0, 0, 0, 1, 0, 2, 3]);
+
+        assert!(detect_anomalies(&daily, start(), 6, 3.5).is_empty());
+    }
+
+    #[test]
+    fn days_without_enough_history_are_skipped() {
+        let daily = series(start(), &[1, 1, 100]);
+
+        assert!(detect_anomalies(&daily, start(), 10, 3.5).is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use storage::wantedly::{
+    WantedlyBreakdownBucketRow, WantedlyBreakdownTotalRow, WantedlyCompanyDimension,
+    WantedlyTimeBucket, WantedlyViewBucket,
+};
+use utoipa::ToSchema;
+
+use crate::infra::analytics::period::JstPeriod;
+
+/// 属性の値ごとの期間全体の集計。value が null は属性未設定（または会社不明）
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BreakdownValue {
+    pub value: Option<String>,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub distinct_companies: i64,
+    /// 期間の閲覧数に占める割合
+    pub view_share: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BreakdownBucketValue {
+    pub value: Option<String>,
+    pub views: i64,
+    pub distinct_viewers: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BreakdownBucket {
+    pub bucket_start: NaiveDate,
+    pub bucket_end: NaiveDate,
+    pub views: i64,
+    /// 閲覧のあった値だけ（閲覧数の多い順）
+    pub values: Vec<BreakdownBucketValue>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CompanyBreakdown {
+    pub period: JstPeriod,
+    pub dimension: WantedlyCompanyDimension,
+    pub granularity: WantedlyTimeBucket,
+    pub total_views: i64,
+    /// 属性が分かっている閲覧の割合
+    pub coverage: f64,
+    pub values: Vec<BreakdownValue>,
+    pub buckets: Vec<BreakdownBucket>,
+}
+
+fn ratio(part: i64, total: i64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// buckets は list_view_time_series の 0 埋め済みの区間。各区間に属性の値ごとの集計を入れる
+pub fn build_company_breakdown(
+    period: JstPeriod,
+    dimension: WantedlyCompanyDimension,
+    granularity: WantedlyTimeBucket,
+    buckets: Vec<WantedlyViewBucket>,
+    totals: Vec<WantedlyBreakdownTotalRow>,
+    rows: Vec<WantedlyBreakdownBucketRow>,
+) -> CompanyBreakdown {
+    let total_views: i64 = totals.iter().map(|t| t.views).sum();
+    let known_views: i64 = totals
+        .iter()
+        .filter(|t| t.value.is_some())
+        .map(|t| t.views)
+        .sum();
+
+    let values = totals
+        .into_iter()
+        .map(|t| BreakdownValue {
+            view_share: ratio(t.views, total_views),
+            value: t.value,
+            views: t.views,
+            distinct_viewers: t.distinct_viewers,
+            distinct_companies: t.distinct_companies,
+        })
+        .collect();
+
+    let buckets = buckets
+        .into_iter()
+        .map(|bucket| BreakdownBucket {
+            values: rows
+                .iter()
+                .filter(|r| r.bucket_start == bucket.bucket_start)
+                .map(|r| BreakdownBucketValue {
+                    value: r.value.clone(),
+                    views: r.views,
+                    distinct_viewers: r.distinct_viewers,
+                })
+                .collect(),
+            bucket_start: bucket.bucket_start,
+            bucket_end: bucket.bucket_end,
+            views: bucket.views,
+        })
+        .collect();
+
+    CompanyBreakdown {
+        period,
+        dimension,
+        granularity,
+        total_views,
+        coverage: ratio(known_views, total_views),
+        values,
+        buckets,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn total(value: Option<&str>, views: i64) -> WantedlyBreakdownTotalRow {
+        WantedlyBreakdownTotalRow {
+            value: value.map(String::from),
+            views,
+            distinct_viewers: views,
+            distinct_companies: 1,
+        }
+    }
+
+    fn bucket(start: NaiveDate, views: i64) -> WantedlyViewBucket {
+        WantedlyViewBucket {
+            bucket_start: start,
+            bucket_end: start,
+            views,
+            distinct_viewers: views,
+            distinct_companies: views,
+        }
+    }
+
+    #[test]
+    fn shares_and_coverage_include_unknown_values() {
+        let period = JstPeriod {
+            from: date(2025, 12, 1),
+            to: date(2025, 12, 2),
+        };
+        let breakdown = build_company_breakdown(
+            period,
+            WantedlyCompanyDimension::Industry,
+            WantedlyTimeBucket::Day,
+            vec![bucket(date(2025, 12, 1), 4), bucket(date(2025, 12, 2), 0)],
+            vec![total(Some("saas"), 3), total(None, 1)],
+            vec![WantedlyBreakdownBucketRow {
+                bucket_start: date(2025, 12, 1),
+                value: Some("saas".into()),
+                views: 3,
+                distinct_viewers: 2,
+            }],
+        );
+
+        assert_eq!(breakdown.total_views, 4);
+        assert_eq!(breakdown.coverage, 0.75);
+        assert_eq!(breakdown.values[0].view_share, 0.75);
+        assert_eq!(breakdown.buckets[0].values.len(), 1);
+        assert!(breakdown.buckets[1].values.is_empty());
+    }
+}
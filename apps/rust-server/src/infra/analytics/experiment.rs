@@ -0,0 +1,341 @@
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+use statrs::distribution::{ContinuousCDF, StudentsT};
+use storage::{
+    hypotheses::{
+        HypothesisExpectedEffect, HypothesisMetric, ProfileHypothesis, ProfileIntervention,
+    },
+    wantedly::WantedlyViewBucket,
+};
+use utoipa::ToSchema;
+
+use crate::infra::analytics::period::JstPeriod;
+
+/// 介入の前後で比べる日次の指標の集計
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct WindowSummary {
+    pub period: JstPeriod,
+    pub days: usize,
+    pub total: f64,
+    pub mean: f64,
+}
+
+/// Welch の t 検定（等分散を仮定しない）の結果。差は after − before
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct WelchTest {
+    pub mean_difference: f64,
+    pub t_statistic: f64,
+    pub degrees_of_freedom: f64,
+    /// 両側 p 値
+    pub p_value: f64,
+    pub confidence: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentVerdict {
+    /// 期待した方向に有意な差がある（no_change の場合は有意差がない）
+    Supported,
+    /// 期待と逆方向に有意な差がある（no_change の場合は有意差がある）
+    Contradicted,
+    Inconclusive,
+    /// 前後どちらかの日数が足りない、またはばらつきがなく検定できない
+    InsufficientData,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct InterventionAnalysis {
+    pub intervention: ProfileIntervention,
+    pub before: WindowSummary,
+    pub after: WindowSummary,
+    /// after の期間が今日（JST）で打ち切られていない
+    pub after_window_complete: bool,
+    pub test: Option<WelchTest>,
+    pub verdict: ExperimentVerdict,
+    /// 前後の期間中に行われた他の介入（効果が混ざっている可能性がある）
+    pub overlapping_intervention_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HypothesisAnalysis {
+    pub hypothesis: ProfileHypothesis,
+    pub window_days: u32,
+    pub confidence: f64,
+    pub interventions: Vec<InterventionAnalysis>,
+}
+
+/// 変更日の直前 window_days 日と、変更日からの window_days 日（today で打ち切り）
+pub fn intervention_windows(
+    changed_on: NaiveDate,
+    window_days: u32,
+    today: NaiveDate,
+) -> (JstPeriod, Option<JstPeriod>) {
+    let days = u64::from(window_days.max(1));
+    let before = JstPeriod {
+        from: changed_on - Days::new(days),
+        to: changed_on - Days::new(1),
+    };
+    let after_to = (changed_on + Days::new(days - 1)).min(today);
+    let after = (changed_on <= after_to).then_some(JstPeriod {
+        from: changed_on,
+        to: after_to,
+    });
+
+    (before, after)
+}
+
+pub fn metric_value(bucket: &WantedlyViewBucket, metric: HypothesisMetric) -> f64 {
+    let value = match metric {
+        HypothesisMetric::Views => bucket.views,
+        HypothesisMetric::DistinctViewers => bucket.distinct_viewers,
+        HypothesisMetric::DistinctCompanies => bucket.distinct_companies,
+    };
+    value as f64
+}
+
+pub fn summarize_window(period: JstPeriod, values: &[f64]) -> WindowSummary {
+    let total = values.iter().sum::<f64>();
+    WindowSummary {
+        period,
+        days: values.len(),
+        total,
+        mean: mean(values).unwrap_or(0.0),
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// 不偏分散
+fn sample_variance(values: &[f64]) -> Option<f64> {
+    let n = values.len();
+    if n < 2 {
+        return None;
+    }
+    let m = mean(values)?;
+    Some(values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (n - 1) as f64)
+}
+
+/// after − before の平均差についての Welch の t 検定と信頼区間。
+/// どちらかが 2 点未満、または両方とも分散 0 の場合は None
+pub fn welch_t_test(before: &[f64], after: &[f64], confidence: f64) -> Option<WelchTest> {
+    let (n1, n2) = (before.len() as f64, after.len() as f64);
+    let (m1, m2) = (mean(before)?, mean(after)?);
+    let (v1, v2) = (sample_variance(before)?, sample_variance(after)?);
+
+    let (a, b) = (v1 / n1, v2 / n2);
+    let se = (a + b).sqrt();
+    if se == 0.0 || !se.is_finite() {
+        return None;
+    }
+
+    // Welch–Satterthwaite の自由度
+    let df = (a + b).powi(2) / (a.powi(2) / (n1 - 1.0) + b.powi(2) / (n2 - 1.0));
+    let dist = StudentsT::new(0.0, 1.0, df).ok()?;
+
+    let diff = m2 - m1;
+    let t = diff / se;
+    let p_value = (2.0 * (1.0 - dist.cdf(t.abs()))).clamp(0.0, 1.0);
+    let half_width = dist.inverse_cdf(1.0 - (1.0 - confidence) / 2.0) * se;
+
+    Some(WelchTest {
+        mean_difference: diff,
+        t_statistic: t,
+        degrees_of_freedom: df,
+        p_value,
+        confidence,
+        ci_low: diff - half_width,
+        ci_high: diff + half_width,
+    })
+}
+
+/// 有意水準は 1 − confidence。no_change は「有意差がない」ことで支持とする（同等性の証明ではない）
+pub fn verdict(expected: HypothesisExpectedEffect, test: Option<&WelchTest>) -> ExperimentVerdict {
+    let Some(test) = test else {
+        return ExperimentVerdict::InsufficientData;
+    };
+    let significant = test.p_value < 1.0 - test.confidence;
+
+    match expected {
+        HypothesisExpectedEffect::NoChange if significant => ExperimentVerdict::Contradicted,
+        HypothesisExpectedEffect::NoChange => ExperimentVerdict::Supported,
+        _ if !significant => ExperimentVerdict::Inconclusive,
+        HypothesisExpectedEffect::Increase if test.mean_difference > 0.0 => {
+            ExperimentVerdict::Supported
+        }
+        HypothesisExpectedEffect::Decrease if test.mean_difference < 0.0 => {
+            ExperimentVerdict::Supported
+        }
+        _ => ExperimentVerdict::Contradicted,
+    }
+}
+
+/// 日次の系列（before〜after を覆う）から 1 つの介入の前後比較を組み立てる
+pub fn analyze_intervention(
+    hypothesis: &ProfileHypothesis,
+    intervention: ProfileIntervention,
+    all_interventions: &[ProfileIntervention],
+    daily: &[WantedlyViewBucket],
+    window_days: u32,
+    confidence: f64,
+    today: NaiveDate,
+) -> InterventionAnalysis {
+    let (before_period, after_period) =
+        intervention_windows(intervention.changed_on, window_days, today);
+
+    let values_in = |period: Option<JstPeriod>| -> Vec<f64> {
+        let Some(period) = period else {
+            return Vec::new();
+        };
+        daily
+            .iter()
+            .filter(|b| period.from <= b.bucket_start && b.bucket_start <= period.to)
+            .map(|b| metric_value(b, hypothesis.metric))
+            .collect()
+    };
+    let before_values = values_in(Some(before_period));
+    let after_values = values_in(after_period);
+
+    let test = welch_t_test(&before_values, &after_values, confidence);
+    let verdict = verdict(hypothesis.expected_effect, test.as_ref());
+
+    let window_end = after_period.map_or(before_period.to, |p| p.to);
+    let overlapping_intervention_ids = all_interventions
+        .iter()
+        .filter(|other| other.id != intervention.id)
+        .filter(|other| before_period.from < other.changed_on && other.changed_on <= window_end)
+        .map(|other| other.id)
+        .collect();
+
+    let after_period = after_period.unwrap_or(JstPeriod {
+        from: intervention.changed_on,
+        to: intervention.changed_on,
+    });
+    InterventionAnalysis {
+        before: summarize_window(before_period, &before_values),
+        after: summarize_window(after_period, &after_values),
+        after_window_complete: after_period.days() == i64::from(window_days),
+        test,
+        verdict,
+        overlapping_intervention_ids,
+        intervention,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn test_result(p_value: f64, mean_difference: f64) -> WelchTest {
+        WelchTest {
+            mean_difference,
+            t_statistic: 0.0,
+            degrees_of_freedom: 10.0,
+            p_value,
+            confidence: 0.95,
+            ci_low: 0.0,
+            ci_high: 0.0,
+        }
+    }
+
+    #[test]
+    fn welch_matches_pooled_t_for_equal_variances() {
+        // 等分散・同じ n では自由度が 2n − 2 になる。t(10) の 97.5% 点は 2.228
+        let before = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let after = [2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+
+        let test = welch_t_test(&before, &after, 0.95).unwrap();
+
+        assert!((test.degrees_of_freedom - 10.0).abs() < 1e-9);
+        assert!((test.mean_difference - 1.0).abs() < 1e-9);
+        assert!((test.t_statistic - 0.9258).abs() < 1e-4);
+        assert!((test.ci_high - test.mean_difference - 2.4066).abs() < 1e-3);
+        assert!(test.p_value > 0.05);
+    }
+
+    #[test]
+    fn welch_uses_satterthwaite_degrees_of_freedom() {
+        let before = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let after = [2.0, 4.0, 6.0, 8.0, 10.0];
+
+        let test = welch_t_test(&before, &after, 0.95).unwrap();
+
+        // se = sqrt(2.5/5 + 10/5)、df = 2.5² / (0.5²/4 + 2²/4)
+        assert!((test.t_statistic - 3.0 / 2.5f64.sqrt()).abs() < 1e-9);
+        assert!((test.degrees_of_freedom - 6.25 / 1.0625).abs() < 1e-9);
+        assert!(test.ci_low < 0.0 && test.ci_high > 0.0);
+    }
+
+    #[test]
+    fn clear_shift_is_significant() {
+        let before = [3.0, 4.0, 2.0, 3.0, 5.0, 4.0, 3.0];
+        let after = [9.0, 11.0, 10.0, 12.0, 9.0, 10.0, 11.0];
+
+        let test = welch_t_test(&before, &after, 0.95).unwrap();
+
+        assert!(test.p_value < 0.001);
+        assert!(test.ci_low > 0.0);
+        assert_eq!(
+            verdict(HypothesisExpectedEffect::Increase, Some(&test)),
+            ExperimentVerdict::Supported
+        );
+        assert_eq!(
+            verdict(HypothesisExpectedEffect::Decrease, Some(&test)),
+            ExperimentVerdict::Contradicted
+        );
+    }
+
+    #[test]
+    fn degenerate_samples_cannot_be_tested() {
+        assert!(welch_t_test(&[1.0], &[1.0, 2.0], 0.95).is_none());
+        assert!(welch_t_test(&[0.0, 0.0, 0.0], &[0.0, 0.0], 0.95).is_none());
+        assert_eq!(
+            verdict(HypothesisExpectedEffect::Increase, None),
+            ExperimentVerdict::InsufficientData
+        );
+    }
+
+    #[test]
+    fn no_change_is_supported_only_without_significance() {
+        assert_eq!(
+            verdict(
+                HypothesisExpectedEffect::NoChange,
+                Some(&test_result(0.4, 1.0))
+            ),
+            ExperimentVerdict::Supported
+        );
+        assert_eq!(
+            verdict(
+                HypothesisExpectedEffect::NoChange,
+                Some(&test_result(0.01, 1.0))
+            ),
+            ExperimentVerdict::Contradicted
+        );
+        assert_eq!(
+            verdict(
+                HypothesisExpectedEffect::Increase,
+                Some(&test_result(0.4, 1.0))
+            ),
+            ExperimentVerdict::Inconclusive
+        );
+    }
+
+    #[test]
+    fn after_window_is_cut_at_today() {
+        let (before, after) = intervention_windows(date(2025, 12, 10), 14, date(2025, 12, 15));
+
+        assert_eq!(before.from, date(2025, 11, 26));
+        assert_eq!(before.to, date(2025, 12, 9));
+        assert_eq!(after.unwrap().days(), 6);
+
+        let (_, after) = intervention_windows(date(2025, 12, 20), 14, date(2025, 12, 15));
+        assert!(after.is_none());
+    }
+}
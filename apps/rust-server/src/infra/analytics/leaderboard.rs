@@ -0,0 +1,76 @@
+use serde::Serialize;
+use storage::wantedly::WantedlyCompanyLeaderboardRow;
+use utoipa::ToSchema;
+
+use crate::infra::analytics::period::JstPeriod;
+
+/// 会社ランキングの 1 行（前期間比を付ける）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CompanyLeaderboardEntry {
+    #[serde(flatten)]
+    pub company: WantedlyCompanyLeaderboardRow,
+    pub views_delta: i64,
+    /// 前期間の閲覧が 0 なら None
+    pub views_change_ratio: Option<f64>,
+}
+
+impl From<WantedlyCompanyLeaderboardRow> for CompanyLeaderboardEntry {
+    fn from(company: WantedlyCompanyLeaderboardRow) -> Self {
+        let views_delta = company.views - company.previous_views;
+        let views_change_ratio = (company.previous_views > 0)
+            .then(|| views_delta as f64 / company.previous_views as f64);
+
+        Self {
+            company,
+            views_delta,
+            views_change_ratio,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CompanyLeaderboard {
+    pub period: JstPeriod,
+    pub previous_period: JstPeriod,
+    pub companies: Vec<CompanyLeaderboardEntry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn row(views: i64, previous_views: i64) -> WantedlyCompanyLeaderboardRow {
+        WantedlyCompanyLeaderboardRow {
+            company_id: 1,
+            company_slug: "company_xyz".into(),
+            company_page_url: "https://www.wantedly.com/companies/company_xyz".into(),
+            views,
+            distinct_viewers: 1,
+            first_viewed_at: Utc::now(),
+            last_viewed_at: Utc::now(),
+            previous_views,
+            previous_distinct_viewers: 0,
+            name: None,
+            domain: None,
+            attribute_source: None,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn trend_against_previous_period() {
+        let entry = CompanyLeaderboardEntry::from(row(6, 4));
+
+        assert_eq!(entry.views_delta, 2);
+        assert_eq!(entry.views_change_ratio, Some(0.5));
+    }
+
+    #[test]
+    fn new_company_has_no_change_ratio() {
+        let entry = CompanyLeaderboardEntry::from(row(3, 0));
+
+        assert_eq!(entry.views_delta, 3);
+        assert_eq!(entry.views_change_ratio, None);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod anomaly;
+pub mod breakdown;
+pub mod experiment;
+pub mod leaderboard;
+pub mod period;
+pub mod profile_versions;
+pub mod repeat;
+pub mod timeseries;
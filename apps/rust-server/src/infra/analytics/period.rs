@@ -0,0 +1,121 @@
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PeriodError {
+    #[error("`from` must not be after `to`")]
+    Reversed,
+
+    #[error("period must not exceed {0} days")]
+    TooLong(u32),
+}
+
+/// JST の暦日の期間（両端を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct JstPeriod {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl JstPeriod {
+    /// 省略時は to = today、from = to から default_days 日分さかのぼった日
+    pub fn resolve(
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        default_days: u32,
+        max_days: u32,
+        today: NaiveDate,
+    ) -> Result<Self, PeriodError> {
+        let to = to.unwrap_or(today);
+        let from = from.unwrap_or_else(|| to - Days::new(u64::from(default_days.max(1) - 1)));
+
+        if from > to {
+            return Err(PeriodError::Reversed);
+        }
+        let period = Self { from, to };
+        if period.days() > i64::from(max_days) {
+            return Err(PeriodError::TooLong(max_days));
+        }
+
+        Ok(period)
+    }
+
+    pub fn days(&self) -> i64 {
+        (self.to - self.from).num_days() + 1
+    }
+
+    /// 直前の同じ長さの期間
+    pub fn previous(&self) -> Self {
+        let days = self.days() as u64;
+        Self {
+            from: self.from - Days::new(days),
+            to: self.from - Days::new(1),
+        }
+    }
+}
+
+pub fn jst_today() -> NaiveDate {
+    let jst = FixedOffset::east_opt(9 * 3600).expect("valid offset");
+    Utc::now().with_timezone(&jst).date_naive()
+}
+
+/// 画面・レポート表示用の JST の日時（YYYY-MM-DD hh:mm）
+pub fn format_jst_minute(at: DateTime<Utc>) -> String {
+    let jst = FixedOffset::east_opt(9 * 3600).expect("valid offset");
+    at.with_timezone(&jst).format("%Y-%m-%d %H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn defaults_to_trailing_window_ending_today() {
+        let period = JstPeriod::resolve(None, None, 30, 366, date(2025, 12, 31)).unwrap();
+
+        assert_eq!(period.from, date(2025, 12, 2));
+        assert_eq!(period.days(), 30);
+    }
+
+    #[test]
+    fn previous_period_has_same_length() {
+        let period = JstPeriod {
+            from: date(2025, 11, 1),
+            to: date(2025, 11, 7),
+        };
+
+        assert_eq!(
+            period.previous(),
+            JstPeriod {
+                from: date(2025, 10, 25),
+                to: date(2025, 10, 31),
+            }
+        );
+    }
+
+    #[test]
+    fn reversed_or_too_long_periods_are_rejected() {
+        let today = date(2025, 12, 31);
+
+        assert_eq!(
+            JstPeriod::resolve(
+                Some(date(2025, 12, 2)),
+                Some(date(2025, 12, 1)),
+                30,
+                366,
+                today
+            ),
+            Err(PeriodError::Reversed)
+        );
+        assert_eq!(
+            JstPeriod::resolve(Some(date(2020, 1, 1)), None, 30, 366, today),
+            Err(PeriodError::TooLong(366))
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, Utc};
+use serde::Serialize;
+use storage::wantedly::{
+    WantedlyOwnProfileExperience, WantedlyOwnProfileVersion, WantedlyViewBucket,
+};
+use utoipa::ToSchema;
+
+use crate::infra::{analytics::period::JstPeriod, wantedly::own_profile::OwnProfileSnapshot};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TextChange {
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ExperienceChange {
+    pub before: WantedlyOwnProfileExperience,
+    pub after: WantedlyOwnProfileExperience,
+}
+
+/// 連続する 2 つのバージョンの差分
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct OwnProfileDiff {
+    pub headline: Option<TextChange>,
+    pub skills_added: Vec<String>,
+    pub skills_removed: Vec<String>,
+    pub experiences_added: Vec<WantedlyOwnProfileExperience>,
+    pub experiences_removed: Vec<WantedlyOwnProfileExperience>,
+    pub experiences_changed: Vec<ExperienceChange>,
+}
+
+/// 職歴は会社と開始時期で同じものとみなす（肩書きや説明の書き換えは changed）
+fn experience_key(experience: &WantedlyOwnProfileExperience) -> (&str, Option<&str>) {
+    (&experience.company, experience.started_on.as_deref())
+}
+
+pub fn diff_own_profile(before: &OwnProfileSnapshot, after: &OwnProfileSnapshot) -> OwnProfileDiff {
+    let headline = (before.headline != after.headline).then(|| TextChange {
+        before: before.headline.clone(),
+        after: after.headline.clone(),
+    });
+
+    let skills_added = after
+        .skills
+        .iter()
+        .filter(|s| !before.skills.contains(s))
+        .cloned()
+        .collect();
+    let skills_removed = before
+        .skills
+        .iter()
+        .filter(|s| !after.skills.contains(s))
+        .cloned()
+        .collect();
+
+    let find_in = |list: &[WantedlyOwnProfileExperience], target: &WantedlyOwnProfileExperience| {
+        list.iter()
+            .find(|e| experience_key(e) == experience_key(target))
+            .cloned()
+    };
+    let mut experiences_added = Vec::new();
+    let mut experiences_changed = Vec::new();
+    for experience in &after.experiences {
+        match find_in(&before.experiences, experience) {
+            None => experiences_added.push(experience.clone()),
+            Some(previous) if previous != *experience => {
+                experiences_changed.push(ExperienceChange {
+                    before: previous,
+                    after: experience.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    let experiences_removed = before
+        .experiences
+        .iter()
+        .filter(|e| find_in(&after.experiences, e).is_none())
+        .cloned()
+        .collect();
+
+    OwnProfileDiff {
+        headline,
+        skills_added,
+        skills_removed,
+        experiences_added,
+        experiences_removed,
+        experiences_changed,
+    }
+}
+
+/// 1 つのバージョンと、それが公開されていた期間の閲覧数
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OwnProfileVersionViews {
+    pub version_id: i64,
+    pub captured_at: DateTime<Utc>,
+    pub source_file: String,
+    #[serde(flatten)]
+    pub profile: OwnProfileSnapshot,
+    /// 直前のバージョンからの差分（最初のバージョンは null）
+    pub diff: Option<OwnProfileDiff>,
+    /// 保存日（JST）から次のバージョンの前日まで。同じ日に次の版があれば null
+    pub live_period: Option<JstPeriod>,
+    pub views: i64,
+    pub views_per_day: Option<f64>,
+    /// 直前のバージョンの views_per_day との差
+    pub views_per_day_change: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OwnProfileTimeline {
+    pub versions: Vec<OwnProfileVersionViews>,
+}
+
+pub fn jst_date(at: DateTime<Utc>) -> NaiveDate {
+    let jst = FixedOffset::east_opt(9 * 3600).expect("valid offset");
+    at.with_timezone(&jst).date_naive()
+}
+
+/// versions は captured_at の古い順、daily は最初のバージョンの日から today までの日次集計
+pub fn build_own_profile_timeline(
+    versions: &[WantedlyOwnProfileVersion],
+    daily: &[WantedlyViewBucket],
+    today: NaiveDate,
+) -> OwnProfileTimeline {
+    let mut entries: Vec<OwnProfileVersionViews> = Vec::with_capacity(versions.len());
+
+    for (index, version) in versions.iter().enumerate() {
+        let from = jst_date(version.captured_at);
+        let to = versions
+            .get(index + 1)
+            .map(|next| jst_date(next.captured_at) - Days::new(1))
+            .unwrap_or(today);
+        let live_period = (from <= to).then_some(JstPeriod { from, to });
+
+        let views = live_period.map_or(0, |period| {
+            daily
+                .iter()
+                .filter(|b| period.from <= b.bucket_start && b.bucket_start <= period.to)
+                .map(|b| b.views)
+                .sum()
+        });
+        let views_per_day = live_period.map(|period| views as f64 / period.days() as f64);
+
+        let profile = OwnProfileSnapshot::from(version);
+        let previous = entries.last();
+        let diff = previous.map(|p| diff_own_profile(&p.profile, &profile));
+        let views_per_day_change = previous
+            .and_then(|p| p.views_per_day)
+            .zip(views_per_day)
+            .map(|(before, after)| after - before);
+
+        entries.push(OwnProfileVersionViews {
+            version_id: version.id,
+            captured_at: version.captured_at,
+            source_file: version.source_file.clone(),
+            profile,
+            diff,
+            live_period,
+            views,
+            views_per_day,
+            views_per_day_change,
+        });
+    }
+
+    OwnProfileTimeline { versions: entries }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::types::Json;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn experience(company: &str, title: &str) -> WantedlyOwnProfileExperience {
+        WantedlyOwnProfileExperience {
+            company: company.into(),
+            title: Some(title.into()),
+            started_on: Some("2021-04".into()),
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(
+        headline: &str,
+        skills: &[&str],
+        experiences: Vec<WantedlyOwnProfileExperience>,
+    ) -> OwnProfileSnapshot {
+        OwnProfileSnapshot {
+            headline: Some(headline.into()),
+            skills: skills.iter().map(|s| s.to_string()).collect(),
+            experiences,
+        }
+    }
+
+    fn version(id: i64, captured_at: &str, headline: &str) -> WantedlyOwnProfileVersion {
+        WantedlyOwnProfileVersion {
+            id,
+            captured_at: captured_at.parse().unwrap(),
+            source_file: format!("{id}.json"),
+            source_format: "json".into(),
+            file_sha256: String::new(),
+            content_sha256: String::new(),
+            headline: Some(headline.into()),
+            skills: Json(Vec::new()),
+            experiences: Json(Vec::new()),
+            imported_at: captured_at.parse().unwrap(),
+        }
+    }
+
+    fn day(d: NaiveDate, views: i64) -> WantedlyViewBucket {
+        WantedlyViewBucket {
+            bucket_start: d,
+            bucket_end: d,
+            views,
+            distinct_viewers: views,
+            distinct_companies: views,
+        }
+    }
+
+    #[test]
+    fn diff_reports_headline_skills_and_experiences() {
+        let before = snapshot(
+            "Backend",
+            &["Rust", "Go"],
+            vec![experience("Acme", "Engineer"), experience("Old", "Intern")],
+        );
+        let after = snapshot(
+            "Data Engineer",
+            &["Rust", "SQL"],
+            vec![experience("Acme", "Lead"), experience("New", "CTO")],
+        );
+
+        let diff = diff_own_profile(&before, &after);
+
+        assert_eq!(
+            diff.headline.unwrap().after.as_deref(),
+            Some("Data Engineer")
+        );
+        assert_eq!(diff.skills_added, vec!["SQL"]);
+        assert_eq!(diff.skills_removed, vec!["Go"]);
+        assert_eq!(diff.experiences_added[0].company, "New");
+        assert_eq!(diff.experiences_removed[0].company, "Old");
+        assert_eq!(
+            diff.experiences_changed[0].after.title.as_deref(),
+            Some("Lead")
+        );
+    }
+
+    #[test]
+    fn identical_snapshots_have_empty_diff() {
+        let a = snapshot("A", &["Rust"], vec![experience("Acme", "Engineer")]);
+
+        assert_eq!(diff_own_profile(&a, &a.clone()), OwnProfileDiff::default());
+    }
+
+    #[test]
+    fn versions_cover_days_until_the_next_version() {
+        // 2025-12-01 09:00 JST と 2025-12-04 09:00 JST
+        let versions = vec![
+            version(1, "2025-12-01T00:00:00Z", "A"),
+            version(2, "2025-12-04T00:00:00Z", "B"),
+        ];
+        let daily: Vec<_> = (1..=5).map(|d| day(date(2025, 12, d), d as i64)).collect();
+
+        let timeline = build_own_profile_timeline(&versions, &daily, date(2025, 12, 5));
+        let [first, second] = timeline.versions.as_slice() else {
+            panic!("expected two versions");
+        };
+
+        assert_eq!(first.live_period.unwrap().to, date(2025, 12, 3));
+        assert_eq!(first.views, 1 + 2 + 3);
+        assert!(first.diff.is_none());
+        assert_eq!(second.views, 4 + 5);
+        assert_eq!(second.views_per_day, Some(4.5));
+        assert_eq!(second.views_per_day_change, Some(2.5));
+        assert!(second.diff.as_ref().unwrap().headline.is_some());
+    }
+
+    #[test]
+    fn same_day_versions_have_no_live_period() {
+        let versions = vec![
+            version(1, "2025-12-01T00:00:00Z", "A"),
+            version(2, "2025-12-01T05:00:00Z", "B"),
+        ];
+
+        let timeline = build_own_profile_timeline(&versions, &[], date(2025, 12, 2));
+
+        assert!(timeline.versions[0].live_period.is_none());
+        assert_eq!(timeline.versions[0].views_per_day, None);
+        assert_eq!(timeline.versions[1].live_period.unwrap().days(), 2);
+    }
+}
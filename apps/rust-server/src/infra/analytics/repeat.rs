@@ -0,0 +1,163 @@
+use serde::Serialize;
+use storage::wantedly::{
+    WantedlyCohortRetentionRow, WantedlyRepeatCompanyRow, WantedlyRepeatViewerRow,
+    WantedlyViewerCohortRow,
+};
+use utoipa::ToSchema;
+
+use crate::infra::analytics::period::JstPeriod;
+
+/// 初回閲覧週から week_offset 週目に閲覧のあった閲覧者数と、コホートに対する割合
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CohortRetention {
+    pub week_offset: i32,
+    pub viewers: i64,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ViewerCohort {
+    #[serde(flatten)]
+    pub cohort: WantedlyViewerCohortRow,
+    pub return_rate: f64,
+    pub retention: Vec<CohortRetention>,
+}
+
+/// 期間内に初めて閲覧した閲覧者のうち、別の日にも閲覧した割合
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RepeatSummary {
+    pub new_viewers: i64,
+    pub returned_viewers: i64,
+    pub return_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RepeatViewerReport {
+    pub period: JstPeriod,
+    pub summary: RepeatSummary,
+    pub viewers: Vec<WantedlyRepeatViewerRow>,
+    pub companies: Vec<WantedlyRepeatCompanyRow>,
+    pub cohorts: Vec<ViewerCohort>,
+}
+
+fn rate(part: i64, whole: i64) -> f64 {
+    if whole > 0 {
+        part as f64 / whole as f64
+    } else {
+        0.0
+    }
+}
+
+/// コホートの集計に週ごとの閲覧者数を付ける
+pub fn build_cohorts(
+    cohorts: Vec<WantedlyViewerCohortRow>,
+    retention: Vec<WantedlyCohortRetentionRow>,
+) -> Vec<ViewerCohort> {
+    cohorts
+        .into_iter()
+        .map(|cohort| {
+            let retention = retention
+                .iter()
+                .filter(|r| r.cohort_week == cohort.cohort_week)
+                .map(|r| CohortRetention {
+                    week_offset: r.week_offset,
+                    viewers: r.viewers,
+                    rate: rate(r.viewers, cohort.viewers),
+                })
+                .collect();
+
+            ViewerCohort {
+                return_rate: rate(cohort.returned_viewers, cohort.viewers),
+                cohort,
+                retention,
+            }
+        })
+        .collect()
+}
+
+pub fn summarize_cohorts(cohorts: &[ViewerCohort]) -> RepeatSummary {
+    let new_viewers = cohorts.iter().map(|c| c.cohort.viewers).sum();
+    let returned_viewers = cohorts.iter().map(|c| c.cohort.returned_viewers).sum();
+
+    RepeatSummary {
+        new_viewers,
+        returned_viewers,
+        return_rate: rate(returned_viewers, new_viewers),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn week(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 12, d).unwrap()
+    }
+
+    fn cohort(d: u32, viewers: i64, returned_viewers: i64) -> WantedlyViewerCohortRow {
+        WantedlyViewerCohortRow {
+            cohort_week: week(d),
+            viewers,
+            returned_viewers,
+            avg_days_to_return: None,
+            median_days_to_return: None,
+        }
+    }
+
+    #[test]
+    fn retention_is_attached_to_matching_cohort() {
+        let retention = vec![
+            WantedlyCohortRetentionRow {
+                cohort_week: week(1),
+                week_offset: 0,
+                viewers: 4,
+            },
+            WantedlyCohortRetentionRow {
+                cohort_week: week(1),
+                week_offset: 2,
+                viewers: 1,
+            },
+            WantedlyCohortRetentionRow {
+                cohort_week: week(8),
+                week_offset: 0,
+                viewers: 2,
+            },
+        ];
+
+        let cohorts = build_cohorts(vec![cohort(1, 4, 2), cohort(8, 2, 0)], retention);
+
+        assert_eq!(cohorts[0].return_rate, 0.5);
+        assert_eq!(
+            cohorts[0].retention,
+            vec![
+                CohortRetention {
+                    week_offset: 0,
+                    viewers: 4,
+                    rate: 1.0,
+                },
+                CohortRetention {
+                    week_offset: 2,
+                    viewers: 1,
+                    rate: 0.25,
+                },
+            ]
+        );
+        assert_eq!(cohorts[1].retention.len(), 1);
+    }
+
+    #[test]
+    fn summary_adds_up_cohorts() {
+        let cohorts = build_cohorts(vec![cohort(1, 4, 2), cohort(8, 6, 1)], vec![]);
+
+        assert_eq!(
+            summarize_cohorts(&cohorts),
+            RepeatSummary {
+                new_viewers: 10,
+                returned_viewers: 3,
+                return_rate: 0.3,
+            }
+        );
+        assert_eq!(summarize_cohorts(&[]).return_rate, 0.0);
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::Datelike;
+use serde::Serialize;
+use storage::wantedly::{WantedlyTimeBucket, WantedlyViewBucket, WantedlyWeekdayViews};
+use utoipa::ToSchema;
+
+use crate::infra::analytics::period::JstPeriod;
+
+const WEEKDAY_LABELS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// 曜日ごとの閲覧数と、期間内のその曜日 1 日あたりの平均
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct WeekdayViews {
+    pub iso_weekday: i32,
+    pub weekday: &'static str,
+    /// 期間内にその曜日が何日あるか
+    pub days: i64,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub average_views: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ViewTimeSeries {
+    pub period: JstPeriod,
+    pub granularity: WantedlyTimeBucket,
+    pub total_views: i64,
+    pub buckets: Vec<WantedlyViewBucket>,
+    pub weekdays: Vec<WeekdayViews>,
+}
+
+impl ViewTimeSeries {
+    pub fn new(
+        period: JstPeriod,
+        granularity: WantedlyTimeBucket,
+        buckets: Vec<WantedlyViewBucket>,
+        weekdays: Vec<WantedlyWeekdayViews>,
+    ) -> Self {
+        Self {
+            period,
+            granularity,
+            total_views: buckets.iter().map(|b| b.views).sum(),
+            buckets,
+            weekdays: weekday_breakdown(period, weekdays),
+        }
+    }
+}
+
+pub fn weekday_breakdown(period: JstPeriod, rows: Vec<WantedlyWeekdayViews>) -> Vec<WeekdayViews> {
+    let mut days = [0i64; 7];
+    for date in period.from.iter_days().take_while(|d| *d <= period.to) {
+        days[date.weekday().num_days_from_monday() as usize] += 1;
+    }
+
+    rows.into_iter()
+        .filter_map(|row| {
+            let index = usize::try_from(row.iso_weekday - 1)
+                .ok()
+                .filter(|i| *i < 7)?;
+            let days = days[index];
+            Some(WeekdayViews {
+                iso_weekday: row.iso_weekday,
+                weekday: WEEKDAY_LABELS[index],
+                days,
+                views: row.views,
+                distinct_viewers: row.distinct_viewers,
+                average_views: if days > 0 {
+                    row.views as f64 / days as f64
+                } else {
+                    0.0
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn weekday_average_uses_days_in_period() {
+        // 2025-12-01 は月曜。10 日間で月曜と火曜と水曜が 2 回ずつある
+        let period = JstPeriod {
+            from: NaiveDate::from_ymd_opt(2025, 12, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2025, 12, 10).unwrap(),
+        };
+        let rows = (1..=7)
+            .map(|d| WantedlyWeekdayViews {
+                iso_weekday: d,
+                views: 4,
+                distinct_viewers: 1,
+            })
+            .collect();
+
+        let breakdown = weekday_breakdown(period, rows);
+
+        assert_eq!(breakdown.len(), 7);
+        assert_eq!(breakdown[0].weekday, "mon");
+        assert_eq!(breakdown[0].days, 2);
+        assert_eq!(breakdown[0].average_views, 2.0);
+        assert_eq!(breakdown[6].weekday, "sun");
+        assert_eq!(breakdown[6].days, 1);
+        assert_eq!(breakdown[6].average_views, 4.0);
+    }
+}
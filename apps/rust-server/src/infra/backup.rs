@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use storage::backup::BackupStorageError;
+use thiserror::Error;
+
+use crate::infra::digest::sha256_hex;
+
+/// アーカイブ形式のバージョン（manifest.json / tables/*.jsonl の構造を変えたら上げる）
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "manifest.json";
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("failed to access archive: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid archive json: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("storage error: {0}")]
+    Storage(#[from] BackupStorageError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+
+    #[error("unsupported archive format version: {0}")]
+    UnsupportedFormat(u32),
+
+    #[error("archive has no manifest.json")]
+    MissingManifest,
+
+    #[error("archive is missing table file: {0}")]
+    MissingTable(String),
+
+    #[error("checksum mismatch for table: {0}")]
+    ChecksumMismatch(String),
+
+    #[error("row count mismatch for table {table}: expected {expected}, got {actual}")]
+    RowCountMismatch {
+        table: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("schema version mismatch: archive {archive:?}, database {database:?}")]
+    SchemaMismatch {
+        archive: Option<i64>,
+        database: Option<i64>,
+    },
+
+    #[error("restore target is not empty: {0}")]
+    NotEmpty(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    /// バックアップ元に適用済みだった最新の sqlx マイグレーション
+    pub schema_version: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub tables: Vec<BackupTableEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupTableEntry {
+    pub name: String,
+    pub file: String,
+    pub row_count: u64,
+    pub sha256: String,
+}
+
+/// テーブル 1 つ分の JSON Lines（1 行 = row_to_json の 1 レコード）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupTableData {
+    pub name: String,
+    pub jsonl: Vec<u8>,
+}
+
+impl BackupTableData {
+    pub fn from_rows(name: &str, rows: &[String]) -> Self {
+        let mut jsonl = Vec::new();
+        for row in rows {
+            jsonl.extend_from_slice(row.as_bytes());
+            jsonl.push(b'\n');
+        }
+        Self {
+            name: name.to_string(),
+            jsonl,
+        }
+    }
+
+    pub fn rows(&self) -> Result<Vec<serde_json::Value>, BackupError> {
+        self.jsonl
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).map_err(BackupError::from))
+            .collect()
+    }
+
+    fn entry(&self) -> BackupTableEntry {
+        BackupTableEntry {
+            name: self.name.clone(),
+            file: format!("tables/{}.jsonl", self.name),
+            row_count: self.jsonl.iter().filter(|b| **b == b'\n').count() as u64,
+            sha256: sha256_hex(&self.jsonl),
+        }
+    }
+}
+
+/// manifest.json と tables/<table>.jsonl を tar.gz に書き出す
+pub fn write_archive(
+    path: &Path,
+    schema_version: Option<i64>,
+    tables: &[BackupTableData],
+) -> Result<BackupManifest, BackupError> {
+    let manifest = BackupManifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        schema_version,
+        created_at: Utc::now(),
+        tables: tables.iter().map(BackupTableData::entry).collect(),
+    };
+
+    let encoder = GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+
+    append_file(
+        &mut tar,
+        MANIFEST_PATH,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    for (table, entry) in tables.iter().zip(&manifest.tables) {
+        append_file(&mut tar, &entry.file, &table.jsonl)?;
+    }
+
+    tar.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+fn append_file<W: std::io::Write>(
+    tar: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+) -> Result<(), BackupError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    tar.append_data(&mut header, path, bytes)?;
+    Ok(())
+}
+
+/// アーカイブを読み込み、形式バージョン・チェックサム・行数を検証する
+pub fn read_archive(path: &Path) -> Result<(BackupManifest, Vec<BackupTableData>), BackupError> {
+    let mut tar = tar::Archive::new(GzDecoder::new(BufReader::new(File::open(path)?)));
+
+    let mut files = BTreeMap::new();
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        files.insert(name, bytes);
+    }
+
+    let manifest: BackupManifest = serde_json::from_slice(
+        &files
+            .remove(MANIFEST_PATH)
+            .ok_or(BackupError::MissingManifest)?,
+    )?;
+    if manifest.format_version != ARCHIVE_FORMAT_VERSION {
+        return Err(BackupError::UnsupportedFormat(manifest.format_version));
+    }
+
+    let tables = manifest
+        .tables
+        .iter()
+        .map(|entry| {
+            let jsonl = files
+                .remove(&entry.file)
+                .ok_or_else(|| BackupError::MissingTable(entry.name.clone()))?;
+            let table = BackupTableData {
+                name: entry.name.clone(),
+                jsonl,
+            };
+
+            let actual = table.entry();
+            if actual.sha256 != entry.sha256 {
+                return Err(BackupError::ChecksumMismatch(entry.name.clone()));
+            }
+            if actual.row_count != entry.row_count {
+                return Err(BackupError::RowCountMismatch {
+                    table: entry.name.clone(),
+                    expected: entry.row_count,
+                    actual: actual.row_count,
+                });
+            }
+
+            Ok(table)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((manifest, tables))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tables() -> Vec<BackupTableData> {
+        vec![
+            BackupTableData::from_rows(
+                "wantedly_companies",
+                &[
+                    r#"{"id":1,"company_slug":"a"}"#.to_string(),
+                    r#"{"id":2,"company_slug":"b"}"#.to_string(),
+                ],
+            ),
+            BackupTableData::from_rows("wantedly_viewers", &[]),
+        ]
+    }
+
+    #[test]
+    fn archive_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.tar.gz");
+
+        let written = write_archive(&path, Some(20251205090000), &sample_tables()).unwrap();
+        let (manifest, tables) = read_archive(&path).unwrap();
+
+        assert_eq!(manifest, written);
+        assert_eq!(manifest.schema_version, Some(20251205090000));
+        assert_eq!(manifest.tables[0].row_count, 2);
+        assert_eq!(manifest.tables[1].row_count, 0);
+        assert_eq!(tables, sample_tables());
+        assert_eq!(tables[0].rows().unwrap()[1]["company_slug"], "b");
+    }
+
+    #[test]
+    fn tampered_table_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.tar.gz");
+
+        let mut manifest_tables = sample_tables();
+        write_archive(&path, None, &manifest_tables).unwrap();
+        let (mut manifest, _) = read_archive(&path).unwrap();
+
+        // manifest はそのままに中身だけ差し替えたアーカイブを作る
+        manifest_tables[0].jsonl = br#"{"id":1,"company_slug":"x"}"#.to_vec();
+        manifest_tables[0].jsonl.push(b'\n');
+        let encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+        manifest.tables.truncate(1);
+        append_file(
+            &mut tar,
+            MANIFEST_PATH,
+            &serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        append_file(
+            &mut tar,
+            &manifest.tables[0].file,
+            &manifest_tables[0].jsonl,
+        )
+        .unwrap();
+        tar.into_inner().unwrap().finish().unwrap();
+
+        assert!(matches!(
+            read_archive(&path),
+            Err(BackupError::ChecksumMismatch(table)) if table == "wantedly_companies"
+        ));
+    }
+
+    #[test]
+    fn unsupported_format_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.tar.gz");
+
+        let manifest = BackupManifest {
+            format_version: ARCHIVE_FORMAT_VERSION + 1,
+            schema_version: None,
+            created_at: Utc::now(),
+            tables: vec![],
+        };
+        let encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+        append_file(
+            &mut tar,
+            MANIFEST_PATH,
+            &serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        tar.into_inner().unwrap().finish().unwrap();
+
+        assert!(matches!(
+            read_archive(&path),
+            Err(BackupError::UnsupportedFormat(v)) if v == ARCHIVE_FORMAT_VERSION + 1
+        ));
+    }
+}
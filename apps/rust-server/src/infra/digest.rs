@@ -0,0 +1,33 @@
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// 鍵付きの HMAC-SHA256（鍵を知らなければ元の値を総当たりで確かめられない）
+pub fn hmac_sha256_hex(key: &[u8], bytes: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any length");
+    mac.update(bytes);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_of_empty_input() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn hmac_depends_on_key() {
+        assert_eq!(hmac_sha256_hex(b"k", b"v"), hmac_sha256_hex(b"k", b"v"));
+        assert_ne!(hmac_sha256_hex(b"k", b"v"), hmac_sha256_hex(b"other", b"v"));
+        assert_ne!(hmac_sha256_hex(b"k", b"v"), sha256_hex(b"v"));
+    }
+}
@@ -0,0 +1,121 @@
+use storage::documents::InsightDocumentKind;
+use storage::wantedly::{CompanyEmployeeBand, CompanyFundingStage, WantedlyCompanyEmbeddingSource};
+
+/// 会社の埋め込み文書（属性のある項目だけを「項目: 値」で並べる）
+pub fn company_document(source: &WantedlyCompanyEmbeddingSource) -> String {
+    let mut lines = vec![format!(
+        "会社: {}",
+        source.name.as_deref().unwrap_or(&source.company_slug)
+    )];
+    if source.name.is_some() {
+        lines.push(format!("slug: {}", source.company_slug));
+    }
+
+    let fields = [
+        ("ドメイン", source.domain.clone()),
+        ("業種", source.industry.clone()),
+        ("従業員数", source.employee_band.map(employee_band_label)),
+        ("資金調達", source.funding_stage.map(funding_stage_label)),
+        ("所在地", source.location.clone()),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+            lines.push(format!("{label}: {value}"));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// スカウト・メモの埋め込み文書（見出しと本文）
+pub fn insight_document(kind: InsightDocumentKind, title: &str, body: &str) -> String {
+    format!(
+        "{}: {}\n{}",
+        document_kind_label(kind),
+        title.trim(),
+        body.trim()
+    )
+}
+
+pub fn document_kind_label(kind: InsightDocumentKind) -> &'static str {
+    match kind {
+        InsightDocumentKind::ScoutMessage => "スカウト",
+        InsightDocumentKind::Note => "メモ",
+    }
+}
+
+fn employee_band_label(band: CompanyEmployeeBand) -> String {
+    let label = match band {
+        CompanyEmployeeBand::E1To10 => "1〜10 人",
+        CompanyEmployeeBand::E11To50 => "11〜50 人",
+        CompanyEmployeeBand::E51To200 => "51〜200 人",
+        CompanyEmployeeBand::E201To500 => "201〜500 人",
+        CompanyEmployeeBand::E501To1000 => "501〜1000 人",
+        CompanyEmployeeBand::E1001To5000 => "1001〜5000 人",
+        CompanyEmployeeBand::E5001Plus => "5001 人以上",
+    };
+    label.to_string()
+}
+
+fn funding_stage_label(stage: CompanyFundingStage) -> String {
+    let label = match stage {
+        CompanyFundingStage::Bootstrapped => "自己資金",
+        CompanyFundingStage::Seed => "シード",
+        CompanyFundingStage::SeriesA => "シリーズ A",
+        CompanyFundingStage::SeriesB => "シリーズ B",
+        CompanyFundingStage::SeriesCPlus => "シリーズ C 以降",
+        CompanyFundingStage::Public => "上場",
+        CompanyFundingStage::Acquired => "買収済み",
+    };
+    label.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(slug: &str) -> WantedlyCompanyEmbeddingSource {
+        WantedlyCompanyEmbeddingSource {
+            company_id: 1,
+            company_slug: slug.into(),
+            name: None,
+            domain: None,
+            industry: None,
+            employee_band: None,
+            funding_stage: None,
+            location: None,
+            embedded_model: None,
+            embedded_sha256: None,
+        }
+    }
+
+    #[test]
+    fn document_lists_only_known_attributes() {
+        assert_eq!(company_document(&source("co_a")), "会社: co_a");
+
+        let full = WantedlyCompanyEmbeddingSource {
+            name: Some("Example".into()),
+            industry: Some("SaaS".into()),
+            location: Some(" ".into()),
+            employee_band: Some(CompanyEmployeeBand::E51To200),
+            funding_stage: Some(CompanyFundingStage::SeriesA),
+            ..source("co_a")
+        };
+        assert_eq!(
+            company_document(&full),
+            "会社: Example\nslug: co_a\n業種: SaaS\n従業員数: 51〜200 人\n資金調達: シリーズ A"
+        );
+    }
+
+    #[test]
+    fn insight_document_has_kind_and_title_heading() {
+        assert_eq!(
+            insight_document(
+                InsightDocumentKind::ScoutMessage,
+                " 面談のお誘い ",
+                "本文\n"
+            ),
+            "スカウト: 面談のお誘い\n本文"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use futures_util::future::BoxFuture;
+
+use storage::wantedly::EMBEDDING_DIMENSIONS;
+
+use super::{Embedder, EmbeddingError};
+
+pub const LOCAL_EMBEDDING_MODEL: &str = "local-hash-v1";
+
+/// 語（英数字）と文字 bigram（日本語など）をハッシュで次元に振り分ける決定的な埋め込み
+///
+/// 意味までは捉えないが、同じ語を含む文ほど近くなる。外部 API なしでテスト・オフライン検証に使う。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalEmbedder;
+
+impl Embedder for LocalEmbedder {
+    fn model(&self) -> &str {
+        LOCAL_EMBEDDING_MODEL
+    }
+
+    fn embed<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Vec<f32>>, EmbeddingError>> {
+        Box::pin(async move { Ok(texts.iter().map(|t| embed_text(t)).collect()) })
+    }
+}
+
+pub fn embed_text(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; EMBEDDING_DIMENSIONS];
+
+    let mut features = features(text);
+    if features.is_empty() {
+        // ゼロベクトルはコサイン距離が定義できない
+        features.push(String::new());
+    }
+    for feature in &features {
+        let hash = fnv1a(feature.as_bytes());
+        let index = (hash % EMBEDDING_DIMENSIONS as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[index] += sign;
+    }
+
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    vector.iter_mut().for_each(|x| *x /= norm);
+    vector
+}
+
+fn features(text: &str) -> Vec<String> {
+    let lower = text.to_lowercase();
+    let mut features = Vec::new();
+
+    for run in lower.split(|c: char| !c.is_alphanumeric()) {
+        if run.is_empty() {
+            continue;
+        }
+        if run.is_ascii() {
+            features.push(format!("w:{run}"));
+            continue;
+        }
+        let chars: Vec<char> = run.chars().collect();
+        if chars.len() == 1 {
+            features.push(format!("c:{}", chars[0]));
+        }
+        features.extend(
+            chars
+                .windows(2)
+                .map(|pair| format!("b:{}{}", pair[0], pair[1])),
+        );
+    }
+
+    features
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn embeddings_are_deterministic_and_normalized() {
+        let a = embed_text("Backend Engineer");
+        let b = embed_text("backend  engineer!");
+
+        assert_eq!(a.len(), EMBEDDING_DIMENSIONS);
+        assert_eq!(a, b);
+        assert!((cosine(&a, &a) - 1.0).abs() < 1e-5);
+        assert!((cosine(&embed_text("…"), &embed_text("…")) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn texts_sharing_words_are_closer() {
+        let base = embed_text("バックエンドエンジニア Go");
+        let near = embed_text("バックエンドエンジニア Rust");
+        let far = embed_text("人事 採用担当");
+
+        assert!(cosine(&base, &near) > cosine(&base, &far));
+    }
+}
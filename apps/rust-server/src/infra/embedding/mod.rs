@@ -0,0 +1,149 @@
+use std::{sync::Arc, time::Duration};
+
+use futures_util::future::BoxFuture;
+use thiserror::Error;
+
+use storage::wantedly::EMBEDDING_DIMENSIONS;
+
+pub mod document;
+pub mod local;
+pub mod openai;
+
+use local::LocalEmbedder;
+use openai::OpenAiEmbedder;
+
+#[derive(Debug, Error)]
+pub enum EmbeddingError {
+    #[error("embedding request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("embedding API returned {status}: {body}")]
+    Api { status: u16, body: String },
+
+    #[error("invalid embedding response: {0}")]
+    InvalidResponse(String),
+}
+
+/// 文字列をベクトルにする（次元は EMBEDDING_DIMENSIONS）
+pub trait Embedder: Send + Sync {
+    /// 保存する埋め込みに記録するモデル名（変わると backfill で作り直す）
+    fn model(&self) -> &str;
+
+    /// texts と同じ順に返す
+    fn embed<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Vec<f32>>, EmbeddingError>>;
+}
+
+/// 件数と次元を確かめてから返す
+pub async fn embed_checked(
+    embedder: &dyn Embedder,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    let vectors = embedder.embed(texts).await?;
+
+    if vectors.len() != texts.len() {
+        return Err(EmbeddingError::InvalidResponse(format!(
+            "expected {} embeddings, got {}",
+            texts.len(),
+            vectors.len()
+        )));
+    }
+    if let Some(v) = vectors.iter().find(|v| v.len() != EMBEDDING_DIMENSIONS) {
+        return Err(EmbeddingError::InvalidResponse(format!(
+            "expected {EMBEDDING_DIMENSIONS} dimensions, got {}",
+            v.len()
+        )));
+    }
+
+    Ok(vectors)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingProvider {
+    /// 外部 API を使わない決定的な埋め込み（テスト・オフライン用）
+    #[default]
+    Local,
+    /// OpenAI 互換の /embeddings
+    OpenAi,
+}
+
+impl std::str::FromStr for EmbeddingProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "local" => Ok(EmbeddingProvider::Local),
+            "openai" => Ok(EmbeddingProvider::OpenAi),
+            other => Err(format!(
+                "unknown EMBEDDING_PROVIDER: {other} (expected local or openai)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmbedderConfig {
+    pub provider: EmbeddingProvider,
+    /// OpenAI 互換 API のベース URL（`/embeddings` の手前まで）
+    pub api_base: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    /// 1 リクエストの待ち時間の上限（None なら既定の 60 秒）
+    pub timeout: Option<Duration>,
+}
+
+pub fn build_embedder(config: &EmbedderConfig) -> Arc<dyn Embedder> {
+    match config.provider {
+        EmbeddingProvider::Local => Arc::new(LocalEmbedder),
+        EmbeddingProvider::OpenAi => Arc::new(OpenAiEmbedder::new(
+            &config.api_base,
+            config.api_key.clone(),
+            &config.model,
+            config.timeout.unwrap_or(openai::DEFAULT_REQUEST_TIMEOUT),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedEmbedder(Vec<Vec<f32>>);
+
+    impl Embedder for FixedEmbedder {
+        fn model(&self) -> &str {
+            "fixed"
+        }
+
+        fn embed<'a>(
+            &'a self,
+            _texts: &'a [String],
+        ) -> BoxFuture<'a, Result<Vec<Vec<f32>>, EmbeddingError>> {
+            Box::pin(async move { Ok(self.0.clone()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn embed_checked_rejects_wrong_count_and_dimensions() {
+        let texts = vec!["a".to_string(), "b".to_string()];
+
+        let one = FixedEmbedder(vec![vec![0.0; EMBEDDING_DIMENSIONS]]);
+        assert!(embed_checked(&one, &texts).await.is_err());
+
+        let short = FixedEmbedder(vec![vec![0.0; 3], vec![0.0; 3]]);
+        let err = embed_checked(&short, &texts).await.unwrap_err();
+        assert!(err.to_string().contains("dimensions"), "{err}");
+
+        let ok = FixedEmbedder(vec![vec![0.0; EMBEDDING_DIMENSIONS]; 2]);
+        assert_eq!(embed_checked(&ok, &texts).await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn provider_is_parsed_case_insensitively() {
+        assert_eq!("OpenAI".parse(), Ok(EmbeddingProvider::OpenAi));
+        assert_eq!(" local ".parse(), Ok(EmbeddingProvider::Local));
+        assert!("cohere".parse::<EmbeddingProvider>().is_err());
+    }
+}
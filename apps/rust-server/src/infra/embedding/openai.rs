@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use storage::wantedly::EMBEDDING_DIMENSIONS;
+
+use super::{Embedder, EmbeddingError};
+
+/// 接続の待ち時間の上限
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 1 リクエスト（応答の読み終わりまで）の待ち時間の既定の上限
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// OpenAI 互換の `POST {api_base}/embeddings`
+#[derive(Debug, Clone)]
+pub struct OpenAiEmbedder {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    dimensions: usize,
+    encoding_format: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiEmbedder {
+    pub fn new(api_base: &str, api_key: Option<String>, model: &str, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(timeout)
+            .build()
+            .expect("http client builds with timeouts only");
+
+        Self {
+            client,
+            endpoint: format!("{}/embeddings", api_base.trim_end_matches('/')),
+            api_key,
+            model: model.to_string(),
+        }
+    }
+
+    async fn request(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut request = self.client.post(&self.endpoint).json(&EmbeddingRequest {
+            model: &self.model,
+            input: texts,
+            dimensions: EMBEDDING_DIMENSIONS,
+            encoding_format: "float",
+        });
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(EmbeddingError::Api {
+                status: status.as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        let mut body: EmbeddingResponse = response.json().await?;
+        // 返る順は index で決まる
+        body.data.sort_by_key(|d| d.index);
+        if body.data.iter().enumerate().any(|(i, d)| d.index != i) {
+            return Err(EmbeddingError::InvalidResponse(
+                "embedding indexes are not contiguous".into(),
+            ));
+        }
+
+        Ok(body.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+impl Embedder for OpenAiEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Vec<f32>>, EmbeddingError>> {
+        Box::pin(self.request(texts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, http::HeaderMap, http::StatusCode, routing::post};
+    use serde_json::{Value, json};
+
+    /// 受け取った input を逆順の index で返すモック
+    async fn spawn_mock() -> String {
+        let app = Router::new()
+            .route(
+                "/slow/embeddings",
+                post(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    StatusCode::OK
+                }),
+            )
+            .route(
+                "/v1/embeddings",
+                post(|headers: HeaderMap, Json(body): Json<Value>| async move {
+                    if headers.get("authorization").and_then(|v| v.to_str().ok())
+                        != Some("Bearer secret")
+                    {
+                        return (StatusCode::UNAUTHORIZED, Json(json!({"error": "no key"})));
+                    }
+                    assert_eq!(body["model"], "test-model");
+                    assert_eq!(body["dimensions"], EMBEDDING_DIMENSIONS);
+
+                    let inputs = body["input"].as_array().unwrap();
+                    let data: Vec<Value> = inputs
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .map(|(i, text)| {
+                            let len = text.as_str().unwrap().len() as f32;
+                            json!({ "index": i, "embedding": [len, i as f32] })
+                        })
+                        .collect();
+                    (StatusCode::OK, Json(json!({ "data": data })))
+                }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{addr}/v1/")
+    }
+
+    #[tokio::test]
+    async fn embeddings_are_returned_in_input_order() {
+        let base = spawn_mock().await;
+        let embedder = OpenAiEmbedder::new(
+            &base,
+            Some("secret".into()),
+            "test-model",
+            DEFAULT_REQUEST_TIMEOUT,
+        );
+
+        let texts = vec!["a".to_string(), "bbb".to_string()];
+        let vectors = embedder.embed(&texts).await.unwrap();
+
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![3.0, 1.0]]);
+        assert_eq!(embedder.model(), "test-model");
+    }
+
+    #[tokio::test]
+    async fn api_errors_carry_status_and_body() {
+        let base = spawn_mock().await;
+        let embedder = OpenAiEmbedder::new(&base, None, "test-model", DEFAULT_REQUEST_TIMEOUT);
+
+        let err = embedder.embed(&["a".to_string()]).await.unwrap_err();
+
+        match err {
+            EmbeddingError::Api { status, body } => {
+                assert_eq!(status, 401);
+                assert!(body.contains("no key"), "{body}");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_responses_time_out() {
+        let base = spawn_mock().await.replace("/v1/", "/slow/");
+        let embedder = OpenAiEmbedder::new(&base, None, "test-model", Duration::from_millis(100));
+
+        let err = embedder.embed(&["a".to_string()]).await.unwrap_err();
+
+        match err {
+            EmbeddingError::Http(e) => assert!(e.is_timeout(), "{e}"),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
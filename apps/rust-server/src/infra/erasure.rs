@@ -0,0 +1,292 @@
+use std::{collections::HashSet, fmt};
+
+use storage::erasure::{ErasureSubjectKind, ErasureSuppression};
+use thiserror::Error;
+
+use crate::infra::{
+    digest::{hmac_sha256_hex, sha256_hex},
+    wantedly::{company::company_slug_from_page_url, dto::WantedlyProfileViewNode},
+};
+
+#[derive(Debug, Error)]
+pub enum ErasureKeyError {
+    #[error("ERASURE_HASH_KEY is not set")]
+    MissingKey,
+
+    #[error(
+        "erasure suppressions are hashed with an unknown key (key id {0}); set ERASURE_HASH_KEY_PREVIOUS"
+    )]
+    UnknownKey(String),
+}
+
+/// 削除要求の対象（閲覧者の userId か会社 slug）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErasureSubject {
+    Viewer(String),
+    Company(String),
+}
+
+impl ErasureSubject {
+    /// 前後の空白を落とし、空なら None
+    pub fn viewer(source_user_id: &str) -> Option<Self> {
+        Some(source_user_id.trim())
+            .filter(|v| !v.is_empty())
+            .map(|v| Self::Viewer(v.to_string()))
+    }
+
+    pub fn company(company_slug: &str) -> Option<Self> {
+        Some(company_slug.trim())
+            .filter(|v| !v.is_empty())
+            .map(|v| Self::Company(v.to_string()))
+    }
+
+    pub fn kind(&self) -> ErasureSubjectKind {
+        match self {
+            ErasureSubject::Viewer(_) => ErasureSubjectKind::Viewer,
+            ErasureSubject::Company(_) => ErasureSubjectKind::Company,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            ErasureSubject::Viewer(v) | ErasureSubject::Company(v) => v,
+        }
+    }
+
+    fn hash_input(&self) -> String {
+        format!("{}:{}", self.kind().as_str(), self.value())
+    }
+}
+
+/// erasure_suppressions / erasure_audit_log のハッシュに使う HMAC 鍵（ERASURE_HASH_KEY）
+#[derive(Clone)]
+pub struct ErasureKey {
+    key: Vec<u8>,
+    id: String,
+}
+
+impl ErasureKey {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        let key = key.into();
+        let id = sha256_hex(&key)[..16].to_string();
+        Self { key, id }
+    }
+
+    /// 鍵の指紋（行の key_id に残す）
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 対象のハッシュ（値そのものは残さない。鍵がなければ値を総当たりで確かめられない）
+    pub fn subject_hash(&self, subject: &ErasureSubject) -> String {
+        hmac_sha256_hex(&self.key, subject.hash_input().as_bytes())
+    }
+}
+
+impl fmt::Debug for ErasureKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasureKey").field("id", &self.id).finish()
+    }
+}
+
+/// 現在の鍵と、ローテーション前の鍵
+#[derive(Debug, Clone, Default)]
+pub struct ErasureKeys {
+    pub current: Option<ErasureKey>,
+    pub previous: Option<ErasureKey>,
+}
+
+impl ErasureKeys {
+    pub fn current(&self) -> Result<&ErasureKey, ErasureKeyError> {
+        self.current.as_ref().ok_or(ErasureKeyError::MissingKey)
+    }
+
+    fn find(&self, key_id: &str) -> Option<&ErasureKey> {
+        [self.current.as_ref(), self.previous.as_ref()]
+            .into_iter()
+            .flatten()
+            .find(|k| k.id() == key_id)
+    }
+
+    /// 同じ対象を旧鍵で作ったときのハッシュ
+    pub fn superseded_hashes(&self, subject: &ErasureSubject) -> Vec<String> {
+        self.previous
+            .iter()
+            .map(|k| k.subject_hash(subject))
+            .collect()
+    }
+}
+
+type SubjectHashes = HashSet<(ErasureSubjectKind, String)>;
+
+/// 削除済み対象のハッシュ集合。取り込み時に node ごとに照合する
+///
+/// 行ごとに作ったときの鍵で照合する。照合できない鍵の行があると作れない（削除済みの対象を取り込まないため）。
+#[derive(Debug, Default)]
+pub struct SuppressionSet {
+    /// 鍵ごとのハッシュ
+    groups: Vec<(ErasureKey, SubjectHashes)>,
+}
+
+impl SuppressionSet {
+    pub fn new(
+        keys: &ErasureKeys,
+        suppressions: impl IntoIterator<Item = ErasureSuppression>,
+    ) -> Result<Self, ErasureKeyError> {
+        let mut set = Self::default();
+        for suppression in suppressions {
+            let key = keys
+                .find(&suppression.key_id)
+                .ok_or_else(|| ErasureKeyError::UnknownKey(suppression.key_id.clone()))?;
+            let entry = (suppression.subject_kind, suppression.subject_hash);
+            match set.groups.iter_mut().find(|(k, _)| k.id() == key.id()) {
+                Some((_, hashes)) => {
+                    hashes.insert(entry);
+                }
+                None => set.groups.push((key.clone(), HashSet::from([entry]))),
+            }
+        }
+        Ok(set)
+    }
+
+    pub fn contains(&self, subject: &ErasureSubject) -> bool {
+        self.groups
+            .iter()
+            .any(|(key, hashes)| hashes.contains(&(subject.kind(), key.subject_hash(subject))))
+    }
+
+    /// 閲覧者本人か、閲覧時の所属会社が削除済みなら true
+    pub fn suppresses_node(&self, node: &WantedlyProfileViewNode) -> bool {
+        if self.groups.is_empty() {
+            return false;
+        }
+
+        let viewer = ErasureSubject::Viewer(node.user_id.to_string());
+        let company = node
+            .company_page_url
+            .as_deref()
+            .and_then(company_slug_from_page_url)
+            .map(ErasureSubject::Company);
+
+        self.contains(&viewer) || company.is_some_and(|c| self.contains(&c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::wantedly::dto::ProfileImpressionMeta;
+    use chrono::Utc;
+
+    fn suppression(key: &ErasureKey, subject: &ErasureSubject) -> ErasureSuppression {
+        ErasureSuppression {
+            id: 1,
+            subject_kind: subject.kind(),
+            subject_hash: key.subject_hash(subject),
+            key_id: key.id().to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn keys(current: &ErasureKey) -> ErasureKeys {
+        ErasureKeys {
+            current: Some(current.clone()),
+            previous: None,
+        }
+    }
+
+    fn node(user_id: i64, company_page_url: Option<&str>) -> WantedlyProfileViewNode {
+        WantedlyProfileViewNode {
+            user_id,
+            short_description: None,
+            company_page_url: company_page_url.map(str::to_string),
+            profile_impression_meta: ProfileImpressionMeta {
+                impressed_date_time: "2025-11-01T10:00:00+09:00".into(),
+            },
+        }
+    }
+
+    #[test]
+    fn blank_subjects_are_rejected() {
+        assert_eq!(ErasureSubject::viewer("  "), None);
+        assert_eq!(
+            ErasureSubject::company(" company_xyz "),
+            Some(ErasureSubject::Company("company_xyz".into()))
+        );
+    }
+
+    #[test]
+    fn hash_depends_on_kind_and_key() {
+        let key = ErasureKey::new("secret");
+        let viewer = ErasureSubject::Viewer("123".into());
+        let company = ErasureSubject::Company("123".into());
+
+        assert_ne!(key.subject_hash(&viewer), key.subject_hash(&company));
+        assert_ne!(
+            key.subject_hash(&viewer),
+            sha256_hex(viewer.hash_input().as_bytes())
+        );
+        assert_ne!(
+            key.subject_hash(&viewer),
+            ErasureKey::new("other").subject_hash(&viewer)
+        );
+        assert!(!format!("{key:?}").contains("secret"));
+    }
+
+    #[test]
+    fn suppressed_viewer_or_company_skips_node() {
+        let key = ErasureKey::new("secret");
+        let set = SuppressionSet::new(
+            &keys(&key),
+            [
+                suppression(&key, &ErasureSubject::Viewer("42".into())),
+                suppression(&key, &ErasureSubject::Company("company_xyz".into())),
+            ],
+        )
+        .unwrap();
+
+        assert!(set.suppresses_node(&node(42, None)));
+        assert!(set.suppresses_node(&node(
+            7,
+            Some("https://www.wantedly.com/companies/company_xyz/post_articles")
+        )));
+        assert!(!set.suppresses_node(&node(7, Some("https://www.wantedly.com/companies/other"))));
+    }
+
+    #[test]
+    fn rows_of_previous_key_still_match() {
+        let old = ErasureKey::new("old");
+        let new = ErasureKey::new("new");
+        let keys = ErasureKeys {
+            current: Some(new.clone()),
+            previous: Some(old.clone()),
+        };
+
+        let set = SuppressionSet::new(
+            &keys,
+            [
+                suppression(&old, &ErasureSubject::Viewer("42".into())),
+                suppression(&new, &ErasureSubject::Viewer("7".into())),
+            ],
+        )
+        .unwrap();
+
+        assert!(set.suppresses_node(&node(42, None)));
+        assert!(set.suppresses_node(&node(7, None)));
+        assert!(!set.suppresses_node(&node(8, None)));
+        assert_eq!(
+            keys.superseded_hashes(&ErasureSubject::Viewer("42".into())),
+            vec![old.subject_hash(&ErasureSubject::Viewer("42".into()))]
+        );
+    }
+
+    #[test]
+    fn rows_of_unknown_key_are_rejected() {
+        let old = ErasureKey::new("old");
+        let rows = [suppression(&old, &ErasureSubject::Viewer("42".into()))];
+
+        let err = SuppressionSet::new(&keys(&ErasureKey::new("new")), rows).unwrap_err();
+
+        assert!(matches!(err, ErasureKeyError::UnknownKey(id) if id == old.id()));
+    }
+}
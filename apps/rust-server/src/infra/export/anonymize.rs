@@ -0,0 +1,616 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use storage::wantedly::WantedlyCompanyExportRow;
+use utoipa::ToSchema;
+
+use super::{
+    ExportError, ExportTable,
+    columns::{ExportColumn, Sensitivity},
+};
+use crate::infra::{digest::hmac_sha256_hex, wantedly::company::company_slug_from_page_url};
+
+/// 自由記述（shortDescription）の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DescriptionMode {
+    /// NULL にする
+    #[default]
+    Strip,
+    /// 職種カテゴリ（role:engineer など）に置き換える
+    Generalize,
+}
+
+/// 会社を特定できる列の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompanyMode {
+    /// 会社はそのまま出す（閲覧者のみ仮名化）
+    Keep,
+    /// slug / URL をキー付きハッシュに置き換え、会社名・ドメインは NULL にする
+    #[default]
+    Hash,
+    /// slug / URL / 会社名・ドメインを業種と従業員規模の区分（bucket:saas/51_200 など）に置き換える
+    Bucket,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnonymizeOptions {
+    pub key: Vec<u8>,
+    pub description: DescriptionMode,
+    pub company: CompanyMode,
+}
+
+impl AnonymizeOptions {
+    fn pseudonym(&self, namespace: &str, value: &str) -> String {
+        let digest = hmac_sha256_hex(&self.key, format!("{namespace}:{value}").as_bytes());
+        format!("{namespace}_{}", &digest[..32])
+    }
+
+    fn company_pseudonym(&self, value: &str) -> String {
+        let slug = company_slug_from_page_url(value).unwrap_or_else(|| value.to_string());
+        self.pseudonym("company", &slug)
+    }
+}
+
+const UNKNOWN_BUCKET: &str = "unknown";
+
+/// company_mode = bucket で使う、会社 slug → 区分のラベル
+#[derive(Debug, Clone, Default)]
+pub struct CompanyBuckets {
+    labels: HashMap<String, String>,
+}
+
+impl CompanyBuckets {
+    pub fn from_companies(companies: &[WantedlyCompanyExportRow]) -> Self {
+        Self {
+            labels: companies
+                .iter()
+                .map(|c| {
+                    let label = bucket_label(c.industry.as_deref(), c.employee_band.as_deref());
+                    (c.company_slug.clone(), label)
+                })
+                .collect(),
+        }
+    }
+
+    /// slug か companyPageUrl の区分（属性のない・知らない会社は bucket:unknown/unknown）
+    fn label(&self, value: &str) -> String {
+        let slug = company_slug_from_page_url(value).unwrap_or_else(|| value.to_string());
+        self.labels
+            .get(&slug)
+            .cloned()
+            .unwrap_or_else(|| bucket_label(None, None))
+    }
+
+    /// 区分のラベル（業種は公開してよい属性なので、元の値に含まれていても漏洩ではない）
+    fn is_label(&self, text: &str) -> bool {
+        text == bucket_label(None, None) || self.labels.values().any(|l| l == text)
+    }
+}
+
+fn bucket_label(industry: Option<&str>, employee_band: Option<&str>) -> String {
+    let part = |v: Option<&str>| {
+        v.map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_lowercase)
+            .unwrap_or_else(|| UNKNOWN_BUCKET.to_string())
+    };
+    format!("bucket:{}/{}", part(industry), part(employee_band))
+}
+
+/// raw_json のうち匿名化後も残すフィールド（閲覧日時の生文字列だけ）
+fn reduce_raw_json(raw: &Value) -> Value {
+    match raw.pointer("/profileImpressionMeta/impressedDateTime") {
+        Some(v) => json!({ "profileImpressionMeta": { "impressedDateTime": v } }),
+        None => json!({}),
+    }
+}
+
+/// 職種カテゴリとキーワード（上から順に判定する）
+///
+/// 英字のキーワードは単語の先頭から一致させ、3 文字以下のもの（cto, pm, ui など）は単語全体の一致に限る
+/// （director の cto や development の pm に反応させない）。日本語のキーワードは部分一致。
+const ROLES: &[(&str, &[&str])] = &[
+    (
+        "role:executive",
+        &[
+            "ceo",
+            "cto",
+            "coo",
+            "cfo",
+            "代表",
+            "取締役",
+            "執行役員",
+            "founder",
+            "創業",
+        ],
+    ),
+    (
+        "role:recruiter",
+        &["人事", "採用", "recruit", "リクルーター", "hr", "talent"],
+    ),
+    (
+        "role:product_manager",
+        &["pdm", "プロダクトマネージャー", "product manager", "pm"],
+    ),
+    ("role:designer", &["デザイナー", "designer", "ui", "ux"]),
+    (
+        "role:engineer",
+        &[
+            "エンジニア",
+            "engineer",
+            "develop",
+            "開発",
+            "sre",
+            "プログラマ",
+        ],
+    ),
+    (
+        "role:business",
+        &[
+            "営業",
+            "sales",
+            "セールス",
+            "bizdev",
+            "マーケ",
+            "marketing",
+            "cs",
+        ],
+    ),
+    ("role:student", &["学生", "student", "大学", "university"]),
+];
+
+const OTHER_ROLE: &str = "role:other";
+
+/// shortDescription を粗い職種カテゴリに丸める
+pub fn generalize_description(text: &str) -> &'static str {
+    let lower = text.to_lowercase();
+    ROLES
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| contains_keyword(&lower, k)))
+        .map(|(role, _)| *role)
+        .unwrap_or(OTHER_ROLE)
+}
+
+/// generalize_description が返す固定の値（元の文に含まれていても漏洩ではない）
+fn is_role_label(text: &str) -> bool {
+    text == OTHER_ROLE || ROLES.iter().any(|(role, _)| *role == text)
+}
+
+fn contains_keyword(text: &str, keyword: &str) -> bool {
+    if !keyword.is_ascii() {
+        return text.contains(keyword);
+    }
+
+    let is_word_char = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    let whole_word = keyword.len() <= 3;
+    text.match_indices(keyword).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + keyword.len()..].chars().next();
+        !is_word_char(before) && (!whole_word || !is_word_char(after))
+    })
+}
+
+/// row_bucket は company_mode = bucket のときの、その行の会社の区分
+fn anonymize_value(
+    column: &ExportColumn,
+    value: &Value,
+    options: &AnonymizeOptions,
+    row_bucket: Option<&str>,
+) -> Value {
+    if value.is_null() {
+        return Value::Null;
+    }
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    match column.sensitivity {
+        Sensitivity::Public => value.clone(),
+        Sensitivity::ViewerId => Value::from(options.pseudonym("viewer", &text)),
+        Sensitivity::CompanyKey => match options.company {
+            CompanyMode::Keep => value.clone(),
+            CompanyMode::Hash => Value::from(options.company_pseudonym(&text)),
+            CompanyMode::Bucket => row_bucket.map_or(Value::Null, Value::from),
+        },
+        Sensitivity::CompanyAttribute => match options.company {
+            CompanyMode::Keep => value.clone(),
+            CompanyMode::Hash => Value::Null,
+            CompanyMode::Bucket => row_bucket.map_or(Value::Null, Value::from),
+        },
+        Sensitivity::FreeText => match options.description {
+            DescriptionMode::Strip => Value::Null,
+            DescriptionMode::Generalize => Value::from(generalize_description(&text)),
+        },
+        Sensitivity::RawJson => reduce_raw_json(value),
+    }
+}
+
+/// 元データから、匿名化後に残ってはいけない識別子を集める
+fn collect_identifiers(table: &ExportTable, options: &AnonymizeOptions) -> Identifiers {
+    let mut ids = Identifiers::default();
+
+    for row in &table.rows {
+        for (column, value) in table.columns.iter().zip(row) {
+            let Some(text) = value_text(value) else {
+                continue;
+            };
+            match column.sensitivity {
+                Sensitivity::ViewerId => {
+                    ids.exact.insert(text);
+                }
+                Sensitivity::FreeText => ids.insert_text(text),
+                Sensitivity::CompanyKey | Sensitivity::CompanyAttribute
+                    if options.company != CompanyMode::Keep =>
+                {
+                    if let Some(slug) = company_slug_from_page_url(&text) {
+                        ids.insert_text(slug);
+                    }
+                    ids.insert_text(text);
+                }
+                Sensitivity::RawJson => {
+                    // node 内の userId / shortDescription / companyPageUrl も対象にする
+                    if let Some(user_id) = value.pointer("/userId").and_then(value_text) {
+                        ids.exact.insert(user_id);
+                    }
+                    if let Some(text) = value.pointer("/shortDescription").and_then(value_text) {
+                        ids.insert_text(text);
+                    }
+                    if options.company != CompanyMode::Keep
+                        && let Some(url) = value.pointer("/companyPageUrl").and_then(value_text)
+                    {
+                        ids.insert_text(url);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    ids
+}
+
+#[derive(Debug, Default)]
+struct Identifiers {
+    /// 値全体が一致したら漏洩とみなすもの（数値の userId など、部分一致だと誤検知するもの）
+    exact: HashSet<String>,
+    /// 部分文字列として含まれていたら漏洩とみなすもの
+    text: HashSet<String>,
+}
+
+impl Identifiers {
+    fn insert_text(&mut self, text: String) {
+        let trimmed = text.trim();
+        // 短すぎる文字列は偶然一致するため完全一致で扱う
+        if trimmed.chars().count() >= 4 {
+            self.text.insert(trimmed.to_string());
+        } else if !trimmed.is_empty() {
+            self.exact.insert(trimmed.to_string());
+        }
+    }
+
+    fn leaked_in(&self, value: &Value) -> Option<String> {
+        match value {
+            Value::Array(items) => items.iter().find_map(|v| self.leaked_in(v)),
+            Value::Object(map) => map.values().find_map(|v| self.leaked_in(v)),
+            other => {
+                let text = value_text(other)?;
+                if self.exact.contains(&text) {
+                    return Some(text);
+                }
+                self.text
+                    .iter()
+                    .find(|t| text.contains(t.as_str()))
+                    .cloned()
+            }
+        }
+    }
+}
+
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(_) | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// 列の性質に従って仮名化し、元の識別子が 1 つも残っていないことを確かめてから返す
+///
+/// buckets は company_mode = bucket のときだけ使う（それ以外は空でよい）。
+pub fn anonymize_table(
+    table: &ExportTable,
+    options: &AnonymizeOptions,
+    buckets: &CompanyBuckets,
+) -> Result<ExportTable, ExportError> {
+    let rows: Vec<Vec<Value>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            let row_bucket = (options.company == CompanyMode::Bucket)
+                .then(|| {
+                    table
+                        .columns
+                        .iter()
+                        .zip(row)
+                        .find(|(c, v)| c.sensitivity == Sensitivity::CompanyKey && !v.is_null())
+                        .and_then(|(_, v)| value_text(v))
+                        .map(|key| buckets.label(&key))
+                })
+                .flatten();
+            table
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| {
+                    anonymize_value(column, value, options, row_bucket.as_deref())
+                })
+                .collect()
+        })
+        .collect();
+
+    let anonymized = ExportTable {
+        columns: table.columns,
+        rows,
+    };
+    verify_anonymized(table, &anonymized, options, buckets)?;
+
+    Ok(anonymized)
+}
+
+fn verify_anonymized(
+    original: &ExportTable,
+    anonymized: &ExportTable,
+    options: &AnonymizeOptions,
+    buckets: &CompanyBuckets,
+) -> Result<(), ExportError> {
+    let ids = collect_identifiers(original, options);
+
+    for row in &anonymized.rows {
+        for (column, value) in anonymized.columns.iter().zip(row) {
+            if column.sensitivity == Sensitivity::Public {
+                continue;
+            }
+            // 職種カテゴリは固定の語彙なので、元の文が "engineer" などでも漏洩とはみなさない
+            if column.sensitivity == Sensitivity::FreeText
+                && value.as_str().is_some_and(is_role_label)
+            {
+                continue;
+            }
+            if matches!(
+                column.sensitivity,
+                Sensitivity::CompanyKey | Sensitivity::CompanyAttribute
+            ) && value.as_str().is_some_and(|v| buckets.is_label(v))
+            {
+                continue;
+            }
+            if ids.leaked_in(value).is_some() {
+                // 値そのものはエラーに含めない
+                return Err(ExportError::IdentifierLeak {
+                    column: column.name,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::export::columns;
+
+    fn options(description: DescriptionMode, company: CompanyMode) -> AnonymizeOptions {
+        AnonymizeOptions {
+            key: b"test-key".to_vec(),
+            description,
+            company,
+        }
+    }
+
+    fn raw_views() -> ExportTable {
+        ExportTable {
+            columns: columns::RAW_VIEWS,
+            rows: vec![vec![
+                json!(1),
+                json!("12345"),
+                json!("https://www.wantedly.com/companies/company_xyz"),
+                json!("XYZ株式会社 バックエンドエンジニア"),
+                json!("3日前"),
+                json!("2025-11-20T00:00:00Z"),
+                json!({
+                    "userId": 12345,
+                    "shortDescription": "XYZ株式会社 バックエンドエンジニア",
+                    "companyPageUrl": "https://www.wantedly.com/companies/company_xyz",
+                    "profileImpressionMeta": { "impressedDateTime": "3日前" }
+                }),
+                json!("2025-11-23T00:00:00Z"),
+            ]],
+        }
+    }
+
+    #[test]
+    fn viewer_ids_are_keyed_and_stable() {
+        let opts = options(DescriptionMode::Strip, CompanyMode::Hash);
+        let a = anonymize_table(&raw_views(), &opts, &CompanyBuckets::default()).unwrap();
+        let b = anonymize_table(&raw_views(), &opts, &CompanyBuckets::default()).unwrap();
+        assert_eq!(a.rows, b.rows);
+
+        let other_key = AnonymizeOptions {
+            key: b"other-key".to_vec(),
+            ..opts
+        };
+        let c = anonymize_table(&raw_views(), &other_key, &CompanyBuckets::default()).unwrap();
+        assert_ne!(a.rows[0][1], c.rows[0][1]);
+        assert!(a.rows[0][1].as_str().unwrap().starts_with("viewer_"));
+    }
+
+    #[test]
+    fn hash_mode_strips_identifying_columns() {
+        let table = anonymize_table(
+            &raw_views(),
+            &options(DescriptionMode::Strip, CompanyMode::Hash),
+            &CompanyBuckets::default(),
+        )
+        .unwrap();
+        let row = &table.rows[0];
+
+        assert_eq!(row[0], json!(1));
+        assert!(row[2].as_str().unwrap().starts_with("company_"));
+        assert_eq!(row[3], Value::Null);
+        assert_eq!(
+            row[6],
+            json!({ "profileImpressionMeta": { "impressedDateTime": "3日前" } })
+        );
+    }
+
+    fn companies() -> Vec<WantedlyCompanyExportRow> {
+        let company =
+            |id: i64, slug: &str, name: &str, industry: Option<&str>| WantedlyCompanyExportRow {
+                company_id: id,
+                company_slug: slug.into(),
+                company_page_url: format!("https://www.wantedly.com/companies/{slug}"),
+                name: Some(name.into()),
+                domain: Some(format!("{slug}.example.com")),
+                attribute_source: Some("manual".into()),
+                confidence: None,
+                industry: industry.map(str::to_string),
+                employee_band: industry.map(|_| "51_200".to_string()),
+                funding_stage: None,
+                location: None,
+                total_views: 3,
+                distinct_viewers: 2,
+                first_viewed_at: None,
+                last_viewed_at: None,
+                created_at: chrono::Utc::now(),
+            };
+        vec![
+            company(1, "company_xyz", "XYZ株式会社", Some("SaaS")),
+            company(2, "company_abc", "ABC株式会社", None),
+        ]
+    }
+
+    #[test]
+    fn bucket_mode_replaces_companies_with_size_and_industry() {
+        let companies = companies();
+        let buckets = CompanyBuckets::from_companies(&companies);
+        let opts = options(DescriptionMode::Strip, CompanyMode::Bucket);
+
+        let views = anonymize_table(&raw_views(), &opts, &buckets).unwrap();
+        assert_eq!(views.rows[0][2], json!("bucket:saas/51_200"));
+
+        let table = ExportTable::from_records(columns::COMPANIES, &companies).unwrap();
+        let anonymized = anonymize_table(&table, &opts, &buckets).unwrap();
+        let first = &anonymized.rows[0];
+        // slug / URL / 会社名 / ドメイン
+        assert_eq!(first[1..=4], vec![json!("bucket:saas/51_200"); 4]);
+        assert_eq!(first[7], json!("SaaS"));
+        assert_eq!(anonymized.rows[1][1], json!("bucket:unknown/unknown"));
+    }
+
+    #[test]
+    fn bucket_mode_detects_leaked_company() {
+        let companies = companies();
+        let buckets = CompanyBuckets::from_companies(&companies);
+        let opts = options(DescriptionMode::Strip, CompanyMode::Bucket);
+        let original = ExportTable::from_records(columns::COMPANIES, &companies).unwrap();
+        let mut leaked = anonymize_table(&original, &opts, &buckets).unwrap();
+        leaked.rows[0][3] = json!("XYZ株式会社");
+
+        assert!(matches!(
+            verify_anonymized(&original, &leaked, &opts, &buckets),
+            Err(ExportError::IdentifierLeak { column: "name" })
+        ));
+    }
+
+    #[test]
+    fn company_url_and_slug_share_pseudonym() {
+        let opts = options(DescriptionMode::Strip, CompanyMode::Hash);
+        assert_eq!(
+            opts.company_pseudonym("https://www.wantedly.com/companies/company_xyz"),
+            opts.company_pseudonym("company_xyz")
+        );
+    }
+
+    #[test]
+    fn generalize_maps_descriptions_to_roles() {
+        let table = anonymize_table(
+            &raw_views(),
+            &options(DescriptionMode::Generalize, CompanyMode::Keep),
+            &CompanyBuckets::default(),
+        )
+        .unwrap();
+
+        assert_eq!(table.rows[0][3], json!("role:engineer"));
+        assert_eq!(generalize_description("人事部 採用担当"), "role:recruiter");
+        assert_eq!(generalize_description("代表取締役"), "role:executive");
+        assert_eq!(generalize_description("趣味で写真"), "role:other");
+    }
+
+    #[test]
+    fn short_keywords_match_whole_words_only() {
+        assert_eq!(
+            generalize_description("Engineering Director"),
+            "role:engineer"
+        );
+        assert_eq!(generalize_description("Web development"), "role:engineer");
+        assert_eq!(generalize_description("開発 development"), "role:engineer");
+        assert_eq!(generalize_description("guitar と build"), "role:other");
+        assert_eq!(generalize_description("physics / chrome"), "role:other");
+        assert_eq!(generalize_description("Co-founder & CTO"), "role:executive");
+        assert_eq!(generalize_description("PMです"), "role:product_manager");
+        assert_eq!(generalize_description("UI/UX デザイン"), "role:designer");
+    }
+
+    #[test]
+    fn bare_role_word_is_not_reported_as_leak() {
+        let mut table = raw_views();
+        table.rows[0][3] = json!("engineer");
+        table.rows[0][6]["shortDescription"] = json!("engineer");
+
+        let anonymized = anonymize_table(
+            &table,
+            &options(DescriptionMode::Generalize, CompanyMode::Keep),
+            &CompanyBuckets::default(),
+        )
+        .unwrap();
+
+        assert_eq!(anonymized.rows[0][3], json!("role:engineer"));
+    }
+
+    #[test]
+    fn viewer_id_inside_raw_json_is_detected() {
+        let opts = options(DescriptionMode::Strip, CompanyMode::Keep);
+        let original = raw_views();
+        let mut leaked = anonymize_table(&original, &opts, &CompanyBuckets::default()).unwrap();
+        leaked.rows[0][6] = json!({ "node": { "userId": 12345 } });
+
+        assert!(matches!(
+            verify_anonymized(&original, &leaked, &opts, &CompanyBuckets::default()),
+            Err(ExportError::IdentifierLeak { column: "raw_json" })
+        ));
+    }
+
+    #[test]
+    fn leak_is_detected() {
+        let opts = options(DescriptionMode::Strip, CompanyMode::Hash);
+        let original = raw_views();
+        let mut leaked = anonymize_table(&original, &opts, &CompanyBuckets::default()).unwrap();
+        leaked.rows[0][3] = json!("role: XYZ株式会社 バックエンドエンジニア");
+
+        let err = verify_anonymized(&original, &leaked, &opts, &CompanyBuckets::default())
+            .expect_err("free text is still present");
+
+        assert!(matches!(
+            err,
+            ExportError::IdentifierLeak {
+                column: "viewer_company_name_raw"
+            }
+        ));
+    }
+}
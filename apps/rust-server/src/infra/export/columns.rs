@@ -0,0 +1,119 @@
+/// エクスポート列の型（CSV / JSON Lines / Parquet で共通）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Int64,
+    Float64,
+    Text,
+    /// JSON 値（CSV / Parquet では JSON 文字列）
+    Json,
+    /// UTC の日時（CSV / JSON Lines では RFC 3339）
+    Timestamp,
+    /// JST の暦日（YYYY-MM-DD）
+    Date,
+}
+
+/// 匿名化モードでの扱いを決める列の性質
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sensitivity {
+    /// そのまま出してよい（内部 ID・日時・集計値など）
+    Public,
+    /// Wantedly の userId
+    ViewerId,
+    /// 会社 slug / companyPageUrl
+    CompanyKey,
+    /// 会社名・ドメインなど会社を特定できる属性
+    CompanyAttribute,
+    /// shortDescription などの自由記述
+    FreeText,
+    /// GQL node 全体
+    RawJson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportColumn {
+    pub name: &'static str,
+    pub kind: ColumnKind,
+    pub nullable: bool,
+    pub sensitivity: Sensitivity,
+}
+
+impl ExportColumn {
+    const fn sensitive(self, sensitivity: Sensitivity) -> Self {
+        ExportColumn {
+            sensitivity,
+            ..self
+        }
+    }
+}
+
+const fn col(name: &'static str, kind: ColumnKind) -> ExportColumn {
+    ExportColumn {
+        name,
+        kind,
+        nullable: false,
+        sensitivity: Sensitivity::Public,
+    }
+}
+
+const fn nullable(name: &'static str, kind: ColumnKind) -> ExportColumn {
+    ExportColumn {
+        name,
+        kind,
+        nullable: true,
+        sensitivity: Sensitivity::Public,
+    }
+}
+
+use ColumnKind::*;
+use Sensitivity::*;
+
+// 列の追加は末尾のみ（既存列の改名・削除・並べ替えは docs/export.md のスキーマ版を上げる）
+pub const RAW_VIEWS: &[ExportColumn] = &[
+    col("raw_profile_view_id", Int64),
+    col("viewer_user_id", Text).sensitive(ViewerId),
+    nullable("viewer_company_page_url", Text).sensitive(CompanyKey),
+    nullable("viewer_company_name_raw", Text).sensitive(FreeText),
+    col("viewed_at_raw", Text),
+    col("viewed_at", Timestamp),
+    col("raw_json", Json).sensitive(RawJson),
+    col("created_at", Timestamp),
+];
+
+pub const IMPRESSIONS: &[ExportColumn] = &[
+    col("impression_id", Int64),
+    col("impressed_at", Timestamp),
+    col("viewer_id", Int64),
+    col("viewer_user_id", Text).sensitive(ViewerId),
+    nullable("company_id_at_view", Int64),
+    nullable("company_slug_at_view", Text).sensitive(CompanyKey),
+    nullable("affiliation_kind", Text),
+    nullable("affiliation_short_description", Text).sensitive(FreeText),
+    col("raw_profile_view_id", Int64),
+];
+
+pub const COMPANIES: &[ExportColumn] = &[
+    col("company_id", Int64),
+    col("company_slug", Text).sensitive(CompanyKey),
+    col("company_page_url", Text).sensitive(CompanyKey),
+    nullable("name", Text).sensitive(CompanyAttribute),
+    nullable("domain", Text).sensitive(CompanyAttribute),
+    nullable("attribute_source", Text),
+    nullable("confidence", Float64),
+    // 業種・規模などの区分は単独では会社を特定できないので匿名化でも残す
+    nullable("industry", Text),
+    nullable("employee_band", Text),
+    nullable("funding_stage", Text),
+    nullable("location", Text),
+    col("total_views", Int64),
+    col("distinct_viewers", Int64),
+    nullable("first_viewed_at", Timestamp),
+    nullable("last_viewed_at", Timestamp),
+    col("created_at", Timestamp),
+];
+
+pub const DAILY_VIEWS: &[ExportColumn] = &[
+    col("date", Date),
+    col("views", Int64),
+    col("distinct_viewers", Int64),
+    col("distinct_companies", Int64),
+];
@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::PgPool;
+use thiserror::Error;
+
+use storage::wantedly::{
+    WantedlyExportError, WantedlyExportFilter, export_companies, export_daily_views,
+    export_impressions, export_raw_views,
+};
+
+pub mod anonymize;
+pub mod columns;
+pub mod writer;
+
+use anonymize::{AnonymizeOptions, CompanyBuckets, CompanyMode, anonymize_table};
+use columns::ExportColumn;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("failed to query export rows: {0}")]
+    Query(#[from] WantedlyExportError),
+
+    #[error("failed to serialize export row: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("export row has no column `{0}`")]
+    MissingColumn(&'static str),
+
+    #[error("invalid value for column `{column}`: {value}")]
+    InvalidValue { column: &'static str, value: String },
+
+    #[error("original identifier remains in column `{column}` after anonymization")]
+    IdentifierLeak { column: &'static str },
+
+    #[error("failed to write file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to write csv: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("failed to build arrow batch: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[error("failed to write parquet: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+}
+
+/// エクスポート対象のデータセット（スキーマは docs/export.md 参照）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportDataset {
+    RawViews,
+    Impressions,
+    Companies,
+    DailyViews,
+}
+
+impl ExportDataset {
+    pub const ALL: [ExportDataset; 4] = [
+        ExportDataset::RawViews,
+        ExportDataset::Impressions,
+        ExportDataset::Companies,
+        ExportDataset::DailyViews,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportDataset::RawViews => "raw_views",
+            ExportDataset::Impressions => "impressions",
+            ExportDataset::Companies => "companies",
+            ExportDataset::DailyViews => "daily_views",
+        }
+    }
+
+    pub fn columns(&self) -> &'static [ExportColumn] {
+        match self {
+            ExportDataset::RawViews => columns::RAW_VIEWS,
+            ExportDataset::Impressions => columns::IMPRESSIONS,
+            ExportDataset::Companies => columns::COMPANIES,
+            ExportDataset::DailyViews => columns::DAILY_VIEWS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+}
+
+/// 列順に並べたエクスポート 1 データセット分の値
+#[derive(Debug, Clone)]
+pub struct ExportTable {
+    pub columns: &'static [ExportColumn],
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl ExportTable {
+    pub fn from_records<T: serde::Serialize>(
+        columns: &'static [ExportColumn],
+        records: &[T],
+    ) -> Result<Self, ExportError> {
+        let rows = records
+            .iter()
+            .map(|record| {
+                let mut object = match serde_json::to_value(record)? {
+                    Value::Object(object) => object,
+                    other => {
+                        return Err(ExportError::InvalidValue {
+                            column: "*",
+                            value: other.to_string(),
+                        });
+                    }
+                };
+                columns
+                    .iter()
+                    .map(|c| {
+                        object
+                            .remove(c.name)
+                            .ok_or(ExportError::MissingColumn(c.name))
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { columns, rows })
+    }
+}
+
+pub async fn fetch_export_table(
+    pool: &PgPool,
+    dataset: ExportDataset,
+    filter: &WantedlyExportFilter,
+) -> Result<ExportTable, ExportError> {
+    let columns = dataset.columns();
+    match dataset {
+        ExportDataset::RawViews => {
+            ExportTable::from_records(columns, &export_raw_views(pool, filter).await?)
+        }
+        ExportDataset::Impressions => {
+            ExportTable::from_records(columns, &export_impressions(pool, filter).await?)
+        }
+        ExportDataset::Companies => {
+            ExportTable::from_records(columns, &export_companies(pool, filter).await?)
+        }
+        ExportDataset::DailyViews => {
+            ExportTable::from_records(columns, &export_daily_views(pool, filter).await?)
+        }
+    }
+}
+
+/// 取得して、指定があれば匿名化まで済ませたテーブルを返す
+pub async fn prepare_export_table(
+    pool: &PgPool,
+    dataset: ExportDataset,
+    filter: &WantedlyExportFilter,
+    anonymize: Option<&AnonymizeOptions>,
+) -> Result<ExportTable, ExportError> {
+    let table = fetch_export_table(pool, dataset, filter).await?;
+    let Some(options) = anonymize else {
+        return Ok(table);
+    };
+
+    // 区分はデータセットや期間によらず、全社の属性から引く
+    let buckets = match options.company {
+        CompanyMode::Bucket => CompanyBuckets::from_companies(
+            &export_companies(pool, &WantedlyExportFilter::default()).await?,
+        ),
+        CompanyMode::Keep | CompanyMode::Hash => CompanyBuckets::default(),
+    };
+    anonymize_table(&table, options, &buckets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use storage::wantedly::WantedlyDailyViewsExportRow;
+
+    #[test]
+    fn from_records_orders_values_by_columns() {
+        let rows = vec![WantedlyDailyViewsExportRow {
+            date: NaiveDate::from_ymd_opt(2025, 11, 23).unwrap(),
+            views: 5,
+            distinct_viewers: 4,
+            distinct_companies: 3,
+        }];
+
+        let table = ExportTable::from_records(columns::DAILY_VIEWS, &rows).unwrap();
+
+        assert_eq!(
+            table.rows,
+            vec![vec![
+                Value::from("2025-11-23"),
+                Value::from(5),
+                Value::from(4),
+                Value::from(3),
+            ]]
+        );
+    }
+
+    #[test]
+    fn from_records_rejects_missing_column() {
+        #[derive(serde::Serialize)]
+        struct Partial {
+            date: &'static str,
+        }
+
+        let err = ExportTable::from_records(columns::DAILY_VIEWS, &[Partial { date: "x" }])
+            .expect_err("views column is missing");
+
+        assert!(matches!(err, ExportError::MissingColumn("views")));
+    }
+}
@@ -0,0 +1,291 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, RecordBatch,
+    builder::{
+        Date32Builder, Float64Builder, Int64Builder, StringBuilder, TimestampMicrosecondBuilder,
+    },
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, NaiveDate};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use serde_json::Value;
+
+use super::{
+    ExportError, ExportFormat, ExportTable,
+    columns::{ColumnKind, ExportColumn},
+};
+
+pub fn write_export_table<W: Write + Send>(
+    table: &ExportTable,
+    format: ExportFormat,
+    writer: W,
+) -> Result<(), ExportError> {
+    match format {
+        ExportFormat::Csv => write_csv(table, writer),
+        ExportFormat::Jsonl => write_jsonl(table, writer),
+        ExportFormat::Parquet => write_parquet(table, writer),
+    }
+}
+
+fn write_csv<W: Write>(table: &ExportTable, writer: W) -> Result<(), ExportError> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(table.columns.iter().map(|c| c.name))?;
+
+    for row in &table.rows {
+        csv.write_record(row.iter().map(|v| match v {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }))?;
+    }
+
+    csv.flush()?;
+    Ok(())
+}
+
+fn write_jsonl<W: Write>(table: &ExportTable, mut writer: W) -> Result<(), ExportError> {
+    for row in &table.rows {
+        // 列順を保つため Map を経由せずに書き出す
+        writer.write_all(b"{")?;
+        for (i, (column, value)) in table.columns.iter().zip(row).enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut writer, column.name)?;
+            writer.write_all(b":")?;
+            serde_json::to_writer(&mut writer, value)?;
+        }
+        writer.write_all(b"}\n")?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_parquet<W: Write + Send>(table: &ExportTable, writer: W) -> Result<(), ExportError> {
+    let schema = Arc::new(arrow_schema(table.columns));
+
+    let arrays = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| build_array(column, table.rows.iter().map(|row| &row[i])))
+        .collect::<Result<Vec<_>, _>>()?;
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut parquet = ArrowWriter::try_new(writer, schema, Some(props))?;
+    parquet.write(&batch)?;
+    parquet.close()?;
+
+    Ok(())
+}
+
+pub fn arrow_schema(columns: &[ExportColumn]) -> Schema {
+    Schema::new(
+        columns
+            .iter()
+            .map(|c| {
+                let data_type = match c.kind {
+                    ColumnKind::Int64 => DataType::Int64,
+                    ColumnKind::Float64 => DataType::Float64,
+                    ColumnKind::Text | ColumnKind::Json => DataType::Utf8,
+                    ColumnKind::Timestamp => {
+                        DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+                    }
+                    ColumnKind::Date => DataType::Date32,
+                };
+                Field::new(c.name, data_type, c.nullable)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn build_array<'a>(
+    column: &ExportColumn,
+    values: impl Iterator<Item = &'a Value>,
+) -> Result<ArrayRef, ExportError> {
+    let invalid = |v: &Value| ExportError::InvalidValue {
+        column: column.name,
+        value: v.to_string(),
+    };
+
+    let array: ArrayRef = match column.kind {
+        ColumnKind::Int64 => {
+            let mut b = Int64Builder::new();
+            for v in values {
+                match v {
+                    Value::Null => b.append_null(),
+                    v => b.append_value(v.as_i64().ok_or_else(|| invalid(v))?),
+                }
+            }
+            Arc::new(b.finish())
+        }
+        ColumnKind::Float64 => {
+            let mut b = Float64Builder::new();
+            for v in values {
+                match v {
+                    Value::Null => b.append_null(),
+                    v => b.append_value(v.as_f64().ok_or_else(|| invalid(v))?),
+                }
+            }
+            Arc::new(b.finish())
+        }
+        ColumnKind::Text => {
+            let mut b = StringBuilder::new();
+            for v in values {
+                match v {
+                    Value::Null => b.append_null(),
+                    v => b.append_value(v.as_str().ok_or_else(|| invalid(v))?),
+                }
+            }
+            Arc::new(b.finish())
+        }
+        ColumnKind::Json => {
+            let mut b = StringBuilder::new();
+            for v in values {
+                b.append_value(v.to_string());
+            }
+            Arc::new(b.finish())
+        }
+        ColumnKind::Timestamp => {
+            let mut b = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+            for v in values {
+                match v {
+                    Value::Null => b.append_null(),
+                    v => {
+                        let dt = v
+                            .as_str()
+                            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                            .ok_or_else(|| invalid(v))?;
+                        b.append_value(dt.timestamp_micros());
+                    }
+                }
+            }
+            Arc::new(b.finish())
+        }
+        ColumnKind::Date => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid epoch");
+            let mut b = Date32Builder::new();
+            for v in values {
+                match v {
+                    Value::Null => b.append_null(),
+                    v => {
+                        let date = v
+                            .as_str()
+                            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+                            .ok_or_else(|| invalid(v))?;
+                        b.append_value((date - epoch).num_days() as i32);
+                    }
+                }
+            }
+            Arc::new(b.finish())
+        }
+    };
+
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::export::columns;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use serde_json::json;
+
+    fn sample_table() -> ExportTable {
+        ExportTable {
+            columns: columns::RAW_VIEWS,
+            rows: vec![vec![
+                json!(1),
+                json!("12345"),
+                Value::Null,
+                json!("Engineer, \"Platform\""),
+                json!("今日"),
+                json!("2025-11-23T00:00:00Z"),
+                json!({ "userId": 12345 }),
+                json!("2025-11-23T01:02:03.456789Z"),
+            ]],
+        }
+    }
+
+    #[test]
+    fn csv_has_header_and_quotes_text() {
+        let mut out = Vec::new();
+        write_export_table(&sample_table(), ExportFormat::Csv, &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some(
+                "raw_profile_view_id,viewer_user_id,viewer_company_page_url,viewer_company_name_raw,viewed_at_raw,viewed_at,raw_json,created_at"
+            )
+        );
+        assert_eq!(
+            lines.next(),
+            Some(
+                r#"1,12345,,"Engineer, ""Platform""",今日,2025-11-23T00:00:00Z,"{""userId"":12345}",2025-11-23T01:02:03.456789Z"#
+            )
+        );
+    }
+
+    #[test]
+    fn jsonl_keeps_column_order() {
+        let mut out = Vec::new();
+        write_export_table(&sample_table(), ExportFormat::Jsonl, &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with(r#"{"raw_profile_view_id":1,"viewer_user_id":"12345","#));
+        assert!(text.ends_with("}\n"));
+
+        let parsed: Value = serde_json::from_str(text.trim_end()).unwrap();
+        assert_eq!(parsed["raw_json"]["userId"], 12345);
+    }
+
+    #[test]
+    fn parquet_round_trips_schema_and_rows() {
+        let file = tempfile::tempfile().unwrap();
+        write_export_table(
+            &sample_table(),
+            ExportFormat::Parquet,
+            file.try_clone().unwrap(),
+        )
+        .unwrap();
+
+        let reader = SerializedFileReader::new(file).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 1);
+
+        let names: Vec<_> = metadata
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect();
+        let expected: Vec<_> = columns::RAW_VIEWS.iter().map(|c| c.name).collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn invalid_timestamp_is_reported_with_column() {
+        let mut table = sample_table();
+        table.rows[0][5] = json!("yesterday");
+
+        let err = write_export_table(&table, ExportFormat::Parquet, Vec::new())
+            .expect_err("timestamp must be rfc3339");
+
+        assert!(matches!(
+            err,
+            ExportError::InvalidValue {
+                column: "viewed_at",
+                ..
+            }
+        ));
+    }
+}
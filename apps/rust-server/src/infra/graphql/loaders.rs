@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, HashMap};
+
+use async_graphql::{Error, dataloader::Loader};
+use sqlx::PgPool;
+use storage::wantedly::{
+    WantedlyCompany, WantedlyCompanyAttributes, WantedlyCompanyViewerRow, WantedlyImpression,
+    WantedlyProfileViewRaw, WantedlyViewer, list_companies_by_ids,
+    list_company_attributes_by_company_ids, list_company_viewers_by_company_ids,
+    list_impressions_by_company_ids, list_impressions_by_viewer_ids, list_profile_views_raw_by_ids,
+    list_viewers_by_ids,
+};
+
+/// 1 リクエストの間に集まったキーを種類ごとに 1 クエリで引く（N+1 を避ける）
+pub struct StorageLoader {
+    pool: PgPool,
+}
+
+impl StorageLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompanyId(pub i64);
+
+/// 会社 id で引く会社属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompanyAttributesOf(pub i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewerId(pub i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawViewId(pub i64);
+
+/// 親（会社・閲覧者）ごとの一覧のキー。limit は親 1 件あたりの上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompanyImpressions {
+    pub company_id: i64,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewerImpressions {
+    pub viewer_id: i64,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompanyViewers {
+    pub company_id: i64,
+    pub limit: i64,
+}
+
+/// 親の id を上限ごとにまとめる（同じフィールドなら上限はふつう 1 種類なので 1 クエリになる）
+pub fn group_by_limit(keys: impl IntoIterator<Item = (i64, i64)>) -> BTreeMap<i64, Vec<i64>> {
+    let mut groups: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for (parent_id, limit) in keys {
+        groups.entry(limit).or_default().push(parent_id);
+    }
+    groups
+}
+
+/// 行を親 id ごとに振り分ける（行のない親も空の一覧で返す）
+fn by_parent<T>(
+    parent_ids: &[i64],
+    rows: Vec<T>,
+    parent_of: impl Fn(&T) -> Option<i64>,
+) -> HashMap<i64, Vec<T>> {
+    let mut grouped: HashMap<i64, Vec<T>> = parent_ids.iter().map(|id| (*id, Vec::new())).collect();
+    for row in rows {
+        if let Some(list) = parent_of(&row).and_then(|id| grouped.get_mut(&id)) {
+            list.push(row);
+        }
+    }
+    grouped
+}
+
+impl Loader<CompanyId> for StorageLoader {
+    type Value = WantedlyCompany;
+    type Error = Error;
+
+    async fn load(&self, keys: &[CompanyId]) -> Result<HashMap<CompanyId, Self::Value>, Error> {
+        let ids: Vec<i64> = keys.iter().map(|key| key.0).collect();
+        let rows = list_companies_by_ids(&self.pool, &ids).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (CompanyId(row.id), row))
+            .collect())
+    }
+}
+
+impl Loader<CompanyAttributesOf> for StorageLoader {
+    type Value = WantedlyCompanyAttributes;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[CompanyAttributesOf],
+    ) -> Result<HashMap<CompanyAttributesOf, Self::Value>, Error> {
+        let ids: Vec<i64> = keys.iter().map(|key| key.0).collect();
+        let rows = list_company_attributes_by_company_ids(&self.pool, &ids).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (CompanyAttributesOf(row.company_id), row))
+            .collect())
+    }
+}
+
+impl Loader<ViewerId> for StorageLoader {
+    type Value = WantedlyViewer;
+    type Error = Error;
+
+    async fn load(&self, keys: &[ViewerId]) -> Result<HashMap<ViewerId, Self::Value>, Error> {
+        let ids: Vec<i64> = keys.iter().map(|key| key.0).collect();
+        let rows = list_viewers_by_ids(&self.pool, &ids).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (ViewerId(row.id), row))
+            .collect())
+    }
+}
+
+impl Loader<RawViewId> for StorageLoader {
+    type Value = WantedlyProfileViewRaw;
+    type Error = Error;
+
+    async fn load(&self, keys: &[RawViewId]) -> Result<HashMap<RawViewId, Self::Value>, Error> {
+        let ids: Vec<i64> = keys.iter().map(|key| key.0).collect();
+        let rows = list_profile_views_raw_by_ids(&self.pool, &ids).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (RawViewId(row.id), row))
+            .collect())
+    }
+}
+
+impl Loader<CompanyImpressions> for StorageLoader {
+    type Value = Vec<WantedlyImpression>;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[CompanyImpressions],
+    ) -> Result<HashMap<CompanyImpressions, Self::Value>, Error> {
+        let mut loaded = HashMap::new();
+        for (limit, ids) in group_by_limit(keys.iter().map(|k| (k.company_id, k.limit))) {
+            let rows = list_impressions_by_company_ids(&self.pool, &ids, limit).await?;
+            loaded.extend(
+                by_parent(&ids, rows, |row| row.company_id_at_view)
+                    .into_iter()
+                    .map(|(company_id, rows)| (CompanyImpressions { company_id, limit }, rows)),
+            );
+        }
+
+        Ok(loaded)
+    }
+}
+
+impl Loader<ViewerImpressions> for StorageLoader {
+    type Value = Vec<WantedlyImpression>;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[ViewerImpressions],
+    ) -> Result<HashMap<ViewerImpressions, Self::Value>, Error> {
+        let mut loaded = HashMap::new();
+        for (limit, ids) in group_by_limit(keys.iter().map(|k| (k.viewer_id, k.limit))) {
+            let rows = list_impressions_by_viewer_ids(&self.pool, &ids, limit).await?;
+            loaded.extend(
+                by_parent(&ids, rows, |row| Some(row.viewer_id))
+                    .into_iter()
+                    .map(|(viewer_id, rows)| (ViewerImpressions { viewer_id, limit }, rows)),
+            );
+        }
+
+        Ok(loaded)
+    }
+}
+
+impl Loader<CompanyViewers> for StorageLoader {
+    type Value = Vec<WantedlyCompanyViewerRow>;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[CompanyViewers],
+    ) -> Result<HashMap<CompanyViewers, Self::Value>, Error> {
+        let mut loaded = HashMap::new();
+        for (limit, ids) in group_by_limit(keys.iter().map(|k| (k.company_id, k.limit))) {
+            let rows = list_company_viewers_by_company_ids(&self.pool, &ids, limit).await?;
+            loaded.extend(
+                by_parent(&ids, rows, |row| Some(row.company_id))
+                    .into_iter()
+                    .map(|(company_id, rows)| (CompanyViewers { company_id, limit }, rows)),
+            );
+        }
+
+        Ok(loaded)
+    }
+}
@@ -0,0 +1,231 @@
+use async_graphql::{Context, Error, Object, Result};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use storage::wantedly::{
+    WantedlyKeysetCursor, WantedlyViewQuery, count_impressions, count_profile_views_raw,
+    find_company_by_slug, find_viewer_by_source_user_id, list_companies, list_company_leaderboard,
+    list_impressions_page, list_profile_views_raw_page, list_view_time_series, summarize_views,
+};
+
+use crate::infra::{
+    analytics::{
+        leaderboard::CompanyLeaderboardEntry,
+        period::{JstPeriod, jst_today},
+    },
+    graphql::types::{
+        Company, Impression, LeaderboardEntry, LeaderboardSort, Page, RawView, SortOrder,
+        TimeBucket, ViewBucket, ViewFilter, ViewSummary, Viewer,
+    },
+    pagination::{self, decode_cursor},
+};
+
+// 期間の既定と上限は REST の同じ集計にそろえる
+const LEADERBOARD_DEFAULT_DAYS: u32 = 30;
+const LEADERBOARD_MAX_DAYS: u32 = 366;
+const SERIES_DEFAULT_DAYS: u32 = 90;
+const SERIES_MAX_DAYS: u32 = 1100;
+
+fn pool<'a>(ctx: &Context<'a>) -> &'a PgPool {
+    ctx.data_unchecked::<PgPool>()
+}
+
+fn resolve_period(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    default_days: u32,
+    max_days: u32,
+) -> Result<JstPeriod> {
+    Ok(JstPeriod::resolve(
+        from,
+        to,
+        default_days,
+        max_days,
+        jst_today(),
+    )?)
+}
+
+fn view_query(
+    filter: ViewFilter,
+    order: SortOrder,
+    after: Option<String>,
+) -> Result<WantedlyViewQuery> {
+    if let (Some(from), Some(to)) = (filter.from, filter.to)
+        && from > to
+    {
+        return Err(Error::new("`from` must not be after `to`"));
+    }
+
+    let after = after
+        .as_deref()
+        .map(|c| decode_cursor(c).ok_or_else(|| Error::new("invalid cursor")))
+        .transpose()?;
+
+    Ok(WantedlyViewQuery {
+        from: filter.from,
+        to: filter.to,
+        company_slug: filter.company.filter(|c| !c.trim().is_empty()),
+        viewer_user_id: filter.viewer.filter(|v| !v.trim().is_empty()),
+        affiliation_kind: filter.affiliation_kind.map(Into::into),
+        order: order.into(),
+        after,
+    })
+}
+
+fn into_page<R, T: async_graphql::OutputType>(
+    rows: Vec<R>,
+    first: i32,
+    total: i64,
+    key: impl Fn(&R) -> WantedlyKeysetCursor,
+    item: impl Fn(R) -> T,
+) -> Page<T> {
+    let page = pagination::Page::from_overfetched(rows, first as usize, total, key);
+
+    Page {
+        items: page.items.into_iter().map(item).collect(),
+        total_count: page.total,
+        next_cursor: page.next_cursor,
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// slug で会社を引く
+    async fn company(&self, ctx: &Context<'_>, slug: String) -> Result<Option<Company>> {
+        Ok(find_company_by_slug(pool(ctx), &slug).await?.map(Company))
+    }
+
+    /// 会社の一覧（slug 順。after に前のページの最後の slug を渡すと続きから）
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn companies(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 50, validator(minimum = 1, maximum = 500))] first: i32,
+        after: Option<String>,
+    ) -> Result<Vec<Company>> {
+        let rows = list_companies(pool(ctx), after.as_deref(), first.into()).await?;
+
+        Ok(rows.into_iter().map(Company).collect())
+    }
+
+    /// Wantedly の userId で閲覧者を引く
+    async fn viewer(&self, ctx: &Context<'_>, source_user_id: String) -> Result<Option<Viewer>> {
+        let viewer = find_viewer_by_source_user_id(pool(ctx), &source_user_id).await?;
+
+        Ok(viewer.map(Viewer))
+    }
+
+    /// インプレッションの一覧（閲覧日時順のキーセットページング）
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn impressions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] filter: ViewFilter,
+        #[graphql(default)] order: SortOrder,
+        #[graphql(default = 50, validator(minimum = 1, maximum = 500))] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<Impression>> {
+        let query = view_query(filter, order, after)?;
+
+        let rows = list_impressions_page(pool(ctx), &query, i64::from(first) + 1).await?;
+        let total = count_impressions(pool(ctx), &query).await?;
+
+        Ok(into_page(
+            rows,
+            first,
+            total,
+            |row| WantedlyKeysetCursor {
+                at: row.impressed_at,
+                id: row.id,
+            },
+            Impression,
+        ))
+    }
+
+    /// raw レコードの一覧（絞り込みは impressions と同じ。日付は viewedAt に掛かる）
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn raw_views(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] filter: ViewFilter,
+        #[graphql(default)] order: SortOrder,
+        #[graphql(default = 50, validator(minimum = 1, maximum = 500))] first: i32,
+        after: Option<String>,
+    ) -> Result<Page<RawView>> {
+        let query = view_query(filter, order, after)?;
+
+        let rows = list_profile_views_raw_page(pool(ctx), &query, i64::from(first) + 1).await?;
+        let total = count_profile_views_raw(pool(ctx), &query).await?;
+
+        Ok(into_page(
+            rows,
+            first,
+            total,
+            |row| WantedlyKeysetCursor {
+                at: row.viewed_at,
+                id: row.id,
+            },
+            RawView,
+        ))
+    }
+
+    /// 会社ランキング（期間の省略時は今日（JST）までの 30 日）
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn company_leaderboard(
+        &self,
+        ctx: &Context<'_>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        #[graphql(default)] sort: LeaderboardSort,
+        #[graphql(default)] order: SortOrder,
+        #[graphql(default = 50, validator(minimum = 1, maximum = 500))] first: i32,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let period = resolve_period(from, to, LEADERBOARD_DEFAULT_DAYS, LEADERBOARD_MAX_DAYS)?;
+
+        let rows = list_company_leaderboard(
+            pool(ctx),
+            period.from,
+            period.to,
+            sort.into(),
+            order.into(),
+            first.into(),
+        )
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CompanyLeaderboardEntry::from(row).into())
+            .collect())
+    }
+
+    /// 閲覧数の推移（期間の省略時は今日（JST）までの 90 日。閲覧のない区間も 0 で返す）
+    async fn view_time_series(
+        &self,
+        ctx: &Context<'_>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        #[graphql(default)] granularity: TimeBucket,
+    ) -> Result<Vec<ViewBucket>> {
+        let period = resolve_period(from, to, SERIES_DEFAULT_DAYS, SERIES_MAX_DAYS)?;
+
+        let buckets =
+            list_view_time_series(pool(ctx), period.from, period.to, granularity.into()).await?;
+
+        Ok(buckets.into_iter().map(ViewBucket::from).collect())
+    }
+
+    /// 期間全体の閲覧数・閲覧者数・会社数（期間の省略時は今日（JST）までの 30 日）
+    async fn view_summary(
+        &self,
+        ctx: &Context<'_>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<ViewSummary> {
+        let period = resolve_period(from, to, LEADERBOARD_DEFAULT_DAYS, SERIES_MAX_DAYS)?;
+
+        let totals = summarize_views(pool(ctx), period.from, period.to).await?;
+
+        Ok(ViewSummary::new(period.from, period.to, totals))
+    }
+}
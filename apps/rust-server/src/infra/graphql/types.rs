@@ -0,0 +1,476 @@
+use async_graphql::{
+    ComplexObject, Context, Enum, Error, InputObject, Json, Object, OutputType, Result,
+    SimpleObject, dataloader::DataLoader,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use storage::wantedly::{
+    WantedlyCompany, WantedlyCompanyAttributes, WantedlyCompanyViewerRow, WantedlyImpression,
+    WantedlyProfileViewRaw, WantedlyViewBucket, WantedlyViewTotals, WantedlyViewer,
+};
+
+use crate::infra::{
+    analytics::leaderboard::CompanyLeaderboardEntry,
+    graphql::loaders::{
+        CompanyAttributesOf, CompanyId, CompanyImpressions, CompanyViewers, RawViewId,
+        StorageLoader, ViewerId, ViewerImpressions,
+    },
+};
+
+fn loader<'a>(ctx: &Context<'a>) -> &'a DataLoader<StorageLoader> {
+    ctx.data_unchecked::<DataLoader<StorageLoader>>()
+}
+
+async fn load_company(ctx: &Context<'_>, id: i64) -> Result<Company> {
+    loader(ctx)
+        .load_one(CompanyId(id))
+        .await?
+        .map(Company)
+        .ok_or_else(|| Error::new(format!("company not found: {id}")))
+}
+
+async fn load_viewer(ctx: &Context<'_>, id: i64) -> Result<Viewer> {
+    loader(ctx)
+        .load_one(ViewerId(id))
+        .await?
+        .map(Viewer)
+        .ok_or_else(|| Error::new(format!("viewer not found: {id}")))
+}
+
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "storage::wantedly::CompanyAttributeSource")]
+pub enum AttributeSource {
+    Ai,
+    /// 手入力。AI による推定で上書きしない
+    Manual,
+}
+
+/// 従業員数の区分
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "storage::wantedly::CompanyEmployeeBand")]
+pub enum EmployeeBand {
+    #[graphql(name = "SIZE_1_10")]
+    E1To10,
+    #[graphql(name = "SIZE_11_50")]
+    E11To50,
+    #[graphql(name = "SIZE_51_200")]
+    E51To200,
+    #[graphql(name = "SIZE_201_500")]
+    E201To500,
+    #[graphql(name = "SIZE_501_1000")]
+    E501To1000,
+    #[graphql(name = "SIZE_1001_5000")]
+    E1001To5000,
+    #[graphql(name = "SIZE_5001_PLUS")]
+    E5001Plus,
+}
+
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "storage::wantedly::CompanyFundingStage")]
+pub enum FundingStage {
+    Bootstrapped,
+    Seed,
+    SeriesA,
+    SeriesB,
+    SeriesCPlus,
+    Public,
+    Acquired,
+}
+
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "storage::wantedly::ViewerAffiliationKind")]
+pub enum AffiliationKind {
+    Company,
+    Freeform,
+    Unknown,
+}
+
+#[derive(Enum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[graphql(remote = "storage::wantedly::WantedlySortOrder")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Enum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[graphql(remote = "storage::wantedly::WantedlyLeaderboardSort")]
+pub enum LeaderboardSort {
+    #[default]
+    Views,
+    DistinctViewers,
+    FirstViewedAt,
+    LastViewedAt,
+    /// 直前の同じ長さの期間からの閲覧数の増減
+    Trend,
+}
+
+#[derive(Enum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[graphql(remote = "storage::wantedly::WantedlyTimeBucket")]
+pub enum TimeBucket {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+pub struct Company(pub WantedlyCompany);
+
+#[Object]
+impl Company {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    async fn slug(&self) -> &str {
+        &self.0.company_slug
+    }
+
+    async fn page_url(&self) -> &str {
+        &self.0.company_page_url
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    /// 会社属性（未設定なら null）
+    async fn attributes(&self, ctx: &Context<'_>) -> Result<Option<CompanyAttributes>> {
+        let attributes = loader(ctx).load_one(CompanyAttributesOf(self.0.id)).await?;
+
+        Ok(attributes.map(CompanyAttributes))
+    }
+
+    /// 閲覧時点でこの会社に所属していた閲覧者からの閲覧（新しい順）
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn impressions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: i32,
+    ) -> Result<Vec<Impression>> {
+        let key = CompanyImpressions {
+            company_id: self.0.id,
+            limit: first.into(),
+        };
+        let rows = loader(ctx).load_one(key).await?.unwrap_or_default();
+
+        Ok(rows.into_iter().map(Impression).collect())
+    }
+
+    /// 閲覧時点でこの会社に所属していた閲覧者（最後の閲覧が新しい順）
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn viewers(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: i32,
+    ) -> Result<Vec<CompanyViewer>> {
+        let key = CompanyViewers {
+            company_id: self.0.id,
+            limit: first.into(),
+        };
+        let rows = loader(ctx).load_one(key).await?.unwrap_or_default();
+
+        Ok(rows.into_iter().map(CompanyViewer).collect())
+    }
+}
+
+pub struct CompanyAttributes(pub WantedlyCompanyAttributes);
+
+#[Object]
+impl CompanyAttributes {
+    async fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    async fn domain(&self) -> Option<&str> {
+        self.0.domain.as_deref()
+    }
+
+    async fn industry(&self) -> Option<&str> {
+        self.0.industry.as_deref()
+    }
+
+    async fn employee_band(&self) -> Option<EmployeeBand> {
+        self.0.employee_band.map(Into::into)
+    }
+
+    async fn funding_stage(&self) -> Option<FundingStage> {
+        self.0.funding_stage.map(Into::into)
+    }
+
+    async fn location(&self) -> Option<&str> {
+        self.0.location.as_deref()
+    }
+
+    async fn source(&self) -> AttributeSource {
+        self.0.source.into()
+    }
+
+    /// AI による推定の確からしさ（手入力なら null）
+    async fn confidence(&self) -> Option<f32> {
+        self.0.confidence
+    }
+
+    /// AI による推定のプロンプトの版（手入力なら null）
+    async fn prompt_version(&self) -> Option<&str> {
+        self.0.prompt_version.as_deref()
+    }
+
+    /// AI による推定に使ったモデル（手入力なら null）
+    async fn model(&self) -> Option<&str> {
+        self.0.model.as_deref()
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.updated_at
+    }
+}
+
+pub struct Viewer(pub WantedlyViewer);
+
+#[Object]
+impl Viewer {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    /// Wantedly の userId（暗号化時はブラインドインデックス）
+    async fn source_user_id(&self) -> &str {
+        &self.0.source_user_id
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    /// 最新の所属履歴の会社
+    async fn current_company(&self, ctx: &Context<'_>) -> Result<Option<Company>> {
+        match self.0.company_id {
+            Some(id) => load_company(ctx, id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// この閲覧者の閲覧（新しい順）
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn impressions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: i32,
+    ) -> Result<Vec<Impression>> {
+        let key = ViewerImpressions {
+            viewer_id: self.0.id,
+            limit: first.into(),
+        };
+        let rows = loader(ctx).load_one(key).await?.unwrap_or_default();
+
+        Ok(rows.into_iter().map(Impression).collect())
+    }
+}
+
+/// 会社から見た閲覧者と、その閲覧者からの閲覧数
+pub struct CompanyViewer(pub WantedlyCompanyViewerRow);
+
+#[Object]
+impl CompanyViewer {
+    async fn viewer(&self, ctx: &Context<'_>) -> Result<Viewer> {
+        load_viewer(ctx, self.0.viewer_id).await
+    }
+
+    async fn views(&self) -> i64 {
+        self.0.views
+    }
+
+    async fn first_viewed_at(&self) -> DateTime<Utc> {
+        self.0.first_viewed_at
+    }
+
+    async fn last_viewed_at(&self) -> DateTime<Utc> {
+        self.0.last_viewed_at
+    }
+}
+
+pub struct Impression(pub WantedlyImpression);
+
+#[Object]
+impl Impression {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    async fn impressed_at(&self) -> DateTime<Utc> {
+        self.0.impressed_at
+    }
+
+    async fn viewer(&self, ctx: &Context<'_>) -> Result<Viewer> {
+        load_viewer(ctx, self.0.viewer_id).await
+    }
+
+    /// 閲覧時点の所属会社（会社として特定できなければ null）
+    async fn company(&self, ctx: &Context<'_>) -> Result<Option<Company>> {
+        match self.0.company_id_at_view {
+            Some(id) => load_company(ctx, id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 元になった raw レコード
+    async fn raw_view(&self, ctx: &Context<'_>) -> Result<RawView> {
+        let id = self.0.raw_profile_view_id;
+
+        loader(ctx)
+            .load_one(RawViewId(id))
+            .await?
+            .map(RawView)
+            .ok_or_else(|| Error::new(format!("raw view not found: {id}")))
+    }
+}
+
+pub struct RawView(pub WantedlyProfileViewRaw);
+
+#[Object]
+impl RawView {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    /// 閲覧者の userId（ブラインドインデックス）
+    async fn viewer_user_id(&self) -> &str {
+        &self.0.viewer_user_id
+    }
+
+    async fn viewer_company_page_url(&self) -> Option<&str> {
+        self.0.viewer_company_page_url.as_deref()
+    }
+
+    async fn viewer_company_name_raw(&self) -> Option<&str> {
+        self.0.viewer_company_name_raw.as_deref()
+    }
+
+    async fn viewed_at_raw(&self) -> &str {
+        &self.0.viewed_at_raw
+    }
+
+    async fn viewed_at(&self) -> DateTime<Utc> {
+        self.0.viewed_at
+    }
+
+    /// 取り込んだ node（保持期間を過ぎた行は削った後の値）
+    async fn raw_json(&self) -> Json<&serde_json::Value> {
+        Json(&self.0.raw_json)
+    }
+
+    async fn raw_json_compacted_at(&self) -> Option<DateTime<Utc>> {
+        self.0.raw_json_compacted_at
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+}
+
+/// 一覧の 1 ページ（nextCursor が null なら最後のページ）
+#[derive(SimpleObject)]
+#[graphql(concrete(name = "ImpressionPage", params(Impression)))]
+#[graphql(concrete(name = "RawViewPage", params(RawView)))]
+pub struct Page<T: OutputType> {
+    pub items: Vec<T>,
+    pub total_count: i64,
+    pub next_cursor: Option<String>,
+}
+
+/// インプレッション / raw 一覧の絞り込み（日付は JST の暦日、両端を含む）
+#[derive(InputObject, Debug, Default)]
+pub struct ViewFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// 会社の slug
+    pub company: Option<String>,
+    /// 閲覧者の userId
+    pub viewer: Option<String>,
+    pub affiliation_kind: Option<AffiliationKind>,
+}
+
+/// 会社ランキングの 1 行（previous* は直前の同じ長さの期間）
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct LeaderboardEntry {
+    #[graphql(skip)]
+    pub company_id: i64,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub first_viewed_at: DateTime<Utc>,
+    pub last_viewed_at: DateTime<Utc>,
+    pub previous_views: i64,
+    pub previous_distinct_viewers: i64,
+    pub views_delta: i64,
+    /// 前期間の閲覧が 0 なら null
+    pub views_change_ratio: Option<f64>,
+}
+
+#[ComplexObject]
+impl LeaderboardEntry {
+    async fn company(&self, ctx: &Context<'_>) -> Result<Company> {
+        load_company(ctx, self.company_id).await
+    }
+}
+
+impl From<CompanyLeaderboardEntry> for LeaderboardEntry {
+    fn from(entry: CompanyLeaderboardEntry) -> Self {
+        let row = entry.company;
+        Self {
+            company_id: row.company_id,
+            views: row.views,
+            distinct_viewers: row.distinct_viewers,
+            first_viewed_at: row.first_viewed_at,
+            last_viewed_at: row.last_viewed_at,
+            previous_views: row.previous_views,
+            previous_distinct_viewers: row.previous_distinct_viewers,
+            views_delta: entry.views_delta,
+            views_change_ratio: entry.views_change_ratio,
+        }
+    }
+}
+
+/// 区間ごとの閲覧数（bucketStart〜bucketEnd は JST の暦日、両端を含む）
+#[derive(SimpleObject)]
+pub struct ViewBucket {
+    pub bucket_start: NaiveDate,
+    pub bucket_end: NaiveDate,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub distinct_companies: i64,
+}
+
+impl From<WantedlyViewBucket> for ViewBucket {
+    fn from(bucket: WantedlyViewBucket) -> Self {
+        Self {
+            bucket_start: bucket.bucket_start,
+            bucket_end: bucket.bucket_end,
+            views: bucket.views,
+            distinct_viewers: bucket.distinct_viewers,
+            distinct_companies: bucket.distinct_companies,
+        }
+    }
+}
+
+/// 期間全体の閲覧数
+#[derive(SimpleObject)]
+pub struct ViewSummary {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub distinct_companies: i64,
+}
+
+impl ViewSummary {
+    pub fn new(from: NaiveDate, to: NaiveDate, totals: WantedlyViewTotals) -> Self {
+        Self {
+            from,
+            to,
+            views: totals.views,
+            distinct_viewers: totals.distinct_viewers,
+            distinct_companies: totals.distinct_companies,
+        }
+    }
+}
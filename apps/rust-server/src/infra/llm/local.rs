@@ -0,0 +1,83 @@
+use futures_util::{StreamExt, future::BoxFuture};
+
+use super::{ChatMessage, ChatRole, LlmClient, LlmError, TextStream};
+
+pub const LOCAL_LLM_MODEL: &str = "local-extractive-v1";
+
+/// 外部 API を使わない決定的な応答
+///
+/// 最後のユーザー発話から `[n] ` で始まる行（RAG のプロンプトの資料の見出し）を拾い、
+/// そのまま列挙する。生成の質ではなく、検索・引用・ストリーミングの経路を確かめるためのもの。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalLlmClient;
+
+impl LlmClient for LocalLlmClient {
+    fn model(&self) -> &str {
+        LOCAL_LLM_MODEL
+    }
+
+    fn stream_chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+    ) -> BoxFuture<'a, Result<TextStream, LlmError>> {
+        let lines = answer_lines(messages);
+        Box::pin(async move { Ok(futures_util::stream::iter(lines.into_iter().map(Ok)).boxed()) })
+    }
+}
+
+fn answer_lines(messages: &[ChatMessage]) -> Vec<String> {
+    let prompt = messages
+        .iter()
+        .rev()
+        .find(|m| m.role == ChatRole::User)
+        .map(|m| m.content.as_str())
+        .unwrap_or_default();
+
+    let cited: Vec<&str> = prompt
+        .lines()
+        .filter(|line| is_citation_heading(line))
+        .collect();
+    if cited.is_empty() {
+        return vec!["資料からは分かりません。".to_string()];
+    }
+
+    std::iter::once("関連する資料は次のとおりです。\n".to_string())
+        .chain(cited.iter().map(|line| format!("- {line}\n")))
+        .collect()
+}
+
+fn is_citation_heading(line: &str) -> bool {
+    line.strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+        .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::llm::complete_chat;
+
+    #[tokio::test]
+    async fn answer_lists_cited_headings() {
+        let messages = vec![
+            ChatMessage::system("[9] system lines are ignored"),
+            ChatMessage::user("質問\n\n[1] 会社: co_a\n本文\n[x] not a citation\n[2] メモ: 面談"),
+        ];
+
+        let answer = complete_chat(&LocalLlmClient, &messages).await.unwrap();
+
+        assert_eq!(
+            answer,
+            "関連する資料は次のとおりです。\n- [1] 会社: co_a\n- [2] メモ: 面談\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn answer_without_sources_says_unknown() {
+        let answer = complete_chat(&LocalLlmClient, &[ChatMessage::user("質問")])
+            .await
+            .unwrap();
+
+        assert_eq!(answer, "資料からは分かりません。");
+    }
+}
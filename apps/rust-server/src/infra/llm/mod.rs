@@ -0,0 +1,148 @@
+use std::{sync::Arc, time::Duration};
+
+use futures_util::{StreamExt, future::BoxFuture, stream::BoxStream};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub mod local;
+pub mod openai;
+pub mod recorded;
+pub mod sse;
+
+use local::LocalLlmClient;
+use openai::OpenAiLlmClient;
+
+#[derive(Debug, Error)]
+pub enum LlmError {
+    #[error("LLM request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("LLM API returned {status}: {body}")]
+    Api { status: u16, body: String },
+
+    #[error("invalid LLM response: {0}")]
+    InvalidResponse(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::System,
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::User,
+            content: content.into(),
+        }
+    }
+}
+
+/// 生成された文の断片のストリーム
+pub type TextStream = BoxStream<'static, Result<String, LlmError>>;
+
+/// チャット形式の LLM
+pub trait LlmClient: Send + Sync {
+    /// 記録に残すモデル名
+    fn model(&self) -> &str;
+
+    /// 応答を断片ごとに流す（接続・認証の失敗は最初の Future で返す）
+    fn stream_chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+    ) -> BoxFuture<'a, Result<TextStream, LlmError>>;
+}
+
+/// 応答を最後まで受け取ってつなげる
+pub async fn complete_chat(
+    client: &dyn LlmClient,
+    messages: &[ChatMessage],
+) -> Result<String, LlmError> {
+    let mut stream = client.stream_chat(messages).await?;
+    let mut text = String::new();
+    while let Some(delta) = stream.next().await {
+        text.push_str(&delta?);
+    }
+    Ok(text)
+}
+
+/// プロンプトに入れる文を max 文字で切る（切ったら … を付ける）
+pub fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LlmProvider {
+    /// 外部 API を使わない決定的な応答（テスト・オフライン用）
+    #[default]
+    Local,
+    /// OpenAI 互換の /chat/completions
+    OpenAi,
+}
+
+impl std::str::FromStr for LlmProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "local" => Ok(LlmProvider::Local),
+            "openai" => Ok(LlmProvider::OpenAi),
+            other => Err(format!(
+                "unknown LLM_PROVIDER: {other} (expected local or openai)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LlmConfig {
+    pub provider: LlmProvider,
+    /// OpenAI 互換 API のベース URL（`/chat/completions` の手前まで）
+    pub api_base: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    /// 応答の読み取りが止まってから打ち切るまでの時間（None なら既定の 60 秒）
+    pub read_timeout: Option<Duration>,
+}
+
+pub fn build_llm_client(config: &LlmConfig) -> Arc<dyn LlmClient> {
+    match config.provider {
+        LlmProvider::Local => Arc::new(LocalLlmClient),
+        LlmProvider::OpenAi => Arc::new(OpenAiLlmClient::new(
+            &config.api_base,
+            config.api_key.clone(),
+            &config.model,
+            config.read_timeout.unwrap_or(openai::DEFAULT_READ_TIMEOUT),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_text_is_truncated_on_char_boundary() {
+        assert_eq!(truncate_chars("あいうえお", 3), "あいう…");
+        assert_eq!(truncate_chars("あい", 3), "あい");
+    }
+}
@@ -0,0 +1,304 @@
+use std::{collections::VecDeque, time::Duration};
+
+use futures_util::{Stream, StreamExt, future::BoxFuture};
+use serde::{Deserialize, Serialize};
+
+use super::{ChatMessage, LlmClient, LlmError, TextStream, sse::SseDataDecoder};
+
+/// 接続の待ち時間の上限
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 応答の読み取りが止まってから打ち切るまでの既定の時間（ストリーム全体の長さは制限しない）
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// OpenAI 互換の `POST {api_base}/chat/completions`（`stream: true`）
+#[derive(Debug, Clone)]
+pub struct OpenAiLlmClient {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    /// 評価で比べられるよう、揺れを抑える
+    temperature: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunk {
+    choices: Vec<ChatChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunkChoice {
+    #[serde(default)]
+    delta: ChatDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatDelta {
+    content: Option<String>,
+}
+
+impl OpenAiLlmClient {
+    pub fn new(
+        api_base: &str,
+        api_key: Option<String>,
+        model: &str,
+        read_timeout: Duration,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(read_timeout)
+            .build()
+            .expect("http client builds with timeouts only");
+
+        Self {
+            client,
+            endpoint: format!("{}/chat/completions", api_base.trim_end_matches('/')),
+            api_key,
+            model: model.to_string(),
+        }
+    }
+
+    async fn request(&self, messages: &[ChatMessage]) -> Result<TextStream, LlmError> {
+        let mut request = self.client.post(&self.endpoint).json(&ChatRequest {
+            model: &self.model,
+            messages,
+            stream: true,
+            temperature: 0.0,
+        });
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(LlmError::Api {
+                status: status.as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        Ok(deltas(response.bytes_stream().boxed()).boxed())
+    }
+}
+
+impl LlmClient for OpenAiLlmClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn stream_chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+    ) -> BoxFuture<'a, Result<TextStream, LlmError>> {
+        Box::pin(self.request(messages))
+    }
+}
+
+struct DeltaState<S> {
+    bytes: S,
+    decoder: SseDataDecoder,
+    pending: VecDeque<String>,
+    done: bool,
+}
+
+/// SSE の各 `data:`（チャンクの JSON）から `choices[0].delta.content` を取り出す。`[DONE]` で終わる
+fn deltas<S, B>(bytes: S) -> impl Stream<Item = Result<String, LlmError>> + Send + 'static
+where
+    S: Stream<Item = Result<B, reqwest::Error>> + Send + Unpin + 'static,
+    B: AsRef<[u8]>,
+{
+    let state = DeltaState {
+        bytes,
+        decoder: SseDataDecoder::default(),
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    futures_util::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(data) = state.pending.pop_front() {
+                if data == "[DONE]" {
+                    return None;
+                }
+                match parse_delta(&data) {
+                    Ok(Some(text)) => return Some((Ok(text), state)),
+                    Ok(None) => continue,
+                    Err(e) => {
+                        state.pending.clear();
+                        state.done = true;
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+            if state.done {
+                return None;
+            }
+
+            match state.bytes.next().await {
+                Some(Ok(chunk)) => {
+                    let events = state.decoder.push(chunk.as_ref());
+                    state.pending.extend(events);
+                }
+                Some(Err(e)) => {
+                    state.done = true;
+                    return Some((Err(LlmError::Http(e)), state));
+                }
+                None => {
+                    state.done = true;
+                    state.pending.extend(state.decoder.finish());
+                }
+            }
+        }
+    })
+}
+
+fn parse_delta(data: &str) -> Result<Option<String>, LlmError> {
+    let chunk: ChatChunk = serde_json::from_str(data)
+        .map_err(|e| LlmError::InvalidResponse(format!("{e}: {data}")))?;
+
+    Ok(chunk
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|c| c.delta.content)
+        .filter(|text| !text.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::llm::complete_chat;
+    use axum::{
+        Json, Router,
+        body::Body,
+        http::{HeaderMap, StatusCode, header},
+        response::{IntoResponse, Response},
+        routing::post,
+    };
+    use serde_json::Value;
+
+    /// 最後のユーザー発話を 2 つの断片に分け、SSE の行をわざとチャンクの途中で切って返すモック
+    async fn spawn_mock() -> String {
+        let app = Router::new()
+            .route(
+                "/stall/chat/completions",
+                post(|| async {
+                    let first = futures_util::stream::once(async {
+                        Ok::<_, std::io::Error>(
+                            "data: {\"choices\":[{\"delta\":{\"content\":\"a\"}}]}\n\n",
+                        )
+                    });
+                    Response::builder()
+                        .header(header::CONTENT_TYPE, "text/event-stream")
+                        .body(Body::from_stream(
+                            first.chain(futures_util::stream::pending()),
+                        ))
+                        .unwrap()
+                }),
+            )
+            .route(
+            "/v1/chat/completions",
+            post(|headers: HeaderMap, Json(body): Json<Value>| async move {
+                if headers.get("authorization").and_then(|v| v.to_str().ok())
+                    != Some("Bearer secret")
+                {
+                    return (StatusCode::UNAUTHORIZED, "no key").into_response();
+                }
+                assert_eq!(body["model"], "test-model");
+                assert_eq!(body["stream"], true);
+
+                let question = body["messages"]
+                    .as_array()
+                    .and_then(|m| m.last())
+                    .and_then(|m| m["content"].as_str())
+                    .unwrap()
+                    .to_string();
+                let (head, tail) = question.split_at(question.len() / 2);
+                let sse = format!(
+                    "data: {}\n\ndata: {{\"choices\":[{{\"delta\":{{}}}}]}}\n\ndata: {}\n\ndata: [DONE]\n\n",
+                    serde_json::json!({ "choices": [{ "delta": { "role": "assistant", "content": head } }] }),
+                    serde_json::json!({ "choices": [{ "delta": { "content": tail } }] }),
+                );
+                let bytes = sse.into_bytes();
+                let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+                    bytes.chunks(7).map(|c| Ok(c.to_vec())).collect();
+
+                Response::builder()
+                    .header(header::CONTENT_TYPE, "text/event-stream")
+                    .body(Body::from_stream(futures_util::stream::iter(chunks)))
+                    .unwrap()
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{addr}/v1")
+    }
+
+    #[tokio::test]
+    async fn streamed_deltas_are_joined_in_order() {
+        let base = spawn_mock().await;
+        let client = OpenAiLlmClient::new(
+            &base,
+            Some("secret".into()),
+            "test-model",
+            DEFAULT_READ_TIMEOUT,
+        );
+        let messages = vec![ChatMessage::system("答える"), ChatMessage::user("abcdef")];
+
+        let deltas: Vec<String> = client
+            .stream_chat(&messages)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(deltas, vec!["abc", "def"]);
+
+        assert_eq!(complete_chat(&client, &messages).await.unwrap(), "abcdef");
+    }
+
+    #[tokio::test]
+    async fn api_errors_are_returned_before_streaming() {
+        let base = spawn_mock().await;
+        let client = OpenAiLlmClient::new(&base, None, "test-model", DEFAULT_READ_TIMEOUT);
+
+        let err = client
+            .stream_chat(&[ChatMessage::user("q")])
+            .await
+            .err()
+            .unwrap();
+
+        assert!(matches!(err, LlmError::Api { status: 401, .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn stalled_streams_time_out() {
+        let base = spawn_mock().await.replace("/v1", "/stall");
+        let client = OpenAiLlmClient::new(&base, None, "test-model", Duration::from_millis(200));
+
+        let mut stream = client.stream_chat(&[ChatMessage::user("q")]).await.unwrap();
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), "a");
+        match stream.next().await.unwrap() {
+            Err(LlmError::Http(e)) => assert!(e.is_timeout(), "{e}"),
+            other => panic!("unexpected item: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn malformed_chunks_are_rejected() {
+        assert!(parse_delta("{").is_err());
+        assert_eq!(parse_delta(r#"{"choices":[]}"#).unwrap(), None);
+    }
+}
@@ -0,0 +1,163 @@
+use std::{collections::BTreeMap, fs, io, path::Path, sync::Arc, sync::Mutex};
+
+use futures_util::{StreamExt, future::BoxFuture};
+use serde::{Deserialize, Serialize};
+
+use super::{ChatMessage, LlmClient, LlmError, TextStream, complete_chat};
+use crate::infra::digest::sha256_hex;
+
+/// プロンプトごとに記録した応答（評価を LLM なしで再現するためのもの）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmRecordings {
+    /// 記録したときのモデル
+    pub model: String,
+    /// prompt_key → 応答の全文
+    pub responses: BTreeMap<String, String>,
+}
+
+impl LlmRecordings {
+    /// ファイルがなければ None
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// メッセージ列の SHA-256（プロンプトが 1 文字でも変われば別のキーになる）
+pub fn prompt_key(messages: &[ChatMessage]) -> String {
+    let json = serde_json::to_vec(messages).unwrap_or_default();
+    sha256_hex(&json)
+}
+
+/// 記録した応答を返す。記録にないプロンプトはエラーにする
+#[derive(Debug, Clone)]
+pub struct ReplayLlmClient {
+    recordings: LlmRecordings,
+}
+
+impl ReplayLlmClient {
+    pub fn new(recordings: LlmRecordings) -> Self {
+        Self { recordings }
+    }
+}
+
+impl LlmClient for ReplayLlmClient {
+    fn model(&self) -> &str {
+        &self.recordings.model
+    }
+
+    fn stream_chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+    ) -> BoxFuture<'a, Result<TextStream, LlmError>> {
+        let key = prompt_key(messages);
+        let response = self.recordings.responses.get(&key).cloned();
+        Box::pin(async move {
+            let text = response.ok_or_else(|| {
+                LlmError::InvalidResponse(format!(
+                    "no recorded response for prompt {key}; record it again"
+                ))
+            })?;
+            Ok(futures_util::stream::once(async move { Ok(text) }).boxed())
+        })
+    }
+}
+
+/// 実際の LLM に問い合わせ、応答を記録する
+pub struct RecordingLlmClient {
+    inner: Arc<dyn LlmClient>,
+    responses: Mutex<BTreeMap<String, String>>,
+}
+
+impl RecordingLlmClient {
+    /// existing が同じモデルの記録なら、それに追記する
+    pub fn new(inner: Arc<dyn LlmClient>, existing: Option<LlmRecordings>) -> Self {
+        let responses = existing
+            .filter(|r| r.model == inner.model())
+            .map(|r| r.responses)
+            .unwrap_or_default();
+        Self {
+            inner,
+            responses: Mutex::new(responses),
+        }
+    }
+
+    pub fn recordings(&self) -> LlmRecordings {
+        LlmRecordings {
+            model: self.inner.model().to_string(),
+            responses: self
+                .responses
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
+}
+
+impl LlmClient for RecordingLlmClient {
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn stream_chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+    ) -> BoxFuture<'a, Result<TextStream, LlmError>> {
+        Box::pin(async move {
+            let text = complete_chat(self.inner.as_ref(), messages).await?;
+            self.responses
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(prompt_key(messages), text.clone());
+            Ok(futures_util::stream::once(async move { Ok(text) }).boxed())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::llm::local::LocalLlmClient;
+
+    #[tokio::test]
+    async fn recorded_responses_are_replayed_by_prompt() {
+        let messages = vec![
+            ChatMessage::system("rules"),
+            ChatMessage::user("## 資料\n[1] 会社: Example\n"),
+        ];
+        let recorder = RecordingLlmClient::new(Arc::new(LocalLlmClient), None);
+        let recorded = complete_chat(&recorder, &messages).await.unwrap();
+
+        let replay = ReplayLlmClient::new(recorder.recordings());
+
+        assert_eq!(replay.model(), LocalLlmClient.model());
+        assert_eq!(complete_chat(&replay, &messages).await.unwrap(), recorded);
+        assert!(
+            complete_chat(&replay, &[ChatMessage::user("other")])
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn recordings_of_another_model_are_not_reused() {
+        let existing = LlmRecordings {
+            model: "other".into(),
+            responses: BTreeMap::from([("k".to_string(), "v".to_string())]),
+        };
+
+        let recorder = RecordingLlmClient::new(Arc::new(LocalLlmClient), Some(existing));
+
+        assert!(recorder.recordings().responses.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+/// Server-Sent Events の本文を `data:` ごとに取り出す（チャンクの境界は行の途中でもよい）
+///
+/// 改行は UTF-8 の多バイト文字の途中に現れないので、行に切ってから文字列にする。
+#[derive(Debug, Default)]
+pub struct SseDataDecoder {
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseDataDecoder {
+    /// 受け取った分を足し、そこまでで完結したイベントの data を返す
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let bytes: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&bytes);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                // 空行でイベントが閉じる
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+
+        events
+    }
+
+    /// 最後の空行のないまま閉じた分
+    pub fn finish(&mut self) -> Option<String> {
+        self.push(b"\n\n").pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_split_across_chunks_are_reassembled() {
+        let mut decoder = SseDataDecoder::default();
+
+        assert!(decoder.push(b"data: {\"a\"").is_empty());
+        assert_eq!(decoder.push(b":1}\r\n\r\n: comment\n"), vec![r#"{"a":1}"#]);
+        assert_eq!(
+            decoder.push(b"event: x\ndata: one\ndata:two\n\ndata: [DONE]"),
+            vec!["one\ntwo"]
+        );
+        assert_eq!(decoder.finish().as_deref(), Some("[DONE]"));
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn multibyte_characters_split_across_chunks_survive() {
+        let mut decoder = SseDataDecoder::default();
+        let bytes = "data: 閲覧\n\n".as_bytes();
+
+        assert!(decoder.push(&bytes[..8]).is_empty());
+        assert_eq!(decoder.push(&bytes[8..]), vec!["閲覧"]);
+    }
+}
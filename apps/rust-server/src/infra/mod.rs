@@ -1,4 +1,15 @@
+pub mod analytics;
+pub mod backup;
+pub mod digest;
+pub mod embedding;
+pub mod erasure;
+pub mod export;
+pub mod graphql;
 pub mod json_loader;
+pub mod live;
+pub mod llm;
+pub mod pagination;
+pub mod report;
 pub mod usecase;
 pub mod wantedly;
 
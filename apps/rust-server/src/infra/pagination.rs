@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use storage::wantedly::WantedlyKeysetCursor;
+use utoipa::ToSchema;
+
+/// カーソル文字列は "<閲覧日時の UNIX マイクロ秒>.<id>"
+pub fn encode_cursor(cursor: WantedlyKeysetCursor) -> String {
+    format!("{}.{}", cursor.at.timestamp_micros(), cursor.id)
+}
+
+pub fn decode_cursor(raw: &str) -> Option<WantedlyKeysetCursor> {
+    let (micros, id) = raw.split_once('.')?;
+    let at = DateTime::<Utc>::from_timestamp_micros(micros.parse().ok()?)?;
+    let id = id.parse().ok()?;
+
+    Some(WantedlyKeysetCursor { at, id })
+}
+
+/// 一覧 API のレスポンス（next_cursor が None なら最後のページ）
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// limit + 1 件読んだ結果から、続きがあるかどうかを判定してページにする
+    pub fn from_overfetched(
+        mut rows: Vec<T>,
+        limit: usize,
+        total: i64,
+        key: impl Fn(&T) -> WantedlyKeysetCursor,
+    ) -> Self {
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+
+        let next_cursor = if has_more {
+            rows.last().map(|row| encode_cursor(key(row)))
+        } else {
+            None
+        };
+
+        Self {
+            items: rows,
+            total,
+            next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn cursor(id: i64) -> WantedlyKeysetCursor {
+        WantedlyKeysetCursor {
+            at: Utc.with_ymd_and_hms(2025, 11, 1, 12, 34, 56).unwrap(),
+            id,
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let encoded = encode_cursor(cursor(42));
+        assert_eq!(decode_cursor(&encoded), Some(cursor(42)));
+    }
+
+    #[test]
+    fn malformed_cursor_is_rejected() {
+        assert_eq!(decode_cursor("abc"), None);
+        assert_eq!(decode_cursor("123.x"), None);
+    }
+
+    #[test]
+    fn next_cursor_points_at_last_item_when_more_rows_exist() {
+        let page = Page::from_overfetched(vec![1, 2, 3], 2, 10, |id| cursor(*id));
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.next_cursor, Some(encode_cursor(cursor(2))));
+
+        let last = Page::from_overfetched(vec![1, 2], 2, 2, |id| cursor(*id));
+        assert_eq!(last.next_cursor, None);
+    }
+}
@@ -0,0 +1,452 @@
+//! レポートの内容を形式に依存しないブロックの並びにする（Markdown / HTML はこれを書き出すだけ）
+
+use chrono::NaiveDate;
+use storage::wantedly::{WantedlyAnomalyKind, WantedlyViewBucket, WantedlyViewTotals};
+
+use crate::infra::{
+    analytics::{
+        period::{JstPeriod, format_jst_minute},
+        profile_versions::OwnProfileDiff,
+    },
+    report::{WeeklyReport, svg},
+};
+
+/// 繰り返し閲覧した会社の表に載せる数
+const REPEAT_COMPANY_ROWS: usize = 10;
+
+/// 文字列はすべてプレーンテキスト（エスケープは書き出す側で行う）
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading(String),
+    Paragraph(String),
+    List(Vec<String>),
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    /// alt はグラフを表示できない環境向けの説明
+    Chart {
+        alt: String,
+        svg: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportDocument {
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+fn format_period(period: &JstPeriod) -> String {
+    format!("{} 〜 {}", period.from, period.to)
+}
+
+fn short_date(day: NaiveDate) -> String {
+    day.format("%m/%d").to_string()
+}
+
+/// 前期との差（前期が 0 なら率は出さない）
+pub fn format_change(current: i64, previous: i64) -> String {
+    let diff = current - previous;
+    if previous == 0 {
+        return format!("{diff:+}");
+    }
+    let rate = diff as f64 / previous as f64 * 100.0;
+    format!("{diff:+} ({rate:+.1}%)")
+}
+
+fn company_label(name: Option<&str>, slug: &str) -> String {
+    match name {
+        Some(name) if !name.trim().is_empty() => format!("{name} ({slug})"),
+        _ => slug.to_string(),
+    }
+}
+
+fn totals_table(current: &WantedlyViewTotals, previous: &WantedlyViewTotals) -> Block {
+    let row = |label: &str, current: i64, previous: i64| {
+        vec![
+            label.to_string(),
+            current.to_string(),
+            previous.to_string(),
+            format_change(current, previous),
+        ]
+    };
+
+    Block::Table {
+        headers: ["指標", "今期", "前期", "増減"].map(String::from).to_vec(),
+        rows: vec![
+            row("閲覧数", current.views, previous.views),
+            row(
+                "閲覧者数",
+                current.distinct_viewers,
+                previous.distinct_viewers,
+            ),
+            row(
+                "会社数",
+                current.distinct_companies,
+                previous.distinct_companies,
+            ),
+        ],
+    }
+}
+
+fn daily_chart(daily: &[WantedlyViewBucket], previous_daily: &[WantedlyViewBucket]) -> Block {
+    let labels: Vec<String> = daily.iter().map(|b| short_date(b.bucket_start)).collect();
+    let values: Vec<i64> = daily.iter().map(|b| b.views).collect();
+    let previous: Vec<i64> = previous_daily.iter().map(|b| b.views).collect();
+
+    Block::Chart {
+        alt: format!(
+            "日ごとの閲覧数（合計 {}、前期 {}）",
+            values.iter().sum::<i64>(),
+            previous.iter().sum::<i64>()
+        ),
+        svg: svg::column_chart(
+            "日ごとの閲覧数",
+            &labels,
+            &values,
+            Some(("前期", &previous)),
+        ),
+    }
+}
+
+fn summarize_diff(diff: &OwnProfileDiff) -> String {
+    let mut parts = Vec::new();
+    if let Some(change) = &diff.headline {
+        parts.push(format!(
+            "見出し「{}」→「{}」",
+            change.before.as_deref().unwrap_or(""),
+            change.after.as_deref().unwrap_or("")
+        ));
+    }
+    if !diff.skills_added.is_empty() {
+        parts.push(format!("スキル追加: {}", diff.skills_added.join(", ")));
+    }
+    if !diff.skills_removed.is_empty() {
+        parts.push(format!("スキル削除: {}", diff.skills_removed.join(", ")));
+    }
+    let experiences = diff.experiences_added.len()
+        + diff.experiences_removed.len()
+        + diff.experiences_changed.len();
+    if experiences > 0 {
+        parts.push(format!("職歴の変更 {experiences} 件"));
+    }
+
+    if parts.is_empty() {
+        "内容の変更なし".to_string()
+    } else {
+        parts.join(" / ")
+    }
+}
+
+fn pipeline_items(report: &WeeklyReport) -> Vec<String> {
+    let mut items: Vec<(String, String)> = Vec::new();
+
+    for import in &report.snapshot_imports {
+        items.push((
+            format_jst_minute(import.snapshot_at),
+            format!(
+                "{} 閲覧データを取り込み: {}（{} 件）",
+                format_jst_minute(import.snapshot_at),
+                import.source_file,
+                import.record_count
+            ),
+        ));
+    }
+    for version in &report.profile_versions {
+        let change = version
+            .diff
+            .as_ref()
+            .map(summarize_diff)
+            .unwrap_or_else(|| "最初の版".to_string());
+        items.push((
+            format_jst_minute(version.captured_at),
+            format!(
+                "{} プロフィールを更新: {change}",
+                format_jst_minute(version.captured_at)
+            ),
+        ));
+    }
+    for entry in &report.interventions {
+        let changed_on = entry.intervention.changed_on.to_string();
+        items.push((
+            changed_on.clone(),
+            format!(
+                "{changed_on} 施策: {}（仮説: {}）",
+                entry.intervention.description, entry.hypothesis_title
+            ),
+        ));
+    }
+
+    items.sort();
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+pub fn build_document(report: &WeeklyReport) -> ReportDocument {
+    let mut blocks = vec![
+        Block::Paragraph(format!(
+            "期間: {}（{} 日間）／ 比較対象: {}",
+            format_period(&report.period),
+            report.period.days(),
+            format_period(&report.previous_period)
+        )),
+        Block::Heading("閲覧数".into()),
+        totals_table(&report.totals, &report.previous_totals),
+        daily_chart(&report.daily, &report.previous_daily),
+    ];
+
+    blocks.push(Block::Heading("よく閲覧した会社".into()));
+    if report.top_companies.is_empty() {
+        blocks.push(Block::Paragraph(
+            "期間内に閲覧した会社はありません。".into(),
+        ));
+    } else {
+        let items: Vec<(String, i64)> = report
+            .top_companies
+            .iter()
+            .map(|c| (company_label(c.name.as_deref(), &c.company_slug), c.views))
+            .collect();
+        blocks.push(Block::Chart {
+            alt: "会社ごとの閲覧数".into(),
+            svg: svg::bar_chart("会社ごとの閲覧数", &items),
+        });
+        blocks.push(Block::Table {
+            headers: ["会社", "閲覧数", "前期", "閲覧者数"]
+                .map(String::from)
+                .to_vec(),
+            rows: report
+                .top_companies
+                .iter()
+                .map(|c| {
+                    vec![
+                        company_label(c.name.as_deref(), &c.company_slug),
+                        c.views.to_string(),
+                        c.previous_views.to_string(),
+                        c.distinct_viewers.to_string(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    blocks.push(Block::Heading("新しく閲覧した会社".into()));
+    if report.new_companies.is_empty() {
+        blocks.push(Block::Paragraph("初めて閲覧した会社はありません。".into()));
+    } else {
+        blocks.push(Block::Table {
+            headers: ["会社", "初回閲覧（JST）", "閲覧数", "閲覧者数"]
+                .map(String::from)
+                .to_vec(),
+            rows: report
+                .new_companies
+                .iter()
+                .map(|c| {
+                    vec![
+                        company_label(c.name.as_deref(), &c.company_slug),
+                        format_jst_minute(c.first_viewed_at),
+                        c.views.to_string(),
+                        c.distinct_viewers.to_string(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    blocks.push(Block::Heading("繰り返し閲覧".into()));
+    blocks.push(Block::Paragraph(format!(
+        "期間内に 2 日以上閲覧した閲覧者: {} 人、会社: {} 社",
+        report.repeat_viewers.len(),
+        report.repeat_companies.len()
+    )));
+    if !report.repeat_companies.is_empty() {
+        blocks.push(Block::Table {
+            headers: ["会社", "閲覧日数", "閲覧数", "繰り返し閲覧者数"]
+                .map(String::from)
+                .to_vec(),
+            rows: report
+                .repeat_companies
+                .iter()
+                .take(REPEAT_COMPANY_ROWS)
+                .map(|c| {
+                    vec![
+                        c.company_slug.clone(),
+                        c.view_days.to_string(),
+                        c.views.to_string(),
+                        c.repeat_viewers.to_string(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    blocks.push(Block::Heading("閲覧数の異常".into()));
+    if report.anomalies.is_empty() {
+        blocks.push(Block::Paragraph("検出された異常はありません。".into()));
+    } else {
+        blocks.push(Block::List(
+            report
+                .anomalies
+                .iter()
+                .map(|a| {
+                    let kind = match a.anomaly.kind {
+                        WantedlyAnomalyKind::Spike => "急増",
+                        WantedlyAnomalyKind::Drop => "急減",
+                    };
+                    let companies: Vec<String> = a
+                        .top_companies
+                        .iter()
+                        .map(|c| {
+                            format!(
+                                "{} {}",
+                                company_label(c.name.as_deref(), &c.company_slug),
+                                c.views
+                            )
+                        })
+                        .collect();
+                    let mut item = format!(
+                        "{} {kind}: 閲覧 {}（基準 {:.1}、スコア {:+.1}）",
+                        a.anomaly.day, a.anomaly.views, a.anomaly.baseline_median, a.anomaly.score
+                    );
+                    if !companies.is_empty() {
+                        item.push_str(&format!(" — {}", companies.join(", ")));
+                    }
+                    item
+                })
+                .collect(),
+        ));
+    }
+
+    blocks.push(Block::Heading("取り込みとプロフィールの変更".into()));
+    let pipeline = pipeline_items(report);
+    if pipeline.is_empty() {
+        blocks.push(Block::Paragraph("期間内の変更はありません。".into()));
+    } else {
+        blocks.push(Block::List(pipeline));
+    }
+
+    ReportDocument {
+        title: format!("プロフィール閲覧レポート {}", format_period(&report.period)),
+        blocks,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::infra::report::WeeklyReport;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// 描画のテスト用の、閲覧のない 1 週間のレポート
+    pub(crate) fn empty_report() -> WeeklyReport {
+        let period = JstPeriod {
+            from: date(2025, 11, 3),
+            to: date(2025, 11, 9),
+        };
+        let daily = |period: JstPeriod| {
+            period
+                .from
+                .iter_days()
+                .take(period.days() as usize)
+                .map(|d| WantedlyViewBucket {
+                    bucket_start: d,
+                    bucket_end: d,
+                    views: 0,
+                    distinct_viewers: 0,
+                    distinct_companies: 0,
+                })
+                .collect()
+        };
+
+        WeeklyReport {
+            period,
+            previous_period: period.previous(),
+            totals: WantedlyViewTotals::default(),
+            previous_totals: WantedlyViewTotals::default(),
+            daily: daily(period),
+            previous_daily: daily(period.previous()),
+            top_companies: Vec::new(),
+            new_companies: Vec::new(),
+            repeat_viewers: Vec::new(),
+            repeat_companies: Vec::new(),
+            anomalies: Vec::new(),
+            snapshot_imports: Vec::new(),
+            profile_versions: Vec::new(),
+            interventions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn change_is_signed_with_rate_when_previous_exists() {
+        assert_eq!(format_change(15, 10), "+5 (+50.0%)");
+        assert_eq!(format_change(5, 10), "-5 (-50.0%)");
+        assert_eq!(format_change(3, 0), "+3");
+    }
+
+    #[test]
+    fn empty_period_still_has_every_section() {
+        let document = build_document(&empty_report());
+
+        let headings: Vec<&str> = document
+            .blocks
+            .iter()
+            .filter_map(|b| match b {
+                Block::Heading(h) => Some(h.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                "閲覧数",
+                "よく閲覧した会社",
+                "新しく閲覧した会社",
+                "繰り返し閲覧",
+                "閲覧数の異常",
+                "取り込みとプロフィールの変更",
+            ]
+        );
+        assert!(document.title.contains("2025-11-03 〜 2025-11-09"));
+        assert!(
+            document
+                .blocks
+                .iter()
+                .any(|b| matches!(b, Block::Chart { .. }))
+        );
+    }
+
+    #[test]
+    fn pipeline_changes_are_listed_in_time_order() {
+        let mut report = empty_report();
+        report
+            .snapshot_imports
+            .push(storage::wantedly::WantedlySnapshotImport {
+                id: 1,
+                source_file: "20251105090000.json".into(),
+                file_sha256: String::new(),
+                snapshot_at: "2025-11-05T00:00:00Z".parse().unwrap(),
+                record_count: 12,
+                imported_at: "2025-11-05T00:00:00Z".parse().unwrap(),
+            });
+        report
+            .interventions
+            .push(crate::infra::report::ReportIntervention {
+                intervention: storage::hypotheses::ProfileIntervention {
+                    id: 1,
+                    hypothesis_id: 1,
+                    description: "見出しを変更".into(),
+                    changed_on: date(2025, 11, 4),
+                    created_at: "2025-11-04T00:00:00Z".parse().unwrap(),
+                },
+                hypothesis_title: "見出しで閲覧が増える".into(),
+            });
+
+        let items = pipeline_items(&report);
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].starts_with("2025-11-04 施策: 見出しを変更"));
+        assert!(items[1].contains("20251105090000.json（12 件）"));
+    }
+}
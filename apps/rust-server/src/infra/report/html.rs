@@ -0,0 +1,90 @@
+use crate::infra::report::{
+    document::{Block, ReportDocument},
+    svg::escape_xml,
+};
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:720px;margin:2em auto;padding:0 1em;color:#222}\
+table{border-collapse:collapse;margin:1em 0}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+td:not(:first-child){text-align:right}\
+figure{margin:1em 0}";
+
+fn push_cells(out: &mut String, tag: &str, cells: &[String]) {
+    out.push_str("<tr>");
+    for cell in cells {
+        out.push_str(&format!("<{tag}>{}</{tag}>", escape_xml(cell)));
+    }
+    out.push_str("</tr>");
+}
+
+/// 単体で開ける 1 枚の HTML（グラフはインライン SVG）
+pub fn render_html(document: &ReportDocument) -> String {
+    let title = escape_xml(&document.title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+
+    for block in &document.blocks {
+        match block {
+            Block::Heading(text) => out.push_str(&format!("<h2>{}</h2>", escape_xml(text))),
+            Block::Paragraph(text) => out.push_str(&format!("<p>{}</p>", escape_xml(text))),
+            Block::List(items) => {
+                out.push_str("<ul>");
+                for item in items {
+                    out.push_str(&format!("<li>{}</li>", escape_xml(item)));
+                }
+                out.push_str("</ul>");
+            }
+            Block::Table { headers, rows } => {
+                out.push_str("<table><thead>");
+                push_cells(&mut out, "th", headers);
+                out.push_str("</thead><tbody>");
+                for row in rows {
+                    push_cells(&mut out, "td", row);
+                }
+                out.push_str("</tbody></table>");
+            }
+            // svg は svg モジュールが組み立てたもの（中の文字列はエスケープ済み）
+            Block::Chart { alt, svg } => out.push_str(&format!(
+                "<figure>{svg}<figcaption hidden>{}</figcaption></figure>",
+                escape_xml(alt)
+            )),
+        }
+        out.push('\n');
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::report::document::{build_document, tests::empty_report};
+
+    #[test]
+    fn renders_a_standalone_page_with_inline_svg() {
+        let html = render_html(&build_document(&empty_report()));
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2>閲覧数</h2>"));
+        assert!(html.contains("<figure><svg xmlns="));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        let document = ReportDocument {
+            title: "<script>".into(),
+            blocks: vec![Block::Table {
+                headers: vec!["会社".into()],
+                rows: vec![vec!["A & B".into()]],
+            }],
+        };
+
+        let html = render_html(&document);
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<td>A &amp; B</td>"));
+    }
+}
@@ -0,0 +1,99 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+use crate::infra::report::document::{Block, ReportDocument};
+
+/// 本文中で書式として解釈される文字をエスケープする（改行は空白にする）
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn table_row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells.iter().map(|c| escape_markdown(c)).collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+/// グラフは data URI の画像として埋め込む（インライン SVG を表示しないビューアでも見える）
+pub fn render_markdown(document: &ReportDocument) -> String {
+    let mut out = format!("# {}\n", escape_markdown(&document.title));
+
+    for block in &document.blocks {
+        out.push('\n');
+        match block {
+            Block::Heading(text) => {
+                out.push_str(&format!("## {}\n", escape_markdown(text)));
+            }
+            Block::Paragraph(text) => {
+                out.push_str(&escape_markdown(text));
+                out.push('\n');
+            }
+            Block::List(items) => {
+                for item in items {
+                    out.push_str(&format!("- {}\n", escape_markdown(item)));
+                }
+            }
+            Block::Table { headers, rows } => {
+                out.push_str(&table_row(headers));
+                out.push('\n');
+                out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for row in rows {
+                    out.push_str(&table_row(row));
+                    out.push('\n');
+                }
+            }
+            Block::Chart { alt, svg } => {
+                out.push_str(&format!(
+                    "![{}](data:image/svg+xml;base64,{})\n",
+                    escape_markdown(alt),
+                    STANDARD.encode(svg)
+                ));
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::report::document::{build_document, tests::empty_report};
+
+    #[test]
+    fn tables_and_text_are_escaped() {
+        let document = ReportDocument {
+            title: "a_b".into(),
+            blocks: vec![
+                Block::Table {
+                    headers: vec!["会社".into(), "閲覧数".into()],
+                    rows: vec![vec!["x|y\nz".into(), "3".into()]],
+                },
+                Block::List(vec!["*強調*".into()]),
+            ],
+        };
+
+        let markdown = render_markdown(&document);
+
+        assert!(markdown.starts_with("# a\\_b\n"));
+        assert!(markdown.contains("| 会社 | 閲覧数 |\n| --- | --- |\n| x\\|y z | 3 |\n"));
+        assert!(markdown.contains("- \\*強調\\*\n"));
+    }
+
+    #[test]
+    fn charts_are_embedded_as_data_uris() {
+        let markdown = render_markdown(&build_document(&empty_report()));
+
+        assert!(markdown.contains("](data:image/svg+xml;base64,PHN2Zy"));
+        assert!(!markdown.contains("<svg"));
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use storage::hypotheses::ProfileIntervention;
+use storage::wantedly::{
+    WantedlyCompanyLeaderboardRow, WantedlyNewCompanyRow, WantedlyRepeatCompanyRow,
+    WantedlyRepeatViewerRow, WantedlySnapshotImport, WantedlyViewBucket, WantedlyViewTotals,
+};
+use utoipa::ToSchema;
+
+use crate::infra::analytics::{
+    anomaly::ViewAnomaly, period::JstPeriod, profile_versions::OwnProfileVersionViews,
+};
+
+pub mod document;
+pub mod html;
+pub mod markdown;
+pub mod svg;
+
+/// レポートの出力形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "text/markdown; charset=utf-8",
+            ReportFormat::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+
+    pub fn render(&self, report: &WeeklyReport) -> String {
+        let document = document::build_document(report);
+        match self {
+            ReportFormat::Markdown => markdown::render_markdown(&document),
+            ReportFormat::Html => html::render_html(&document),
+        }
+    }
+}
+
+/// 期間内に実施した施策と、その仮説のタイトル
+#[derive(Debug, Clone)]
+pub struct ReportIntervention {
+    pub intervention: ProfileIntervention,
+    pub hypothesis_title: String,
+}
+
+/// レポートの材料（期間と直前の同じ長さの期間の集計）
+#[derive(Debug, Clone)]
+pub struct WeeklyReport {
+    pub period: JstPeriod,
+    pub previous_period: JstPeriod,
+    pub totals: WantedlyViewTotals,
+    pub previous_totals: WantedlyViewTotals,
+    pub daily: Vec<WantedlyViewBucket>,
+    pub previous_daily: Vec<WantedlyViewBucket>,
+    pub top_companies: Vec<WantedlyCompanyLeaderboardRow>,
+    pub new_companies: Vec<WantedlyNewCompanyRow>,
+    pub repeat_viewers: Vec<WantedlyRepeatViewerRow>,
+    pub repeat_companies: Vec<WantedlyRepeatCompanyRow>,
+    pub anomalies: Vec<ViewAnomaly>,
+    pub snapshot_imports: Vec<WantedlySnapshotImport>,
+    /// 期間内に保存したプロフィールの版（直前の版との差分つき）
+    pub profile_versions: Vec<OwnProfileVersionViews>,
+    pub interventions: Vec<ReportIntervention>,
+}
@@ -0,0 +1,211 @@
+//! レポートに埋め込む SVG グラフ（外部の JS / フォントに頼らず、そのまま画像として表示できる）
+
+use std::fmt::Write;
+
+const WIDTH: f64 = 640.0;
+const COLUMN_HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 40.0;
+const MARGIN_RIGHT: f64 = 12.0;
+const MARGIN_TOP: f64 = 28.0;
+const MARGIN_BOTTOM: f64 = 28.0;
+/// x 軸のラベルはこれより多くならないよう間引く
+const MAX_X_LABELS: usize = 10;
+const BAR_ROW_HEIGHT: f64 = 22.0;
+const BAR_LABEL_WIDTH: f64 = 180.0;
+const BAR_COLOR: &str = "#4f7cac";
+const COMPARISON_COLOR: &str = "#d08c3a";
+const TEXT_STYLE: &str = r##"font-family="sans-serif" font-size="11" fill="#333""##;
+
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn open_svg(out: &mut String, height: f64, title: &str) {
+    let _ = write!(
+        out,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" role="img" aria-label="{title}"><title>{title}</title><rect width="100%" height="100%" fill="#fff"/><text x="{MARGIN_LEFT}" y="16" {TEXT_STYLE} font-weight="bold">{title}</text>"##,
+        title = escape_xml(title),
+    );
+}
+
+/// 縦棒グラフ。comparison を渡すと、同じ位置に折れ線で重ねる（直前の期間との比較用）
+pub fn column_chart(
+    title: &str,
+    labels: &[String],
+    values: &[i64],
+    comparison: Option<(&str, &[i64])>,
+) -> String {
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = COLUMN_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let baseline = MARGIN_TOP + plot_height;
+
+    let max = values
+        .iter()
+        .chain(comparison.iter().flat_map(|(_, c)| c.iter()))
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let band = plot_width / values.len().max(1) as f64;
+    let y = |v: i64| baseline - v as f64 / max * plot_height;
+
+    let mut out = String::new();
+    open_svg(&mut out, COLUMN_HEIGHT, title);
+
+    // 目盛り（0 / 半分 / 最大）
+    for tick in [0.0, max / 2.0, max] {
+        let ty = baseline - tick / max * plot_height;
+        let _ = write!(
+            out,
+            r##"<line x1="{MARGIN_LEFT}" y1="{ty:.1}" x2="{x2:.1}" y2="{ty:.1}" stroke="#e0e0e0"/><text x="{tx:.1}" y="{ly:.1}" text-anchor="end" {TEXT_STYLE}>{tick}</text>"##,
+            x2 = WIDTH - MARGIN_RIGHT,
+            tx = MARGIN_LEFT - 4.0,
+            ly = ty + 4.0,
+            tick = format_tick(tick),
+        );
+    }
+
+    for (index, value) in values.iter().enumerate() {
+        let x = MARGIN_LEFT + band * index as f64 + band * 0.15;
+        let top = y(*value);
+        let _ = write!(
+            out,
+            r#"<rect x="{x:.1}" y="{top:.1}" width="{w:.1}" height="{h:.1}" fill="{BAR_COLOR}"><title>{label}: {value}</title></rect>"#,
+            w = band * 0.7,
+            h = baseline - top,
+            label = escape_xml(labels.get(index).map(String::as_str).unwrap_or("")),
+        );
+    }
+
+    let step = labels.len().div_ceil(MAX_X_LABELS).max(1);
+    for (index, label) in labels.iter().enumerate().step_by(step) {
+        let _ = write!(
+            out,
+            r#"<text x="{x:.1}" y="{ly:.1}" text-anchor="middle" {TEXT_STYLE}>{label}</text>"#,
+            x = MARGIN_LEFT + band * (index as f64 + 0.5),
+            ly = baseline + 16.0,
+            label = escape_xml(label),
+        );
+    }
+
+    if let Some((name, comparison)) = comparison {
+        let points: Vec<String> = comparison
+            .iter()
+            .enumerate()
+            .map(|(index, v)| {
+                format!(
+                    "{:.1},{:.1}",
+                    MARGIN_LEFT + band * (index as f64 + 0.5),
+                    y(*v)
+                )
+            })
+            .collect();
+        let _ = write!(
+            out,
+            r#"<polyline points="{points}" fill="none" stroke="{COMPARISON_COLOR}" stroke-width="2" stroke-dasharray="4 3"/><text x="{lx:.1}" y="16" text-anchor="end" font-family="sans-serif" font-size="11" fill="{COMPARISON_COLOR}">- - {name}</text>"#,
+            points = points.join(" "),
+            lx = WIDTH - MARGIN_RIGHT,
+            name = escape_xml(name),
+        );
+    }
+
+    out.push_str("</svg>");
+    out
+}
+
+/// 横棒グラフ（ラベルと値の組を上から順に並べる）
+pub fn bar_chart(title: &str, items: &[(String, i64)]) -> String {
+    let height = MARGIN_TOP + BAR_ROW_HEIGHT * items.len().max(1) as f64 + 8.0;
+    let plot_width = WIDTH - BAR_LABEL_WIDTH - MARGIN_RIGHT - 40.0;
+    let max = items.iter().map(|(_, v)| *v).max().unwrap_or(0).max(1) as f64;
+
+    let mut out = String::new();
+    open_svg(&mut out, height, title);
+
+    for (index, (label, value)) in items.iter().enumerate() {
+        let top = MARGIN_TOP + BAR_ROW_HEIGHT * index as f64;
+        let width = *value as f64 / max * plot_width;
+        let _ = write!(
+            out,
+            r#"<text x="{lx:.1}" y="{ty:.1}" text-anchor="end" {TEXT_STYLE}>{label}</text><rect x="{BAR_LABEL_WIDTH}" y="{ry:.1}" width="{width:.1}" height="{h:.1}" fill="{BAR_COLOR}"/><text x="{vx:.1}" y="{ty:.1}" {TEXT_STYLE}>{value}</text>"#,
+            lx = BAR_LABEL_WIDTH - 6.0,
+            ty = top + BAR_ROW_HEIGHT / 2.0 + 4.0,
+            ry = top + 3.0,
+            h = BAR_ROW_HEIGHT - 6.0,
+            vx = BAR_LABEL_WIDTH + width + 4.0,
+            label = escape_xml(&truncate_label(label, 28)),
+        );
+    }
+
+    out.push_str("</svg>");
+    out
+}
+
+fn format_tick(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+fn truncate_label(label: &str, max_chars: usize) -> String {
+    if label.chars().count() <= max_chars {
+        return label.to_string();
+    }
+    let mut truncated: String = label.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_chart_draws_a_bar_per_value_and_the_comparison_line() {
+        let labels: Vec<String> = ["11/01", "11/02", "11/03"].map(String::from).to_vec();
+
+        let svg = column_chart("閲覧数", &labels, &[1, 4, 2], Some(("前期", &[2, 2, 2])));
+
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect x=").count(), 3);
+        assert!(svg.contains("<polyline"));
+        // 最大値 4 の棒は描画領域の高さいっぱい
+        assert!(svg.contains(r##"height="184.0" fill="#4f7cac""##));
+    }
+
+    #[test]
+    fn labels_are_escaped_and_thinned_out() {
+        let labels: Vec<String> = (0..30).map(|i| format!("<{i}>")).collect();
+        let values = vec![0; 30];
+
+        let svg = column_chart("a & b", &labels, &values, None);
+
+        assert!(svg.contains("a &amp; b"));
+        assert!(!svg.contains("<0>"));
+        assert_eq!(svg.matches("text-anchor=\"middle\"").count(), 10);
+    }
+
+    #[test]
+    fn bar_chart_truncates_long_labels() {
+        let svg = bar_chart(
+            "会社",
+            &[("とても長い会社名".repeat(5), 10), ("short".into(), 5)],
+        );
+
+        assert!(svg.contains('…'));
+        assert!(svg.contains(">short<"));
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use storage::{
+    hypotheses::{
+        HypothesisError, find_hypothesis_by_id, list_interventions,
+        list_interventions_for_hypothesis,
+    },
+    wantedly::{WantedlyTimeBucket, WantedlyTimeSeriesError, list_view_time_series},
+};
+use thiserror::Error;
+
+use crate::infra::analytics::experiment::{
+    HypothesisAnalysis, analyze_intervention, intervention_windows,
+};
+
+const DEFAULT_WINDOW_DAYS: u32 = 14;
+const MIN_WINDOW_DAYS: u32 = 3;
+const MAX_WINDOW_DAYS: u32 = 180;
+const DEFAULT_CONFIDENCE: f64 = 0.95;
+
+#[derive(Debug, Error)]
+pub enum HypothesisAnalysisError {
+    #[error(transparent)]
+    Hypothesis(#[from] HypothesisError),
+
+    #[error(transparent)]
+    TimeSeries(#[from] WantedlyTimeSeriesError),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HypothesisAnalysisOptionsError {
+    #[error("`window_days` must be between {MIN_WINDOW_DAYS} and {MAX_WINDOW_DAYS}")]
+    WindowDays,
+
+    #[error("`confidence` must be greater than 0 and less than 1")]
+    Confidence,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HypothesisAnalysisOptions {
+    /// 変更日の前後それぞれ何日（JST）を比べるか
+    pub window_days: u32,
+    /// 信頼区間の水準。有意水準は 1 − confidence
+    pub confidence: f64,
+}
+
+impl HypothesisAnalysisOptions {
+    /// 省略時は前後 14 日、信頼水準 95%
+    pub fn resolve(
+        window_days: Option<u32>,
+        confidence: Option<f64>,
+    ) -> Result<Self, HypothesisAnalysisOptionsError> {
+        let window_days = window_days.unwrap_or(DEFAULT_WINDOW_DAYS);
+        if !(MIN_WINDOW_DAYS..=MAX_WINDOW_DAYS).contains(&window_days) {
+            return Err(HypothesisAnalysisOptionsError::WindowDays);
+        }
+
+        let confidence = confidence.unwrap_or(DEFAULT_CONFIDENCE);
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err(HypothesisAnalysisOptionsError::Confidence);
+        }
+
+        Ok(Self {
+            window_days,
+            confidence,
+        })
+    }
+}
+
+/// 仮説に紐づく介入ごとに、変更日の前後の日次指標を比べる。仮説がなければ None
+pub async fn analyze_profile_hypothesis(
+    pool: &PgPool,
+    hypothesis_id: i64,
+    options: &HypothesisAnalysisOptions,
+    today: NaiveDate,
+) -> Result<Option<HypothesisAnalysis>, HypothesisAnalysisError> {
+    let Some(hypothesis) = find_hypothesis_by_id(pool, hypothesis_id).await? else {
+        return Ok(None);
+    };
+
+    // 他の仮説の介入も含めて、期間が重なるものを知らせる
+    let all_interventions = list_interventions(pool).await?;
+
+    let mut interventions = Vec::new();
+    for intervention in list_interventions_for_hypothesis(pool, hypothesis_id).await? {
+        let (before, after) =
+            intervention_windows(intervention.changed_on, options.window_days, today);
+        let to = after.map_or(before.to, |p| p.to);
+        let daily = list_view_time_series(pool, before.from, to, WantedlyTimeBucket::Day).await?;
+
+        interventions.push(analyze_intervention(
+            &hypothesis,
+            intervention,
+            &all_interventions,
+            &daily,
+            options.window_days,
+            options.confidence,
+            today,
+        ));
+    }
+
+    Ok(Some(HypothesisAnalysis {
+        hypothesis,
+        window_days: options.window_days,
+        confidence: options.confidence,
+        interventions,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_compare_two_weeks_at_95_percent() {
+        let options = HypothesisAnalysisOptions::resolve(None, None).unwrap();
+
+        assert_eq!(options.window_days, 14);
+        assert_eq!(options.confidence, 0.95);
+    }
+
+    #[test]
+    fn rejects_out_of_range_options() {
+        assert_eq!(
+            HypothesisAnalysisOptions::resolve(Some(1), None).unwrap_err(),
+            HypothesisAnalysisOptionsError::WindowDays
+        );
+        assert_eq!(
+            HypothesisAnalysisOptions::resolve(None, Some(1.0)).unwrap_err(),
+            HypothesisAnalysisOptionsError::Confidence
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use storage::wantedly::{
+    WantedlyRepeatViewError, list_repeat_companies, list_repeat_viewers,
+    list_viewer_cohort_retention, list_viewer_cohorts,
+};
+
+use thiserror::Error;
+
+use crate::infra::analytics::{
+    period::{JstPeriod, PeriodError},
+    repeat::{RepeatViewerReport, build_cohorts, summarize_cohorts},
+};
+
+const DEFAULT_DAYS: u32 = 365;
+const MAX_DAYS: u32 = 1100;
+const DEFAULT_MIN_VIEW_DAYS: i64 = 2;
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RepeatViewerOptionsError {
+    #[error(transparent)]
+    Period(#[from] PeriodError),
+
+    #[error("`min_view_days` must be at least 2")]
+    MinViewDays,
+
+    #[error("`limit` must be between 1 and {MAX_LIMIT}")]
+    Limit,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RepeatViewerOptions {
+    pub period: JstPeriod,
+    /// 何日（JST）以上閲覧したら「繰り返し」とみなすか
+    pub min_view_days: i64,
+    /// 閲覧者・会社の一覧の最大件数
+    pub limit: i64,
+}
+
+impl RepeatViewerOptions {
+    /// 期間の省略時は today（JST）までの 365 日、min_view_days は 2、limit は 100
+    pub fn resolve(
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        min_view_days: Option<i64>,
+        limit: Option<i64>,
+        today: NaiveDate,
+    ) -> Result<Self, RepeatViewerOptionsError> {
+        let period = JstPeriod::resolve(from, to, DEFAULT_DAYS, MAX_DAYS, today)?;
+
+        let min_view_days = min_view_days.unwrap_or(DEFAULT_MIN_VIEW_DAYS);
+        if min_view_days < 2 {
+            return Err(RepeatViewerOptionsError::MinViewDays);
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(RepeatViewerOptionsError::Limit);
+        }
+
+        Ok(Self {
+            period,
+            min_view_days,
+            limit,
+        })
+    }
+}
+
+/// 繰り返し閲覧した閲覧者・会社と、初回閲覧週ごとのコホートをまとめて集計する
+pub async fn analyze_repeat_viewers(
+    pool: &PgPool,
+    options: &RepeatViewerOptions,
+) -> Result<RepeatViewerReport, WantedlyRepeatViewError> {
+    let JstPeriod { from, to } = options.period;
+
+    let viewers = list_repeat_viewers(pool, from, to, options.min_view_days, options.limit).await?;
+    let companies =
+        list_repeat_companies(pool, from, to, options.min_view_days, options.limit).await?;
+    let cohorts = build_cohorts(
+        list_viewer_cohorts(pool, from, to).await?,
+        list_viewer_cohort_retention(pool, from, to).await?,
+    );
+
+    Ok(RepeatViewerReport {
+        period: options.period,
+        summary: summarize_cohorts(&cohorts),
+        viewers,
+        companies,
+        cohorts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()
+    }
+
+    #[test]
+    fn defaults_cover_the_last_year() {
+        let options = RepeatViewerOptions::resolve(None, None, None, None, today()).unwrap();
+
+        assert_eq!(options.period.days(), 365);
+        assert_eq!(options.min_view_days, 2);
+        assert_eq!(options.limit, 100);
+    }
+
+    #[test]
+    fn single_view_is_not_a_repeat() {
+        assert_eq!(
+            RepeatViewerOptions::resolve(None, None, Some(1), None, today()).unwrap_err(),
+            RepeatViewerOptionsError::MinViewDays
+        );
+        assert_eq!(
+            RepeatViewerOptions::resolve(None, None, None, Some(0), today()).unwrap_err(),
+            RepeatViewerOptionsError::Limit
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use storage::retrieval::{
+    RetrievalError, RetrievedCompany, RetrievedDocument, RetrievedViewerDescription,
+    search_companies, search_documents, search_viewer_descriptions,
+};
+use storage::wantedly::{
+    WantedlyCompanyEmbeddingSource, WantedlyEmbeddingError, widen_hnsw_search,
+};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::infra::{
+    embedding::{
+        Embedder, EmbeddingError,
+        document::{company_document, document_kind_label},
+        embed_checked,
+    },
+    llm::{ChatMessage, LlmClient, LlmError, TextStream, complete_chat, truncate_chars},
+};
+
+/// プロンプトの版（プロンプトを変えたら上げる）
+pub const RAG_PROMPT_VERSION: &str = "rag-v1";
+
+/// 1 つの資料としてプロンプトに入れる本文の上限（文字数）
+const MAX_PASSAGE_CHARS: usize = 1200;
+
+const SYSTEM_PROMPT: &str = "\
+あなたは Wantedly のプロフィール閲覧データについて答えるアシスタントです。\
+ユーザーの質問に、与えられた資料だけを根拠に日本語で簡潔に答えてください。\
+根拠にした資料は文末に [1] のように番号で示してください。\
+資料から分からないことは推測せず「資料からは分かりません」と答えてください。";
+
+#[derive(Debug, Error)]
+pub enum RagError {
+    #[error(transparent)]
+    Retrieval(#[from] RetrievalError),
+
+    #[error(transparent)]
+    Storage(#[from] WantedlyEmbeddingError),
+
+    #[error(transparent)]
+    Embedding(#[from] EmbeddingError),
+
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 資料の出どころ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RagSourceKind {
+    Company,
+    ViewerDescription,
+    ScoutMessage,
+    Note,
+}
+
+/// 回答の根拠としてプロンプトに入れた資料
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RagPassage {
+    /// 回答中の [n] の n
+    pub citation: usize,
+    pub kind: RagSourceKind,
+    /// 会社 slug / 閲覧者 id / 文書 id
+    pub reference: String,
+    pub title: String,
+    pub text: String,
+    /// 質問とのコサイン距離
+    pub distance: f64,
+}
+
+/// 回答の生成を始めた状態（資料は確定済み、本文はこれから流れる）
+pub struct RagAnswerStream {
+    pub prompt_version: &'static str,
+    pub model: String,
+    pub passages: Vec<RagPassage>,
+    pub deltas: TextStream,
+}
+
+/// 最後まで生成した回答
+#[derive(Debug, Clone, Serialize)]
+pub struct RagAnswer {
+    pub prompt_version: &'static str,
+    pub model: String,
+    pub question: String,
+    pub answer: String,
+    pub passages: Vec<RagPassage>,
+}
+
+/// stream_answer の回答を最後まで受け取って返す（CLI・評価用）
+pub async fn answer_question(
+    pool: &PgPool,
+    embedder: &dyn Embedder,
+    llm: &dyn LlmClient,
+    question: &str,
+    top_k: usize,
+) -> Result<RagAnswer, RagError> {
+    let passages = retrieve_passages(pool, embedder, question, top_k).await?;
+    let answer = complete_chat(llm, &build_messages(question, &passages)).await?;
+
+    Ok(RagAnswer {
+        prompt_version: RAG_PROMPT_VERSION,
+        model: llm.model().to_string(),
+        question: question.to_string(),
+        answer,
+        passages,
+    })
+}
+
+/// 質問を埋め込み、会社・閲覧者の自己紹介・スカウトとメモから近い順に top_k 件を資料にして
+/// LLM に答えさせる
+pub async fn stream_answer(
+    pool: &PgPool,
+    embedder: &dyn Embedder,
+    llm: &dyn LlmClient,
+    question: &str,
+    top_k: usize,
+) -> Result<RagAnswerStream, RagError> {
+    let passages = retrieve_passages(pool, embedder, question, top_k).await?;
+    let messages = build_messages(question, &passages);
+    let deltas = llm.stream_chat(&messages).await?;
+
+    Ok(RagAnswerStream {
+        prompt_version: RAG_PROMPT_VERSION,
+        model: llm.model().to_string(),
+        passages,
+        deltas,
+    })
+}
+
+pub async fn retrieve_passages(
+    pool: &PgPool,
+    embedder: &dyn Embedder,
+    question: &str,
+    top_k: usize,
+) -> Result<Vec<RagPassage>, RagError> {
+    let model = embedder.model().to_string();
+    let mut vectors = embed_checked(embedder, &[question.to_string()]).await?;
+    let embedding = vectors.remove(0);
+    let k = top_k as i64;
+
+    // どの種類が近いか分からないので、種類ごとに top_k 件ずつ引いてから距離で混ぜる
+    let mut tx = pool.begin().await?;
+    widen_hnsw_search(&mut tx, k).await?;
+    let companies = search_companies(&mut tx, &embedding, &model, k).await?;
+    let viewers = search_viewer_descriptions(&mut tx, &embedding, &model, k).await?;
+    let documents = search_documents(&mut tx, &embedding, &model, k).await?;
+    tx.commit().await?;
+
+    Ok(merge_passages(companies, viewers, documents, top_k))
+}
+
+fn merge_passages(
+    companies: Vec<RetrievedCompany>,
+    viewers: Vec<RetrievedViewerDescription>,
+    documents: Vec<RetrievedDocument>,
+    top_k: usize,
+) -> Vec<RagPassage> {
+    let mut passages: Vec<RagPassage> = companies
+        .into_iter()
+        .map(company_passage)
+        .chain(viewers.into_iter().map(viewer_passage))
+        .chain(documents.into_iter().map(document_passage))
+        .collect();
+
+    passages.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    passages.truncate(top_k);
+    for (i, passage) in passages.iter_mut().enumerate() {
+        passage.citation = i + 1;
+    }
+    passages
+}
+
+fn company_passage(company: RetrievedCompany) -> RagPassage {
+    let source = WantedlyCompanyEmbeddingSource {
+        company_id: company.company_id,
+        company_slug: company.company_slug.clone(),
+        name: company.name.clone(),
+        domain: company.domain,
+        industry: company.industry,
+        employee_band: company.employee_band,
+        funding_stage: company.funding_stage,
+        location: company.location,
+        embedded_model: None,
+        embedded_sha256: None,
+    };
+    // 1 行目の「会社: 〜」は見出しにするので本文からは外す
+    let document = company_document(&source);
+    let mut lines: Vec<&str> = document.lines().skip(1).collect();
+    let views = format!("閲覧数: {}", company.views);
+    lines.push(&views);
+
+    RagPassage {
+        citation: 0,
+        kind: RagSourceKind::Company,
+        reference: company.company_slug.clone(),
+        title: format!(
+            "会社: {}",
+            company.name.as_deref().unwrap_or(&company.company_slug)
+        ),
+        text: lines.join("\n"),
+        distance: company.distance,
+    }
+}
+
+fn viewer_passage(viewer: RetrievedViewerDescription) -> RagPassage {
+    RagPassage {
+        citation: 0,
+        kind: RagSourceKind::ViewerDescription,
+        reference: viewer.viewer_id.to_string(),
+        title: format!(
+            "閲覧者の自己紹介（所属: {}）",
+            viewer.company_slug.as_deref().unwrap_or("不明")
+        ),
+        text: truncate_chars(&viewer.short_description, MAX_PASSAGE_CHARS),
+        distance: viewer.distance,
+    }
+}
+
+fn document_passage(document: RetrievedDocument) -> RagPassage {
+    use storage::documents::InsightDocumentKind;
+
+    let kind = match document.kind {
+        InsightDocumentKind::ScoutMessage => RagSourceKind::ScoutMessage,
+        InsightDocumentKind::Note => RagSourceKind::Note,
+    };
+    let mut text = format!("日付: {}", document.occurred_at.format("%Y-%m-%d"));
+    if let Some(slug) = &document.company_slug {
+        text.push_str(&format!("\n会社: {slug}"));
+    }
+    text.push('\n');
+    text.push_str(&truncate_chars(document.body.trim(), MAX_PASSAGE_CHARS));
+
+    RagPassage {
+        citation: 0,
+        kind,
+        reference: document.document_id.to_string(),
+        title: format!(
+            "{}: {}",
+            document_kind_label(document.kind),
+            document.title.trim()
+        ),
+        text,
+        distance: document.distance,
+    }
+}
+
+/// system にルール、user に質問と `[n] 見出し` で始まる資料を入れる
+fn build_messages(question: &str, passages: &[RagPassage]) -> Vec<ChatMessage> {
+    let mut prompt = format!("質問: {}\n\n## 資料\n", question.trim());
+    if passages.is_empty() {
+        prompt.push_str("（該当する資料はありません）\n");
+    }
+    for passage in passages {
+        prompt.push_str(&format!("\n[{}] {}\n", passage.citation, passage.title));
+        if !passage.text.is_empty() {
+            prompt.push_str(&passage.text);
+            prompt.push('\n');
+        }
+    }
+
+    vec![
+        ChatMessage::system(SYSTEM_PROMPT),
+        ChatMessage::user(prompt),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use storage::documents::InsightDocumentKind;
+
+    use crate::infra::llm::{complete_chat, local::LocalLlmClient};
+
+    fn company(slug: &str, distance: f64) -> RetrievedCompany {
+        RetrievedCompany {
+            company_id: 1,
+            company_slug: slug.into(),
+            name: Some("Example".into()),
+            domain: None,
+            industry: Some("SaaS".into()),
+            employee_band: None,
+            funding_stage: None,
+            location: None,
+            views: 3,
+            distance,
+        }
+    }
+
+    fn document(id: i64, distance: f64) -> RetrievedDocument {
+        RetrievedDocument {
+            document_id: id,
+            kind: InsightDocumentKind::ScoutMessage,
+            title: "面談のお誘い".into(),
+            body: "ぜひ一度お話ししましょう".into(),
+            company_slug: Some("co_a".into()),
+            occurred_at: Utc.with_ymd_and_hms(2025, 12, 1, 0, 0, 0).unwrap(),
+            distance,
+        }
+    }
+
+    #[test]
+    fn passages_are_merged_by_distance_and_numbered() {
+        let viewer = RetrievedViewerDescription {
+            viewer_id: 7,
+            short_description: "PM".into(),
+            company_slug: None,
+            distance: 0.2,
+        };
+
+        let passages = merge_passages(
+            vec![company("co_a", 0.3)],
+            vec![viewer],
+            vec![document(5, 0.1), document(6, 0.9)],
+            3,
+        );
+
+        assert_eq!(
+            passages
+                .iter()
+                .map(|p| (p.citation, p.kind, p.reference.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, RagSourceKind::ScoutMessage, "5"),
+                (2, RagSourceKind::ViewerDescription, "7"),
+                (3, RagSourceKind::Company, "co_a"),
+            ]
+        );
+        assert_eq!(passages[1].title, "閲覧者の自己紹介（所属: 不明）");
+        assert_eq!(passages[2].text, "slug: co_a\n業種: SaaS\n閲覧数: 3");
+    }
+
+    #[test]
+    fn prompt_lists_passages_under_citation_headings() {
+        let passages = merge_passages(
+            vec![company("co_a", 0.3)],
+            vec![],
+            vec![document(5, 0.1)],
+            5,
+        );
+
+        let messages = build_messages(" どんな会社から見られた？ ", &passages);
+
+        assert_eq!(messages[0], ChatMessage::system(SYSTEM_PROMPT));
+        assert_eq!(
+            messages[1].content,
+            "質問: どんな会社から見られた？\n\n## 資料\n\
+             \n[1] スカウト: 面談のお誘い\n日付: 2025-12-01\n会社: co_a\nぜひ一度お話ししましょう\n\
+             \n[2] 会社: Example\nslug: co_a\n業種: SaaS\n閲覧数: 3\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn local_client_cites_prompt_headings() {
+        let passages = merge_passages(vec![company("co_a", 0.3)], vec![], vec![], 5);
+
+        let answer = complete_chat(&LocalLlmClient, &build_messages("質問", &passages))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            answer,
+            "関連する資料は次のとおりです。\n- [1] 会社: Example\n"
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use sqlx::PgPool;
+use storage::documents::{
+    InsightDocumentEmbeddingSource, InsightDocumentError, list_document_embedding_sources,
+    upsert_document_embedding,
+};
+use storage::wantedly::{
+    WantedlyCompanyEmbeddingSource, WantedlyEmbeddingError, WantedlyViewerDescription,
+    WantedlyViewerDescriptionEmbeddingKey, delete_viewer_description_embeddings,
+    list_company_embedding_sources, list_viewer_description_embedding_keys,
+    list_viewer_descriptions, upsert_company_embedding, upsert_viewer_description_embedding,
+};
+use thiserror::Error;
+
+use crate::infra::{
+    digest::sha256_hex,
+    embedding::{
+        Embedder, EmbeddingError,
+        document::{company_document, insight_document},
+        embed_checked,
+    },
+};
+
+/// 1 回の埋め込みリクエストに入れる文書数の既定値
+pub const DEFAULT_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Error)]
+pub enum EmbeddingBackfillError {
+    #[error(transparent)]
+    Storage(#[from] WantedlyEmbeddingError),
+
+    #[error(transparent)]
+    Documents(#[from] InsightDocumentError),
+
+    #[error(transparent)]
+    Embedding(#[from] EmbeddingError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct EmbeddingBackfillCounts {
+    /// 新しく、または作り直して埋め込んだ件数
+    pub embedded: usize,
+    /// 文書もモデルも変わっていない件数
+    pub up_to_date: usize,
+    /// 元の文書がなくなって消した件数
+    pub deleted: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EmbeddingBackfillReport {
+    pub model: String,
+    pub companies: EmbeddingBackfillCounts,
+    pub viewer_descriptions: EmbeddingBackfillCounts,
+    /// スカウト・メモ（文書を消すと埋め込みも一緒に消えるので deleted は常に 0）
+    pub documents: EmbeddingBackfillCounts,
+}
+
+/// 埋め込み待ちの文書（key は会社 id / 閲覧者 id / 文書 id）
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingEmbedding {
+    key: i64,
+    text: String,
+    /// 会社・文書は sha256、自己紹介は pii_digest（平文のハッシュを残さないため）
+    content_digest: String,
+}
+
+/// 埋め込みがない、または文書・モデルが変わった会社・閲覧者の自己紹介・スカウトとメモを埋め込む
+pub async fn backfill_embeddings(
+    pool: &PgPool,
+    embedder: &dyn Embedder,
+    batch_size: usize,
+) -> Result<EmbeddingBackfillReport, EmbeddingBackfillError> {
+    let model = embedder.model().to_string();
+    let batch_size = batch_size.max(1);
+
+    let sources = list_company_embedding_sources(pool).await?;
+    let (pending, up_to_date) = plan_company_embeddings(&sources, &model);
+    let mut companies = EmbeddingBackfillCounts {
+        up_to_date,
+        ..Default::default()
+    };
+    for batch in pending.chunks(batch_size) {
+        let vectors = embed_batch(embedder, batch).await?;
+        let mut tx = pool.begin().await?;
+        for (item, vector) in batch.iter().zip(&vectors) {
+            upsert_company_embedding(&mut *tx, item.key, &model, &item.content_digest, vector)
+                .await?;
+        }
+        tx.commit().await?;
+        companies.embedded += batch.len();
+    }
+
+    let descriptions = list_viewer_descriptions(pool).await?;
+    let existing = list_viewer_description_embedding_keys(pool).await?;
+    let plan = plan_viewer_description_embeddings(&descriptions, &existing, &model);
+    let mut viewer_descriptions = EmbeddingBackfillCounts {
+        up_to_date: plan.up_to_date,
+        deleted: delete_viewer_description_embeddings(pool, &plan.stale_ids).await?,
+        ..Default::default()
+    };
+    for batch in plan.pending.chunks(batch_size) {
+        let vectors = embed_batch(embedder, batch).await?;
+        let mut tx = pool.begin().await?;
+        for (item, vector) in batch.iter().zip(&vectors) {
+            upsert_viewer_description_embedding(
+                &mut *tx,
+                item.key,
+                &item.content_digest,
+                &model,
+                vector,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        viewer_descriptions.embedded += batch.len();
+    }
+
+    let sources = list_document_embedding_sources(pool).await?;
+    let (pending, up_to_date) = plan_document_embeddings(&sources, &model);
+    let mut documents = EmbeddingBackfillCounts {
+        up_to_date,
+        ..Default::default()
+    };
+    for batch in pending.chunks(batch_size) {
+        let vectors = embed_batch(embedder, batch).await?;
+        let mut tx = pool.begin().await?;
+        for (item, vector) in batch.iter().zip(&vectors) {
+            upsert_document_embedding(&mut *tx, item.key, &model, &item.content_digest, vector)
+                .await?;
+        }
+        tx.commit().await?;
+        documents.embedded += batch.len();
+    }
+
+    Ok(EmbeddingBackfillReport {
+        model,
+        companies,
+        viewer_descriptions,
+        documents,
+    })
+}
+
+async fn embed_batch(
+    embedder: &dyn Embedder,
+    batch: &[PendingEmbedding],
+) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    let texts: Vec<String> = batch.iter().map(|item| item.text.clone()).collect();
+    embed_checked(embedder, &texts).await
+}
+
+/// （埋め込む会社, 最新の会社数）
+fn plan_company_embeddings(
+    sources: &[WantedlyCompanyEmbeddingSource],
+    model: &str,
+) -> (Vec<PendingEmbedding>, usize) {
+    let mut pending = Vec::new();
+    let mut up_to_date = 0;
+
+    for source in sources {
+        let text = company_document(source);
+        let content_sha256 = sha256_hex(text.as_bytes());
+        if source.embedded_model.as_deref() == Some(model)
+            && source.embedded_sha256.as_deref() == Some(content_sha256.as_str())
+        {
+            up_to_date += 1;
+            continue;
+        }
+        pending.push(PendingEmbedding {
+            key: source.company_id,
+            text,
+            content_digest: content_sha256,
+        });
+    }
+
+    (pending, up_to_date)
+}
+
+/// （埋め込む文書, 最新の文書数）
+fn plan_document_embeddings(
+    sources: &[InsightDocumentEmbeddingSource],
+    model: &str,
+) -> (Vec<PendingEmbedding>, usize) {
+    let mut pending = Vec::new();
+    let mut up_to_date = 0;
+
+    for source in sources {
+        let text = insight_document(source.kind, &source.title, &source.body);
+        let content_sha256 = sha256_hex(text.as_bytes());
+        if source.embedded_model.as_deref() == Some(model)
+            && source.embedded_sha256.as_deref() == Some(content_sha256.as_str())
+        {
+            up_to_date += 1;
+            continue;
+        }
+        pending.push(PendingEmbedding {
+            key: source.document_id,
+            text,
+            content_digest: content_sha256,
+        });
+    }
+
+    (pending, up_to_date)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ViewerDescriptionPlan {
+    pending: Vec<PendingEmbedding>,
+    up_to_date: usize,
+    /// 所属履歴から消えた自己紹介の埋め込み
+    stale_ids: Vec<i64>,
+}
+
+fn plan_viewer_description_embeddings(
+    descriptions: &[WantedlyViewerDescription],
+    existing: &[WantedlyViewerDescriptionEmbeddingKey],
+    model: &str,
+) -> ViewerDescriptionPlan {
+    let existing_by_key: HashMap<(i64, &str), &WantedlyViewerDescriptionEmbeddingKey> = existing
+        .iter()
+        .map(|e| ((e.viewer_id, e.content_digest.as_str()), e))
+        .collect();
+
+    let mut plan = ViewerDescriptionPlan::default();
+    let mut current = HashSet::new();
+    for description in descriptions {
+        let content_digest = description.content_digest.clone();
+        current.insert((description.viewer_id, content_digest.clone()));
+
+        match existing_by_key.get(&(description.viewer_id, content_digest.as_str())) {
+            Some(e) if e.model == model => plan.up_to_date += 1,
+            _ => plan.pending.push(PendingEmbedding {
+                key: description.viewer_id,
+                text: description.short_description.clone(),
+                content_digest,
+            }),
+        }
+    }
+
+    plan.stale_ids = existing
+        .iter()
+        .filter(|e| !current.contains(&(e.viewer_id, e.content_digest.clone())))
+        .map(|e| e.id)
+        .collect();
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn company(
+        id: i64,
+        slug: &str,
+        embedded: Option<(&str, String)>,
+    ) -> WantedlyCompanyEmbeddingSource {
+        WantedlyCompanyEmbeddingSource {
+            company_id: id,
+            company_slug: slug.into(),
+            name: None,
+            domain: None,
+            industry: None,
+            employee_band: None,
+            funding_stage: None,
+            location: None,
+            embedded_model: embedded.as_ref().map(|(m, _)| m.to_string()),
+            embedded_sha256: embedded.map(|(_, sha)| sha),
+        }
+    }
+
+    #[test]
+    fn companies_are_embedded_when_missing_or_stale() {
+        let current = sha256_hex("会社: co_b".as_bytes());
+        let sources = vec![
+            company(1, "co_a", None),
+            company(2, "co_b", Some(("m1", current.clone()))),
+            company(3, "co_c", Some(("m1", "outdated".into()))),
+            company(4, "co_b", Some(("m0", current))),
+        ];
+
+        let (pending, up_to_date) = plan_company_embeddings(&sources, "m1");
+
+        assert_eq!(up_to_date, 1);
+        assert_eq!(
+            pending.iter().map(|p| p.key).collect::<Vec<_>>(),
+            vec![1, 3, 4]
+        );
+        assert_eq!(pending[0].text, "会社: co_a");
+    }
+
+    #[test]
+    fn documents_are_embedded_when_missing_or_stale() {
+        let document = |id: i64, embedded: Option<(&str, String)>| InsightDocumentEmbeddingSource {
+            document_id: id,
+            kind: storage::documents::InsightDocumentKind::Note,
+            title: "面談".into(),
+            body: "カジュアル面談".into(),
+            embedded_model: embedded.as_ref().map(|(m, _)| m.to_string()),
+            embedded_sha256: embedded.map(|(_, sha)| sha),
+        };
+        let current = sha256_hex("メモ: 面談\nカジュアル面談".as_bytes());
+        let sources = vec![
+            document(1, None),
+            document(2, Some(("m1", current))),
+            document(3, Some(("m1", "outdated".into()))),
+        ];
+
+        let (pending, up_to_date) = plan_document_embeddings(&sources, "m1");
+
+        assert_eq!(up_to_date, 1);
+        assert_eq!(
+            pending.iter().map(|p| p.key).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn viewer_descriptions_are_diffed_against_stored_digests() {
+        let key = |id: i64, viewer_id: i64, text: &str, model: &str| {
+            WantedlyViewerDescriptionEmbeddingKey {
+                id,
+                viewer_id,
+                content_digest: format!("hmac:{text}"),
+                model: model.into(),
+                embedded_at: Utc::now(),
+            }
+        };
+        let description = |viewer_id: i64, text: &str| WantedlyViewerDescription {
+            viewer_id,
+            short_description: text.into(),
+            content_digest: format!("hmac:{text}"),
+        };
+
+        let descriptions = vec![
+            description(1, "PM"),
+            description(1, "Engineer"),
+            description(2, "Designer"),
+        ];
+        let existing = vec![
+            key(10, 1, "PM", "m1"),
+            key(11, 2, "Designer", "m0"),
+            key(12, 2, "Sales", "m1"),
+        ];
+
+        let plan = plan_viewer_description_embeddings(&descriptions, &existing, "m1");
+
+        assert_eq!(plan.up_to_date, 1);
+        assert_eq!(
+            plan.pending
+                .iter()
+                .map(|p| (p.key, p.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, "Engineer"), (2, "Designer")]
+        );
+        assert_eq!(plan.stale_ids, vec![12]);
+    }
+}
@@ -0,0 +1,36 @@
+use std::fs;
+use std::path::Path;
+
+use sqlx::PgPool;
+use storage::backup::{BACKUP_TABLES, dump_table_rows, latest_applied_migration};
+
+use crate::infra::backup::{BackupError, BackupManifest, BackupTableData, write_archive};
+
+/// 全テーブルを 1 つのスナップショット（REPEATABLE READ）から読み出してアーカイブに書く
+pub async fn backup_insights_data(
+    pool: &PgPool,
+    output: &Path,
+) -> Result<BackupManifest, BackupError> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    let schema_version = latest_applied_migration(&mut tx).await?;
+
+    let mut tables = Vec::with_capacity(BACKUP_TABLES.len());
+    for table in BACKUP_TABLES {
+        let rows = dump_table_rows(&mut tx, table).await?;
+        tables.push(BackupTableData::from_rows(table, &rows));
+    }
+    tx.commit().await?;
+
+    let manifest = write_archive(output, schema_version, &tables)?;
+    tracing::info!(path = %output.display(), ?schema_version, "wrote backup archive");
+
+    Ok(manifest)
+}
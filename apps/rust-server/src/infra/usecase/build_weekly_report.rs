@@ -0,0 +1,162 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use storage::hypotheses::{HypothesisError, list_hypotheses, list_interventions};
+use storage::wantedly::{
+    WantedlyLeaderboardError, WantedlyLeaderboardSort, WantedlyOwnProfileError,
+    WantedlyRepeatViewError, WantedlySnapshotImportError, WantedlySortOrder, WantedlyTimeBucket,
+    WantedlyTimeSeriesError, list_company_leaderboard, list_new_companies,
+    list_own_profile_versions, list_repeat_companies, list_repeat_viewers,
+    list_snapshot_imports_between, list_view_time_series, summarize_views,
+};
+use thiserror::Error;
+
+use crate::infra::{
+    analytics::{
+        period::{JstPeriod, PeriodError},
+        profile_versions::{build_own_profile_timeline, jst_date},
+    },
+    report::{ReportIntervention, WeeklyReport},
+    usecase::detect_view_anomalies::{ViewAnomalyError, list_view_anomalies_with_companies},
+};
+
+const DEFAULT_DAYS: u32 = 7;
+const MAX_DAYS: u32 = 93;
+const TOP_COMPANIES: i64 = 10;
+const NEW_COMPANIES: i64 = 50;
+/// 繰り返し閲覧の人数・社数を数えるために読む上限
+const REPEAT_LIMIT: i64 = 1000;
+const REPEAT_MIN_VIEW_DAYS: i64 = 2;
+
+#[derive(Debug, Error)]
+pub enum WeeklyReportError {
+    #[error(transparent)]
+    TimeSeries(#[from] WantedlyTimeSeriesError),
+
+    #[error(transparent)]
+    Leaderboard(#[from] WantedlyLeaderboardError),
+
+    #[error(transparent)]
+    Repeat(#[from] WantedlyRepeatViewError),
+
+    #[error(transparent)]
+    Anomaly(#[from] ViewAnomalyError),
+
+    #[error(transparent)]
+    SnapshotImport(#[from] WantedlySnapshotImportError),
+
+    #[error(transparent)]
+    OwnProfile(#[from] WantedlyOwnProfileError),
+
+    #[error(transparent)]
+    Hypothesis(#[from] HypothesisError),
+}
+
+/// 期間の省略時は today（JST）までの 7 日。最長 93 日
+pub fn resolve_report_period(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<JstPeriod, PeriodError> {
+    JstPeriod::resolve(from, to, DEFAULT_DAYS, MAX_DAYS, today)
+}
+
+/// 期間と直前の同じ長さの期間を集計し、レポートの材料をそろえる
+pub async fn build_weekly_report(
+    pool: &PgPool,
+    period: JstPeriod,
+) -> Result<WeeklyReport, WeeklyReportError> {
+    let JstPeriod { from, to } = period;
+    let previous_period = period.previous();
+
+    let totals = summarize_views(pool, from, to).await?;
+    let previous_totals = summarize_views(pool, previous_period.from, previous_period.to).await?;
+    let daily = list_view_time_series(pool, from, to, WantedlyTimeBucket::Day).await?;
+    let previous_daily = list_view_time_series(
+        pool,
+        previous_period.from,
+        previous_period.to,
+        WantedlyTimeBucket::Day,
+    )
+    .await?;
+
+    let top_companies = list_company_leaderboard(
+        pool,
+        from,
+        to,
+        WantedlyLeaderboardSort::Views,
+        WantedlySortOrder::Desc,
+        TOP_COMPANIES,
+    )
+    .await?;
+    let new_companies = list_new_companies(pool, from, to, NEW_COMPANIES).await?;
+
+    let repeat_viewers =
+        list_repeat_viewers(pool, from, to, REPEAT_MIN_VIEW_DAYS, REPEAT_LIMIT).await?;
+    let repeat_companies =
+        list_repeat_companies(pool, from, to, REPEAT_MIN_VIEW_DAYS, REPEAT_LIMIT).await?;
+
+    let anomalies = list_view_anomalies_with_companies(pool, period, None).await?;
+    let snapshot_imports = list_snapshot_imports_between(pool, from, to).await?;
+
+    // 差分は期間より前の版とも比べるので、全版から時系列を作ってから絞り込む
+    let versions = list_own_profile_versions(pool).await?;
+    let profile_versions = build_own_profile_timeline(&versions, &[], to)
+        .versions
+        .into_iter()
+        .filter(|v| (from..=to).contains(&jst_date(v.captured_at)))
+        .collect();
+
+    let hypotheses = list_hypotheses(pool).await?;
+    let interventions = list_interventions(pool)
+        .await?
+        .into_iter()
+        .filter(|i| (from..=to).contains(&i.changed_on))
+        .map(|intervention| ReportIntervention {
+            hypothesis_title: hypotheses
+                .iter()
+                .find(|h| h.id == intervention.hypothesis_id)
+                .map(|h| h.title.clone())
+                .unwrap_or_default(),
+            intervention,
+        })
+        .collect();
+
+    Ok(WeeklyReport {
+        period,
+        previous_period,
+        totals,
+        previous_totals,
+        daily,
+        previous_daily,
+        top_companies,
+        new_companies,
+        repeat_viewers,
+        repeat_companies,
+        anomalies,
+        snapshot_imports,
+        profile_versions,
+        interventions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_the_last_7_days() {
+        let today = NaiveDate::from_ymd_opt(2025, 11, 9).unwrap();
+
+        let period = resolve_report_period(None, None, today).unwrap();
+
+        assert_eq!(period.from, NaiveDate::from_ymd_opt(2025, 11, 3).unwrap());
+        assert_eq!(
+            period.previous().to,
+            NaiveDate::from_ymd_opt(2025, 11, 2).unwrap()
+        );
+        assert_eq!(
+            resolve_report_period(NaiveDate::from_ymd_opt(2025, 1, 1), None, today).unwrap_err(),
+            PeriodError::TooLong(MAX_DAYS)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use storage::wantedly::{
+    WantedlyRawJsonCompaction, WantedlyRawRetentionError, compact_profile_view_raw_json,
+    estimate_profile_view_raw_json_compaction, profile_view_raw_table_bytes,
+    vacuum_profile_view_raw,
+};
+
+/// 1 回の UPDATE で削る行数（ロックを長く持たないように分割する）
+const COMPACTION_BATCH_SIZE: i64 = 1000;
+
+/// viewed_at から何日経った raw_json を削るか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawJsonRetentionPolicy {
+    pub older_than_days: u32,
+}
+
+impl RawJsonRetentionPolicy {
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(i64::from(self.older_than_days))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RawJsonRetentionReport {
+    pub cutoff: DateTime<Utc>,
+    pub dry_run: bool,
+    pub compaction: WantedlyRawJsonCompaction,
+    pub reclaimed_bytes: i64,
+    pub table_bytes_before: i64,
+    pub table_bytes_after: i64,
+}
+
+/// 保持期間を過ぎた raw_json を、正規化をやり直せる項目だけに削る
+///
+/// dry_run なら対象件数と削減量の見積もりだけを返す。vacuum なら最後に VACUUM して
+/// 削った領域を再利用できるようにする。
+pub async fn apply_raw_json_retention(
+    pool: &PgPool,
+    policy: RawJsonRetentionPolicy,
+    dry_run: bool,
+    vacuum: bool,
+) -> Result<RawJsonRetentionReport, WantedlyRawRetentionError> {
+    let cutoff = policy.cutoff(Utc::now());
+    let table_bytes_before = profile_view_raw_table_bytes(pool).await?;
+
+    let compaction = if dry_run {
+        estimate_profile_view_raw_json_compaction(pool, cutoff).await?
+    } else {
+        let mut total = WantedlyRawJsonCompaction::default();
+        loop {
+            let batch = compact_profile_view_raw_json(pool, cutoff, COMPACTION_BATCH_SIZE).await?;
+            if batch.rows == 0 {
+                break;
+            }
+            total.rows += batch.rows;
+            total.bytes_before += batch.bytes_before;
+            total.bytes_after += batch.bytes_after;
+        }
+        total
+    };
+
+    if vacuum && !dry_run {
+        vacuum_profile_view_raw(pool).await?;
+    }
+    let table_bytes_after = profile_view_raw_table_bytes(pool).await?;
+
+    Ok(RawJsonRetentionReport {
+        cutoff,
+        dry_run,
+        reclaimed_bytes: compaction.reclaimed_bytes(),
+        compaction,
+        table_bytes_before,
+        table_bytes_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::wantedly::dto::WantedlyProfileViewNode;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn cutoff_counts_back_from_now() {
+        let now = Utc.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).unwrap();
+        let policy = RawJsonRetentionPolicy {
+            older_than_days: 30,
+        };
+
+        assert_eq!(
+            policy.cutoff(now),
+            Utc.with_ymd_and_hms(2025, 12, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn compacted_payload_still_parses_as_node() {
+        // wantedly_compact_raw_json が残す形（null の項目は落ちる）
+        let compacted = json!({
+            "userId": 12345678,
+            "companyPageUrl": "https://www.wantedly.com/companies/company_xyz",
+            "profileImpressionMeta": { "impressedDateTime": "3日前" }
+        });
+
+        let node = WantedlyProfileViewNode::from_value(&compacted).unwrap();
+        assert_eq!(node.user_id, 12345678);
+        assert_eq!(node.short_description, None);
+        assert_eq!(node.profile_impression_meta.impressed_date_time, "3日前");
+    }
+}
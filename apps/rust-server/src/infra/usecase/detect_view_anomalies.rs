@@ -0,0 +1,156 @@
+use chrono::{Days, NaiveDate};
+use sqlx::PgPool;
+use storage::wantedly::{
+    WantedlyAnomalyKind, WantedlyTimeBucket, WantedlyTimeSeriesError, WantedlyViewAnomalyError,
+    list_view_anomalies, list_view_anomaly_companies, list_view_time_series,
+    replace_view_anomalies,
+};
+use thiserror::Error;
+
+use crate::infra::analytics::{
+    anomaly::{ViewAnomaly, ViewAnomalyReport, attach_top_companies, detect_anomalies},
+    period::{JstPeriod, PeriodError},
+};
+
+const DEFAULT_DAYS: u32 = 90;
+const MAX_DAYS: u32 = 1100;
+const DEFAULT_WINDOW_DAYS: u32 = 28;
+const MIN_WINDOW_DAYS: u32 = 7;
+const MAX_WINDOW_DAYS: u32 = 180;
+const DEFAULT_THRESHOLD: f64 = 3.5;
+/// 異常のあった日ごとに記録する会社の数
+const TOP_COMPANIES: i64 = 5;
+
+#[derive(Debug, Error)]
+pub enum ViewAnomalyError {
+    #[error(transparent)]
+    TimeSeries(#[from] WantedlyTimeSeriesError),
+
+    #[error(transparent)]
+    Anomaly(#[from] WantedlyViewAnomalyError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ViewAnomalyOptionsError {
+    #[error(transparent)]
+    Period(#[from] PeriodError),
+
+    #[error("`window_days` must be between {MIN_WINDOW_DAYS} and {MAX_WINDOW_DAYS}")]
+    WindowDays,
+
+    #[error("`threshold` must be greater than 0")]
+    Threshold,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ViewAnomalyOptions {
+    /// 判定する日の範囲（JST）
+    pub period: JstPeriod,
+    /// 基準にする直前の日数
+    pub window_days: u32,
+    /// |score| がこれ以上なら異常とみなす
+    pub threshold: f64,
+}
+
+impl ViewAnomalyOptions {
+    /// 期間の省略時は today（JST）までの 90 日、window_days は 28、threshold は 3.5
+    pub fn resolve(
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        window_days: Option<u32>,
+        threshold: Option<f64>,
+        today: NaiveDate,
+    ) -> Result<Self, ViewAnomalyOptionsError> {
+        let period = JstPeriod::resolve(from, to, DEFAULT_DAYS, MAX_DAYS, today)?;
+
+        let window_days = window_days.unwrap_or(DEFAULT_WINDOW_DAYS);
+        if !(MIN_WINDOW_DAYS..=MAX_WINDOW_DAYS).contains(&window_days) {
+            return Err(ViewAnomalyOptionsError::WindowDays);
+        }
+
+        let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+        if !(threshold > 0.0 && threshold.is_finite()) {
+            return Err(ViewAnomalyOptionsError::Threshold);
+        }
+
+        Ok(Self {
+            period,
+            window_days,
+            threshold,
+        })
+    }
+}
+
+/// 期間内の各日の閲覧数を直前 window_days 日と比べ、異常を wantedly_view_anomalies に記録し直す
+pub async fn detect_view_anomalies(
+    pool: &PgPool,
+    options: &ViewAnomalyOptions,
+) -> Result<ViewAnomalyReport, ViewAnomalyError> {
+    let JstPeriod { from, to } = options.period;
+    let history_from = from - Days::new(u64::from(options.window_days));
+
+    let daily = list_view_time_series(pool, history_from, to, WantedlyTimeBucket::Day).await?;
+    let detected = detect_anomalies(
+        &daily,
+        from,
+        options.window_days as usize,
+        options.threshold,
+    );
+
+    let mut tx = pool.begin().await?;
+    replace_view_anomalies(&mut tx, from, to, &detected, TOP_COMPANIES).await?;
+    tx.commit().await?;
+
+    Ok(ViewAnomalyReport {
+        period: options.period,
+        window_days: options.window_days,
+        threshold: options.threshold,
+        anomalies: list_view_anomalies_with_companies(pool, options.period, None).await?,
+    })
+}
+
+/// 記録済みの異常を、その日に多く閲覧した会社と合わせて返す
+pub async fn list_view_anomalies_with_companies(
+    pool: &PgPool,
+    period: JstPeriod,
+    kind: Option<WantedlyAnomalyKind>,
+) -> Result<Vec<ViewAnomaly>, ViewAnomalyError> {
+    let anomalies = list_view_anomalies(pool, period.from, period.to, kind).await?;
+    let ids: Vec<i64> = anomalies.iter().map(|a| a.id).collect();
+    let companies = list_view_anomaly_companies(pool, &ids).await?;
+
+    Ok(attach_top_companies(anomalies, companies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()
+    }
+
+    #[test]
+    fn defaults_check_the_last_90_days_against_4_weeks() {
+        let options = ViewAnomalyOptions::resolve(None, None, None, None, today()).unwrap();
+
+        assert_eq!(options.period.days(), 90);
+        assert_eq!(options.window_days, 28);
+        assert_eq!(options.threshold, 3.5);
+    }
+
+    #[test]
+    fn rejects_out_of_range_options() {
+        assert_eq!(
+            ViewAnomalyOptions::resolve(None, None, Some(3), None, today()).unwrap_err(),
+            ViewAnomalyOptionsError::WindowDays
+        );
+        assert_eq!(
+            ViewAnomalyOptions::resolve(None, None, None, Some(0.0), today()).unwrap_err(),
+            ViewAnomalyOptionsError::Threshold
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use storage::wantedly::{
+    CompanyAttributeProvenance, CompanyAttributeSource, WantedlyCompanyAttributesInput,
+    WantedlyCompanyError, WantedlyEnrichmentCandidate, WantedlyEnrichmentError,
+    clear_enrichment_failure, list_company_viewer_descriptions, list_enrichment_candidates,
+    record_enrichment_failure, upsert_company_attributes,
+};
+use thiserror::Error;
+
+use crate::infra::llm::{ChatMessage, LlmClient, LlmError, complete_chat, truncate_chars};
+
+/// プロンプトの版（プロンプトや検証を変えたら上げる）
+pub const ENRICHMENT_PROMPT_VERSION: &str = "enrich-v1";
+
+/// 1 回の実行で推定する会社数の既定値
+pub const DEFAULT_ENRICHMENT_LIMIT: i64 = 50;
+
+/// プロンプトに入れる自己紹介の件数と長さの上限
+pub const DESCRIPTIONS_PER_COMPANY: i64 = 10;
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// 却下した会社を次に推定するまでの間隔（却下が続くたびに倍にし、上限で止める）
+const RETRY_BASE_HOURS: i64 = 1;
+const RETRY_MAX_HOURS: i64 = 24 * 7;
+
+const MAX_NAME_CHARS: usize = 100;
+const MAX_INDUSTRY_CHARS: usize = 50;
+
+const SYSTEM_PROMPT: &str = "\
+あなたは Wantedly に掲載されている会社の基本情報を推定するアシスタントです。\
+会社ページの slug と URL、その会社に所属する閲覧者の自己紹介から、会社名・公式サイトのドメイン・業種を推定してください。\
+次の形の JSON だけを返してください: \
+{\"name\": 文字列または null, \"domain\": 文字列または null, \"industry\": 文字列または null, \"confidence\": 0 以上 1 以下の数}\n\
+- domain は公式サイトのホスト名（例: example.co.jp）。wantedly.com は公式サイトではない\n\
+- industry は英小文字の短い語（例: saas, hr, fintech, consulting）\n\
+- 分からない項目は null にし、confidence には推定全体の確からしさを入れる";
+
+#[derive(Debug, Error)]
+pub enum CompanyEnrichmentError {
+    #[error(transparent)]
+    Storage(#[from] WantedlyEnrichmentError),
+
+    #[error(transparent)]
+    Company(#[from] WantedlyCompanyError),
+
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CompanyEnrichmentOptions {
+    pub limit: i64,
+    /// 別のプロンプトの版・モデルで推定した AI の行も推定し直す
+    pub refresh_stale: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedEnrichment {
+    pub company_slug: String,
+    pub reason: String,
+    /// 同じプロンプトの版・モデルで続けて却下された回数（今回を含む）
+    pub failures: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompanyEnrichmentReport {
+    pub prompt_version: &'static str,
+    pub model: String,
+    pub candidates: usize,
+    /// 属性を書き込んだ会社数
+    pub enriched: usize,
+    /// 推定中に手入力された会社数（上書きしない）
+    pub skipped_manual: usize,
+    /// 応答が JSON として読めない・検証に通らなかった会社
+    pub rejected: Vec<RejectedEnrichment>,
+}
+
+/// LLM の応答を検証したもの
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompanyEnrichment {
+    pub name: Option<String>,
+    pub domain: Option<String>,
+    pub industry: Option<String>,
+    /// 小数 2 桁に丸めた 0.00 〜 1.00
+    pub confidence: f32,
+}
+
+impl CompanyEnrichment {
+    fn input(&self) -> WantedlyCompanyAttributesInput {
+        WantedlyCompanyAttributesInput {
+            name: self.name.clone(),
+            domain: self.domain.clone(),
+            industry: self.industry.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 属性のない会社について、slug・URL・閲覧者の自己紹介から LLM に会社名・ドメイン・業種を推定させて
+/// source = ai で書き込む
+///
+/// 応答を却下した会社は記録して間を空ける（次の実行では後ろの会社に進む）。
+/// LLM への接続・API のエラーでは中断する（それまでに書き込んだ会社はそのまま）。
+pub async fn enrich_company_attributes(
+    pool: &PgPool,
+    llm: &dyn LlmClient,
+    options: &CompanyEnrichmentOptions,
+) -> Result<CompanyEnrichmentReport, CompanyEnrichmentError> {
+    let model = llm.model().to_string();
+    let candidates = list_enrichment_candidates(
+        pool,
+        ENRICHMENT_PROMPT_VERSION,
+        &model,
+        options.refresh_stale,
+        options.limit,
+    )
+    .await?;
+
+    let company_ids: Vec<i64> = candidates.iter().map(|c| c.company_id).collect();
+    let mut descriptions: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in
+        list_company_viewer_descriptions(pool, &company_ids, DESCRIPTIONS_PER_COMPANY).await?
+    {
+        descriptions
+            .entry(row.company_id)
+            .or_default()
+            .push(row.short_description);
+    }
+
+    let provenance = |confidence: f32| CompanyAttributeProvenance {
+        source: CompanyAttributeSource::Ai,
+        confidence: Some(confidence),
+        prompt_version: Some(ENRICHMENT_PROMPT_VERSION.to_string()),
+        model: Some(model.clone()),
+    };
+
+    let mut report = CompanyEnrichmentReport {
+        prompt_version: ENRICHMENT_PROMPT_VERSION,
+        model: model.clone(),
+        candidates: candidates.len(),
+        enriched: 0,
+        skipped_manual: 0,
+        rejected: Vec::new(),
+    };
+    for candidate in &candidates {
+        let company_descriptions = descriptions
+            .get(&candidate.company_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let enrichment =
+            match infer_company_attributes(llm, candidate, company_descriptions).await? {
+                Ok(enrichment) => enrichment,
+                Err(reason) => {
+                    let failures = candidate.failures + 1;
+                    record_enrichment_failure(
+                        pool,
+                        candidate.company_id,
+                        ENRICHMENT_PROMPT_VERSION,
+                        &model,
+                        failures,
+                        &reason,
+                        retry_delay(failures),
+                    )
+                    .await?;
+                    report.rejected.push(RejectedEnrichment {
+                        company_slug: candidate.company_slug.clone(),
+                        reason,
+                        failures,
+                    });
+                    continue;
+                }
+            };
+
+        let written = upsert_company_attributes(
+            pool,
+            candidate.company_id,
+            &enrichment.input(),
+            &provenance(enrichment.confidence),
+        )
+        .await?;
+        clear_enrichment_failure(pool, candidate.company_id).await?;
+        match written {
+            Some(_) => report.enriched += 1,
+            None => report.skipped_manual += 1,
+        }
+    }
+
+    Ok(report)
+}
+
+/// failures 回続けて却下した会社を次に推定するまでの間隔
+fn retry_delay(failures: i32) -> Duration {
+    let exponent = failures.saturating_sub(1).clamp(0, 16) as u32;
+    let hours = RETRY_BASE_HOURS.saturating_mul(1 << exponent);
+    Duration::hours(hours.min(RETRY_MAX_HOURS))
+}
+
+/// 1 社ぶんを推定する（書き込まない）。Ok(Err(理由)) は応答の却下
+pub async fn infer_company_attributes(
+    llm: &dyn LlmClient,
+    candidate: &WantedlyEnrichmentCandidate,
+    descriptions: &[String],
+) -> Result<Result<CompanyEnrichment, String>, LlmError> {
+    let response = complete_chat(llm, &build_messages(candidate, descriptions)).await?;
+    Ok(parse_enrichment(&response))
+}
+
+/// system に出力の形、user に会社の手がかりを入れる
+fn build_messages(
+    candidate: &WantedlyEnrichmentCandidate,
+    descriptions: &[String],
+) -> Vec<ChatMessage> {
+    let mut prompt = format!(
+        "slug: {}\nURL: {}\n\n## 所属する閲覧者の自己紹介\n",
+        candidate.company_slug, candidate.company_page_url
+    );
+    if descriptions.is_empty() {
+        prompt.push_str("（なし）\n");
+    }
+    for description in descriptions {
+        prompt.push_str(&format!(
+            "- {}\n",
+            truncate_chars(&description.replace('\n', " "), MAX_DESCRIPTION_CHARS)
+        ));
+    }
+
+    vec![
+        ChatMessage::system(SYSTEM_PROMPT),
+        ChatMessage::user(prompt),
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEnrichment {
+    name: Option<String>,
+    domain: Option<String>,
+    industry: Option<String>,
+    confidence: Option<f64>,
+}
+
+/// 応答から JSON オブジェクトを取り出して検証する（Err は却下の理由）
+///
+/// confidence が範囲外なら却下する。各項目は形が不正なら null として扱う。
+fn parse_enrichment(response: &str) -> Result<CompanyEnrichment, String> {
+    // コードブロックや前置きの文で包まれていても、最初の { から最後の } までを読む
+    let json = match (response.find('{'), response.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => return Err("response has no JSON object".into()),
+    };
+    let raw: RawEnrichment =
+        serde_json::from_str(json).map_err(|e| format!("invalid JSON: {e}"))?;
+
+    let confidence = match raw.confidence {
+        Some(c) if (0.0..=1.0).contains(&c) => ((c * 100.0).round() / 100.0) as f32,
+        Some(c) => return Err(format!("confidence out of range: {c}")),
+        None => return Err("confidence is missing".into()),
+    };
+
+    Ok(CompanyEnrichment {
+        name: bounded_text(raw.name, MAX_NAME_CHARS),
+        domain: raw.domain.as_deref().and_then(normalize_domain),
+        industry: bounded_text(raw.industry, MAX_INDUSTRY_CHARS).map(|v| v.to_lowercase()),
+        confidence,
+    })
+}
+
+fn bounded_text(value: Option<String>, max_chars: usize) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && v.chars().count() <= max_chars)
+}
+
+/// URL で返ってきてもホスト名にそろえる。ホスト名として不正なもの・Wantedly 自身は捨てる
+pub fn normalize_domain(value: &str) -> Option<String> {
+    let value = value.trim().to_ascii_lowercase();
+    let value = value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"))
+        .unwrap_or(&value);
+    let host = value.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host
+        .strip_prefix("www.")
+        .unwrap_or(host)
+        .trim_end_matches('.');
+
+    let labels: Vec<&str> = host.split('.').collect();
+    let valid = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_lowercase()));
+    if !valid || host == "wantedly.com" || host.ends_with(".wantedly.com") {
+        return None;
+    }
+
+    Some(host.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fenced_json_is_parsed_and_normalized() {
+        let response = "```json\n{\"name\": \" Example 株式会社 \", \"domain\": \"https://www.Example.co.jp/about\", \"industry\": \"SaaS\", \"confidence\": 0.834}\n```";
+
+        assert_eq!(
+            parse_enrichment(response).unwrap(),
+            CompanyEnrichment {
+                name: Some("Example 株式会社".into()),
+                domain: Some("example.co.jp".into()),
+                industry: Some("saas".into()),
+                confidence: 0.83,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_fields_become_null() {
+        let enrichment = parse_enrichment(
+            r#"{"name": "", "domain": "www.wantedly.com", "industry": null, "confidence": 0.2}"#,
+        )
+        .unwrap();
+
+        assert_eq!(enrichment.name, None);
+        assert_eq!(enrichment.domain, None);
+        assert_eq!(enrichment.industry, None);
+        assert_eq!(normalize_domain("not a domain"), None);
+        assert_eq!(normalize_domain("example"), None);
+    }
+
+    #[test]
+    fn responses_without_valid_confidence_are_rejected() {
+        assert!(parse_enrichment("資料からは分かりません。").is_err());
+        assert!(parse_enrichment(r#"{"name": "Example"}"#).is_err());
+        assert!(parse_enrichment(r#"{"name": "Example", "confidence": 1.5}"#).is_err());
+        assert!(parse_enrichment(r#"{"name": 1, "confidence": 0.5}"#).is_err());
+    }
+
+    #[test]
+    fn rejected_companies_back_off_up_to_a_week() {
+        assert_eq!(retry_delay(1), Duration::hours(1));
+        assert_eq!(retry_delay(2), Duration::hours(2));
+        assert_eq!(retry_delay(4), Duration::hours(8));
+        assert_eq!(retry_delay(9), Duration::days(7));
+        assert_eq!(retry_delay(i32::MAX), Duration::days(7));
+    }
+
+    #[test]
+    fn prompt_lists_company_clues() {
+        let candidate = WantedlyEnrichmentCandidate {
+            company_id: 1,
+            company_slug: "co_a".into(),
+            company_page_url: "https://www.wantedly.com/companies/co_a".into(),
+            failures: 0,
+        };
+
+        let messages = build_messages(&candidate, &["人事\nSaaS".into()]);
+
+        assert_eq!(messages[0], ChatMessage::system(SYSTEM_PROMPT));
+        assert_eq!(
+            messages[1].content,
+            "slug: co_a\nURL: https://www.wantedly.com/companies/co_a\n\n\
+             ## 所属する閲覧者の自己紹介\n- 人事 SaaS\n"
+        );
+        assert!(
+            build_messages(&candidate, &[])[1]
+                .content
+                .ends_with("（なし）\n")
+        );
+    }
+}
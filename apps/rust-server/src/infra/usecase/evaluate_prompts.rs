@@ -0,0 +1,473 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use storage::evaluations::{
+    EvalRunError, EvalRunSummary, EvalSuite, NewEvalRun, find_latest_eval_run, insert_eval_run,
+};
+use storage::wantedly::{
+    WantedlyCompanyError, WantedlyEnrichmentCandidate, WantedlyEnrichmentError,
+    find_company_by_slug, list_company_viewer_descriptions,
+};
+use thiserror::Error;
+
+use crate::infra::{
+    digest::sha256_hex,
+    embedding::Embedder,
+    llm::{LlmClient, LlmError},
+    usecase::{
+        answer_question::{
+            RAG_PROMPT_VERSION, RagAnswer, RagError, RagSourceKind, answer_question,
+        },
+        enrich_company_attributes::{
+            CompanyEnrichment, DESCRIPTIONS_PER_COMPANY, ENRICHMENT_PROMPT_VERSION,
+            infer_company_attributes, normalize_domain,
+        },
+    },
+};
+
+/// 前回からこれより下がったら退行とみなす（浮動小数の誤差よけ）
+const REGRESSION_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Error)]
+pub enum EvalError {
+    #[error(transparent)]
+    Rag(#[from] RagError),
+
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+
+    #[error(transparent)]
+    Company(#[from] WantedlyCompanyError),
+
+    #[error(transparent)]
+    Enrichment(#[from] WantedlyEnrichmentError),
+
+    #[error(transparent)]
+    Storage(#[from] EvalRunError),
+
+    #[error("failed to serialize eval results: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 評価ケース（docs/evaluation.md 参照）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvalCases {
+    #[serde(default)]
+    pub rag: Vec<RagEvalCase>,
+    #[serde(default)]
+    pub enrichment: Vec<EnrichmentEvalCase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RagEvalCase {
+    pub id: String,
+    pub question: String,
+    /// 資料として引かれるべきもの
+    #[serde(default)]
+    pub expected_sources: Vec<ExpectedSource>,
+    /// 回答に含まれるべき語句
+    #[serde(default)]
+    pub expected_answer: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedSource {
+    pub kind: RagSourceKind,
+    /// 会社 slug / 閲覧者 id / 文書 id
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichmentEvalCase {
+    pub id: String,
+    pub company_slug: String,
+    /// null・省略した項目は採点しない
+    #[serde(default)]
+    pub expected: ExpectedAttributes,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExpectedAttributes {
+    pub name: Option<String>,
+    pub domain: Option<String>,
+    pub industry: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EvalOptions {
+    /// RAG の資料の件数
+    pub top_k: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RagCaseResult {
+    pub id: String,
+    /// 期待した資料のうち引けた割合（期待がなければ None）
+    pub retrieval_recall: Option<f64>,
+    /// 期待した語句のうち回答に含まれた割合（期待がなければ None）
+    pub answer_score: Option<f64>,
+    pub missing_sources: Vec<ExpectedSource>,
+    pub missing_phrases: Vec<String>,
+    pub answer: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnrichmentCaseResult {
+    pub id: String,
+    /// 期待した項目のうち一致した割合（期待がなければ None）
+    pub score: Option<f64>,
+    /// 一致しなかった項目
+    pub mismatches: Vec<String>,
+    /// 会社が見つからない・応答を却下したときの理由
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalRunReport {
+    pub run: EvalRunSummary,
+    /// 同じケースでの前回の実行
+    pub previous: Option<EvalRunSummary>,
+    /// 前回より retrieval_recall か score が下がった
+    pub regressed: bool,
+    pub results: serde_json::Value,
+}
+
+/// ケースのある評価を順に実行し、結果を eval_runs に保存する
+///
+/// 採点は決定的な文字列比較だけで行う。LLM には記録した応答を返す ReplayLlmClient を渡すと、
+/// 外部 API なしで同じ結果を再現できる。
+pub async fn evaluate_prompts(
+    pool: &PgPool,
+    embedder: &dyn Embedder,
+    llm: &dyn LlmClient,
+    cases: &EvalCases,
+    options: &EvalOptions,
+) -> Result<Vec<EvalRunReport>, EvalError> {
+    let mut reports = Vec::new();
+
+    if !cases.rag.is_empty() {
+        let mut results = Vec::with_capacity(cases.rag.len());
+        for case in &cases.rag {
+            let answer =
+                answer_question(pool, embedder, llm, &case.question, options.top_k).await?;
+            results.push(score_rag_case(case, &answer));
+        }
+
+        let run = NewEvalRun {
+            suite: EvalSuite::Rag,
+            prompt_version: RAG_PROMPT_VERSION.to_string(),
+            model: llm.model().to_string(),
+            embedding_model: Some(embedder.model().to_string()),
+            cases_sha256: cases_sha256(&cases.rag)?,
+            case_count: results.len() as i32,
+            retrieval_recall: mean(results.iter().filter_map(|r| r.retrieval_recall)),
+            score: mean(results.iter().filter_map(|r| r.answer_score)),
+            results: serde_json::to_value(&results)?,
+        };
+        reports.push(record_run(pool, run).await?);
+    }
+
+    if !cases.enrichment.is_empty() {
+        let mut results = Vec::with_capacity(cases.enrichment.len());
+        for case in &cases.enrichment {
+            let Some(company) = find_company_by_slug(pool, &case.company_slug).await? else {
+                results.push(EnrichmentCaseResult {
+                    id: case.id.clone(),
+                    score: Some(0.0),
+                    mismatches: Vec::new(),
+                    error: Some(format!("company not found: {}", case.company_slug)),
+                });
+                continue;
+            };
+            let descriptions: Vec<String> =
+                list_company_viewer_descriptions(pool, &[company.id], DESCRIPTIONS_PER_COMPANY)
+                    .await?
+                    .into_iter()
+                    .map(|row| row.short_description)
+                    .collect();
+            let candidate = WantedlyEnrichmentCandidate {
+                company_id: company.id,
+                company_slug: company.company_slug,
+                company_page_url: company.company_page_url,
+                failures: 0,
+            };
+
+            let outcome = infer_company_attributes(llm, &candidate, &descriptions).await?;
+            results.push(score_enrichment_case(case, &outcome));
+        }
+
+        let run = NewEvalRun {
+            suite: EvalSuite::Enrichment,
+            prompt_version: ENRICHMENT_PROMPT_VERSION.to_string(),
+            model: llm.model().to_string(),
+            embedding_model: None,
+            cases_sha256: cases_sha256(&cases.enrichment)?,
+            case_count: results.len() as i32,
+            retrieval_recall: None,
+            score: mean(results.iter().filter_map(|r| r.score)),
+            results: serde_json::to_value(&results)?,
+        };
+        reports.push(record_run(pool, run).await?);
+    }
+
+    Ok(reports)
+}
+
+async fn record_run(pool: &PgPool, new: NewEvalRun) -> Result<EvalRunReport, EvalError> {
+    let previous = find_latest_eval_run(pool, new.suite, &new.cases_sha256).await?;
+    let run = insert_eval_run(pool, &new).await?;
+
+    Ok(EvalRunReport {
+        regressed: previous.as_ref().is_some_and(|p| is_regression(p, &run)),
+        previous,
+        run,
+        results: new.results,
+    })
+}
+
+/// ケースの中身が変わったら別の版として扱う
+fn cases_sha256<T: Serialize>(cases: &[T]) -> Result<String, serde_json::Error> {
+    Ok(sha256_hex(&serde_json::to_vec(cases)?))
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+fn is_regression(previous: &EvalRunSummary, current: &EvalRunSummary) -> bool {
+    let dropped = |before: Option<f64>, after: Option<f64>| match (before, after) {
+        (Some(before), Some(after)) => after < before - REGRESSION_TOLERANCE,
+        _ => false,
+    };
+    dropped(previous.retrieval_recall, current.retrieval_recall)
+        || dropped(previous.score, current.score)
+}
+
+/// 大文字小文字と空白の違いを無視して比べる
+fn normalize_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn score_rag_case(case: &RagEvalCase, answer: &RagAnswer) -> RagCaseResult {
+    let missing_sources: Vec<ExpectedSource> = case
+        .expected_sources
+        .iter()
+        .filter(|expected| {
+            !answer
+                .passages
+                .iter()
+                .any(|p| p.kind == expected.kind && p.reference == expected.reference)
+        })
+        .cloned()
+        .collect();
+
+    let normalized_answer = normalize_text(&answer.answer);
+    let missing_phrases: Vec<String> = case
+        .expected_answer
+        .iter()
+        .filter(|phrase| !normalized_answer.contains(&normalize_text(phrase)))
+        .cloned()
+        .collect();
+
+    let ratio = |expected: usize, missing: usize| {
+        (expected > 0).then(|| (expected - missing) as f64 / expected as f64)
+    };
+
+    RagCaseResult {
+        id: case.id.clone(),
+        retrieval_recall: ratio(case.expected_sources.len(), missing_sources.len()),
+        answer_score: ratio(case.expected_answer.len(), missing_phrases.len()),
+        missing_sources,
+        missing_phrases,
+        answer: answer.answer.clone(),
+    }
+}
+
+fn score_enrichment_case(
+    case: &EnrichmentEvalCase,
+    outcome: &Result<CompanyEnrichment, String>,
+) -> EnrichmentCaseResult {
+    let expected = &case.expected;
+    let expected_count = [&expected.name, &expected.domain, &expected.industry]
+        .iter()
+        .filter(|v| v.is_some())
+        .count();
+
+    let enrichment = match outcome {
+        Ok(enrichment) => enrichment,
+        Err(reason) => {
+            return EnrichmentCaseResult {
+                id: case.id.clone(),
+                score: (expected_count > 0).then_some(0.0),
+                mismatches: Vec::new(),
+                error: Some(reason.clone()),
+            };
+        }
+    };
+
+    let fields = [
+        (
+            "name",
+            expected.name.as_deref().map(normalize_text),
+            enrichment.name.as_deref().map(normalize_text),
+        ),
+        (
+            "domain",
+            expected.domain.as_deref().and_then(normalize_domain),
+            enrichment.domain.clone(),
+        ),
+        (
+            "industry",
+            expected.industry.as_deref().map(normalize_text),
+            enrichment.industry.as_deref().map(normalize_text),
+        ),
+    ];
+    let mismatches: Vec<String> = fields
+        .iter()
+        .filter(|(_, expected, actual)| expected.is_some() && expected != actual)
+        .map(|(field, _, _)| field.to_string())
+        .collect();
+
+    EnrichmentCaseResult {
+        id: case.id.clone(),
+        score: (expected_count > 0)
+            .then(|| (expected_count - mismatches.len()) as f64 / expected_count as f64),
+        mismatches,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::usecase::answer_question::RagPassage;
+    use chrono::Utc;
+
+    fn passage(kind: RagSourceKind, reference: &str) -> RagPassage {
+        RagPassage {
+            citation: 1,
+            kind,
+            reference: reference.into(),
+            title: String::new(),
+            text: String::new(),
+            distance: 0.1,
+        }
+    }
+
+    fn summary(retrieval_recall: Option<f64>, score: Option<f64>) -> EvalRunSummary {
+        EvalRunSummary {
+            id: 1,
+            suite: EvalSuite::Rag,
+            prompt_version: "rag-v1".into(),
+            model: "m".into(),
+            embedding_model: None,
+            cases_sha256: "sha".into(),
+            case_count: 1,
+            retrieval_recall,
+            score,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn rag_case_scores_recall_and_answer_phrases() {
+        let case = RagEvalCase {
+            id: "q1".into(),
+            question: "どの会社？".into(),
+            expected_sources: vec![
+                ExpectedSource {
+                    kind: RagSourceKind::Company,
+                    reference: "co_a".into(),
+                },
+                ExpectedSource {
+                    kind: RagSourceKind::Note,
+                    reference: "3".into(),
+                },
+            ],
+            expected_answer: vec!["Example 社".into(), "[1]".into()],
+        };
+        let answer = RagAnswer {
+            prompt_version: RAG_PROMPT_VERSION,
+            model: "m".into(),
+            question: case.question.clone(),
+            answer: "example社 です".into(),
+            passages: vec![
+                passage(RagSourceKind::Company, "co_a"),
+                passage(RagSourceKind::ScoutMessage, "3"),
+            ],
+        };
+
+        let result = score_rag_case(&case, &answer);
+
+        assert_eq!(result.retrieval_recall, Some(0.5));
+        assert_eq!(result.answer_score, Some(0.5));
+        assert_eq!(
+            result.missing_sources,
+            vec![case.expected_sources[1].clone()]
+        );
+        assert_eq!(result.missing_phrases, vec!["[1]".to_string()]);
+    }
+
+    #[test]
+    fn enrichment_case_compares_only_expected_fields() {
+        let case = EnrichmentEvalCase {
+            id: "c1".into(),
+            company_slug: "co_a".into(),
+            expected: ExpectedAttributes {
+                name: Some("Example 株式会社".into()),
+                domain: Some("https://www.example.co.jp".into()),
+                industry: Some("fintech".into()),
+            },
+        };
+        let enrichment = CompanyEnrichment {
+            name: Some("example株式会社".into()),
+            domain: Some("example.co.jp".into()),
+            industry: Some("saas".into()),
+            confidence: 0.8,
+        };
+
+        let result = score_enrichment_case(&case, &Ok(enrichment));
+        assert_eq!(result.score, Some(2.0 / 3.0));
+        assert_eq!(result.mismatches, vec!["industry".to_string()]);
+
+        let rejected = score_enrichment_case(&case, &Err("invalid JSON".into()));
+        assert_eq!(rejected.score, Some(0.0));
+        assert_eq!(rejected.error.as_deref(), Some("invalid JSON"));
+    }
+
+    #[test]
+    fn lower_score_than_previous_run_is_a_regression() {
+        assert!(is_regression(
+            &summary(Some(0.8), Some(0.5)),
+            &summary(Some(0.7), Some(0.9))
+        ));
+        assert!(!is_regression(
+            &summary(Some(0.8), Some(0.5)),
+            &summary(Some(0.8), Some(0.5))
+        ));
+        assert!(!is_regression(
+            &summary(None, Some(0.5)),
+            &summary(Some(0.1), None)
+        ));
+    }
+
+    #[test]
+    fn cases_hash_changes_with_content() {
+        let case = |question: &str| RagEvalCase {
+            id: "q1".into(),
+            question: question.into(),
+            expected_sources: Vec::new(),
+            expected_answer: Vec::new(),
+        };
+
+        assert_ne!(
+            cases_sha256(&[case("a")]).unwrap(),
+            cases_sha256(&[case("b")]).unwrap()
+        );
+        assert_eq!(mean([0.5, 1.0].into_iter()), Some(0.75));
+        assert_eq!(mean(std::iter::empty()), None);
+    }
+}
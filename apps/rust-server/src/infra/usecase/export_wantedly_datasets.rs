@@ -0,0 +1,37 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use sqlx::PgPool;
+use storage::wantedly::WantedlyExportFilter;
+
+use crate::infra::export::{
+    ExportDataset, ExportError, ExportFormat, anonymize::AnonymizeOptions, prepare_export_table,
+    writer::write_export_table,
+};
+
+/// データセットごとに（匿名化指定時は仮名化・検証してから） `<out_dir>/<dataset>.<ext>` を書き出し、書き出したパスを返す
+pub async fn export_wantedly_datasets_to_dir(
+    pool: &PgPool,
+    datasets: &[ExportDataset],
+    format: ExportFormat,
+    filter: &WantedlyExportFilter,
+    anonymize: Option<&AnonymizeOptions>,
+    out_dir: &Path,
+) -> Result<Vec<PathBuf>, ExportError> {
+    fs::create_dir_all(out_dir)?;
+
+    let mut written = Vec::with_capacity(datasets.len());
+    for dataset in datasets {
+        let table = prepare_export_table(pool, *dataset, filter, anonymize).await?;
+
+        let path = out_dir.join(format!("{}.{}", dataset.as_str(), format.extension()));
+        let file = BufWriter::new(File::create(&path)?);
+        write_export_table(&table, format, file)?;
+
+        tracing::info!(path = %path.display(), rows = table.rows.len(), "exported dataset");
+        written.push(path);
+    }
+
+    Ok(written)
+}
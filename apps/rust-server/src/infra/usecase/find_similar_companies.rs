@@ -0,0 +1,106 @@
+use serde::Serialize;
+use sqlx::PgPool;
+use storage::wantedly::{
+    WantedlyCompanyError, WantedlyEmbeddingError, WantedlySimilarCompany, find_company_by_slug,
+    find_company_embedding, list_similar_companies,
+};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::infra::embedding::{Embedder, EmbeddingError, embed_checked};
+
+/// has_viewed で絞るときに、近い順に何倍の候補から選ぶか
+const FILTER_CANDIDATE_FACTOR: i64 = 10;
+
+#[derive(Debug, Error)]
+pub enum SimilarCompanyError {
+    #[error("company not found: {0}")]
+    CompanyNotFound(String),
+
+    #[error("company {slug} has no embedding for model {model}; run backfill-embeddings")]
+    NotEmbedded { slug: String, model: String },
+
+    #[error(transparent)]
+    Company(#[from] WantedlyCompanyError),
+
+    #[error(transparent)]
+    Storage(#[from] WantedlyEmbeddingError),
+
+    #[error(transparent)]
+    Embedding(#[from] EmbeddingError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 何に似た会社を探すか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimilarTo<'a> {
+    /// 会社の slug（結果からは除く）
+    Company(&'a str),
+    /// 自由文（その場で埋め込む）
+    Text(&'a str),
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SimilarCompanyList {
+    /// 比べた埋め込みのモデル
+    pub model: String,
+    /// 基準にした会社（slug 指定時）
+    pub source_company_slug: Option<String>,
+    pub companies: Vec<WantedlySimilarCompany>,
+}
+
+/// 会社の埋め込みがコサイン距離で近い順に limit 社（has_viewed を指定すると閲覧の有無で絞る）
+pub async fn find_similar_companies(
+    pool: &PgPool,
+    embedder: &dyn Embedder,
+    target: SimilarTo<'_>,
+    has_viewed: Option<bool>,
+    limit: i64,
+) -> Result<SimilarCompanyList, SimilarCompanyError> {
+    let model = embedder.model().to_string();
+
+    let (embedding, source) = match target {
+        SimilarTo::Company(slug) => {
+            let company = find_company_by_slug(pool, slug)
+                .await?
+                .ok_or_else(|| SimilarCompanyError::CompanyNotFound(slug.to_string()))?;
+            let embedding = find_company_embedding(pool, company.id, &model)
+                .await?
+                .ok_or_else(|| SimilarCompanyError::NotEmbedded {
+                    slug: slug.to_string(),
+                    model: model.clone(),
+                })?;
+            (embedding, Some(company))
+        }
+        SimilarTo::Text(text) => {
+            let mut vectors = embed_checked(embedder, &[text.to_string()]).await?;
+            (vectors.remove(0), None)
+        }
+    };
+
+    let candidates = match has_viewed {
+        Some(_) => limit * FILTER_CANDIDATE_FACTOR,
+        None => limit,
+    };
+
+    let mut tx = pool.begin().await?;
+    let companies = list_similar_companies(
+        &mut tx,
+        &embedding,
+        &model,
+        source.as_ref().map(|c| c.id),
+        has_viewed,
+        candidates,
+        limit,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(SimilarCompanyList {
+        model,
+        source_company_slug: source.map(|c| c.company_slug),
+        companies,
+    })
+}
@@ -0,0 +1,162 @@
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::infra::{
+    digest::sha256_hex,
+    wantedly::own_profile::{OwnProfileFormat, OwnProfileParseError, parse_own_profile},
+};
+use storage::wantedly::{
+    NewWantedlyOwnProfileVersion, WantedlyOwnProfileError, find_own_profile_version_before,
+    find_own_profile_version_by_file_sha256, insert_own_profile_version,
+};
+
+#[derive(Debug, Error)]
+pub enum WantedlyOwnProfileImportError {
+    #[error("failed to read file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid own profile file name (expected <YYYYMMDDhhmmss in JST>.json|.html): {0}")]
+    FileName(String),
+
+    #[error("failed to parse own profile: {0}")]
+    Parse(#[from] OwnProfileParseError),
+
+    #[error("failed to store own profile version: {0}")]
+    Storage(#[from] WantedlyOwnProfileError),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OwnProfileImportOutcome {
+    Imported {
+        version_id: i64,
+    },
+    /// 直前のバージョンと内容が同じなので記録しない
+    Unchanged {
+        previous_version_id: i64,
+    },
+    AlreadyImported {
+        version_id: i64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnProfileImportResult {
+    pub source_file: String,
+    pub captured_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub outcome: OwnProfileImportOutcome,
+}
+
+/// ファイル名（JST の YYYYMMDDhhmmss + .json / .html）から保存日時を読む
+pub fn own_profile_captured_at(file_name: &str) -> Option<DateTime<Utc>> {
+    let (stem, _) = file_name.rsplit_once('.')?;
+    let naive_local = NaiveDateTime::parse_from_str(stem, "%Y%m%d%H%M%S").ok()?;
+
+    let jst = FixedOffset::east_opt(9 * 3600)?;
+    let jst_dt = jst.from_local_datetime(&naive_local).single()?;
+
+    Some(jst_dt.with_timezone(&Utc))
+}
+
+pub async fn import_wantedly_own_profile_from_file(
+    pool: &PgPool,
+    path: &Path,
+    captured_at: DateTime<Utc>,
+) -> Result<OwnProfileImportOutcome, WantedlyOwnProfileImportError> {
+    let format = OwnProfileFormat::from_path(path)?;
+    let bytes = fs::read(path)?;
+
+    // 同じ内容のファイルは取り込み済みとしてスキップする
+    let file_sha256 = sha256_hex(&bytes);
+    if let Some(imported) = find_own_profile_version_by_file_sha256(pool, &file_sha256).await? {
+        return Ok(OwnProfileImportOutcome::AlreadyImported {
+            version_id: imported.id,
+        });
+    }
+
+    let snapshot = parse_own_profile(&String::from_utf8_lossy(&bytes), format)?;
+    let content_sha256 = snapshot.content_sha256();
+
+    // 変更のないスナップショットは版を増やさない
+    if let Some(previous) = find_own_profile_version_before(pool, captured_at).await?
+        && previous.content_sha256 == content_sha256
+    {
+        return Ok(OwnProfileImportOutcome::Unchanged {
+            previous_version_id: previous.id,
+        });
+    }
+
+    let source_file = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let version_id = insert_own_profile_version(
+        pool,
+        &NewWantedlyOwnProfileVersion {
+            captured_at,
+            source_file,
+            source_format: format.as_str().to_string(),
+            file_sha256,
+            content_sha256,
+            headline: snapshot.headline,
+            skills: snapshot.skills,
+            experiences: snapshot.experiences,
+        },
+    )
+    .await?;
+
+    Ok(OwnProfileImportOutcome::Imported { version_id })
+}
+
+/// ディレクトリ内のスナップショットを保存日時の古い順に取り込む
+pub async fn import_wantedly_own_profile_dir(
+    pool: &PgPool,
+    dir: &Path,
+) -> Result<Vec<OwnProfileImportResult>, WantedlyOwnProfileImportError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let captured_at = own_profile_captured_at(&file_name)
+            .ok_or_else(|| WantedlyOwnProfileImportError::FileName(file_name.clone()))?;
+        files.push((captured_at, file_name, path));
+    }
+    files.sort();
+
+    let mut results = Vec::with_capacity(files.len());
+    for (captured_at, source_file, path) in files {
+        let outcome = import_wantedly_own_profile_from_file(pool, &path, captured_at).await?;
+        results.push(OwnProfileImportResult {
+            source_file,
+            captured_at,
+            outcome,
+        });
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_at_is_read_from_jst_file_name() {
+        let at = own_profile_captured_at("20251201093000.html").unwrap();
+
+        assert_eq!(at.to_rfc3339(), "2025-12-01T00:30:00+00:00");
+        assert!(own_profile_captured_at("profile.json").is_none());
+        assert!(own_profile_captured_at("20251201093000").is_none());
+    }
+}
@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 use sqlx::PgPool;
+use std::fs;
+use std::path::Path;
 use thiserror::Error;
 
 use crate::infra::{
+    digest::sha256_hex,
+    erasure::{ErasureKeyError, ErasureKeys, SuppressionSet},
     json_loader::{JsonLoadError, load_json_file},
     wantedly::{
         converter::{WantedlyProfileViewConvertError, convert_wantedly_json_node_to_storage},
@@ -11,8 +15,11 @@ use crate::infra::{
         json::{WantedlyJsonStructureError, extract_impressed_user_edges},
     },
 };
+use storage::erasure::{ErasureError, list_erasure_suppressions};
 use storage::wantedly::{
-    NewWantedlyProfileViewRaw, WantedlyProfileViewRawError, upsert_profile_view_raw,
+    NewWantedlyProfileViewRaw, NewWantedlySnapshotImport, WantedlyProfileViewRawError,
+    WantedlySnapshotImportError, find_snapshot_import_by_sha256, insert_snapshot_import,
+    upsert_profile_view_raw,
 };
 
 #[derive(Debug, Error)]
@@ -34,6 +41,15 @@ pub enum WantedlyImportError {
 
     #[error("failed to process one record: {0}")]
     MissingNode(&'static str),
+
+    #[error("failed to record snapshot import: {0}")]
+    SnapshotImport(#[from] WantedlySnapshotImportError),
+
+    #[error("failed to load erasure suppressions: {0}")]
+    Suppression(#[from] ErasureError),
+
+    #[error("failed to check erasure suppressions: {0}")]
+    SuppressionKey(#[from] ErasureKeyError),
 }
 
 pub async fn import_one_profile_view(
@@ -53,21 +69,55 @@ pub async fn import_one_profile_view(
 
 pub async fn import_wantedly_profile_views_from_file(
     pool: &PgPool,
+    erasure_keys: &ErasureKeys,
     path: &str,
     snapshot_at: DateTime<Utc>,
 ) -> Result<usize, WantedlyImportError> {
+    // 同じ内容のファイルは取り込み済みとしてスキップする
+    let file_sha256 = sha256_hex(&fs::read(path).map_err(JsonLoadError::from)?);
+    if let Some(imported) = find_snapshot_import_by_sha256(pool, &file_sha256).await? {
+        tracing::info!(path, imported_at = %imported.imported_at, "snapshot already imported");
+        return Ok(0);
+    }
+
     let json = load_json_file(path)?;
     let nodes: &Vec<Value> = extract_impressed_user_edges(&json)?;
-    println!("{:?}", serde_json::to_string_pretty(nodes).unwrap());
+    tracing::debug!(path, count = nodes.len(), "loaded profile view nodes");
+
+    // 削除要求済みの閲覧者・会社は取り込まない
+    let suppressions = SuppressionSet::new(erasure_keys, list_erasure_suppressions(pool).await?)?;
 
     let mut count = 0;
+    let mut suppressed = 0;
     for edge in nodes {
         let node_value = edge.get("node").ok_or(WantedlyImportError::MissingNode(
             "missing `node` field in edge",
         ))?;
+        if suppressions.suppresses_node(&WantedlyProfileViewNode::from_value(node_value)?) {
+            suppressed += 1;
+            continue;
+        }
         import_one_profile_view(pool, node_value, snapshot_at).await?;
         count += 1;
     }
+    if suppressed > 0 {
+        tracing::info!(path, suppressed, "skipped erased viewers / companies");
+    }
+
+    let source_file = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+    insert_snapshot_import(
+        pool,
+        &NewWantedlySnapshotImport {
+            source_file,
+            file_sha256,
+            snapshot_at,
+            record_count: count as i32,
+        },
+    )
+    .await?;
 
     Ok(count)
 }
@@ -1 +1,18 @@
+pub mod analyze_profile_hypothesis;
+pub mod analyze_repeat_viewers;
+pub mod answer_question;
+pub mod backfill_embeddings;
+pub mod backup_insights_data;
+pub mod build_weekly_report;
+pub mod compact_wantedly_raw_json;
+pub mod detect_view_anomalies;
+pub mod enrich_company_attributes;
+pub mod evaluate_prompts;
+pub mod export_wantedly_datasets;
+pub mod find_similar_companies;
+pub mod import_wantedly_own_profile;
 pub mod import_wantedly_profile_views;
+pub mod normalize_wantedly_profile_views;
+pub mod purge_erasure_subject;
+pub mod restore_insights_data;
+pub mod rotate_pii_key;
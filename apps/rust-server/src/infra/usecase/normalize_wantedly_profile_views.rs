@@ -0,0 +1,155 @@
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::infra::wantedly::affiliation::{AffiliationObservation, build_affiliation_spans};
+use storage::wantedly::{
+    NewWantedlyImpression, NewWantedlyViewerAffiliation, WantedlyCompanyError,
+    WantedlyImpressionError, WantedlyLiveEventError, WantedlyProfileViewRaw,
+    WantedlyProfileViewRawError, WantedlyViewerAffiliationError, WantedlyViewerError,
+    delete_live_events_before, delete_viewer_affiliations, find_live_event_watermark,
+    insert_viewer_affiliation, list_profile_views_raw_ordered, lock_profile_view_normalization,
+    publish_live_events_after, update_viewer_current_company, upsert_company, upsert_impression,
+    upsert_viewer,
+};
+
+/// ライブフィードのイベントを残す日数（Last-Event-ID で再送できる範囲）
+const LIVE_EVENT_RETENTION_DAYS: i64 = 7;
+
+#[derive(Debug, Error)]
+pub enum WantedlyNormalizeError {
+    #[error("failed to load raw records: {0}")]
+    RawRecord(#[from] WantedlyProfileViewRawError),
+
+    #[error("failed to normalize company: {0}")]
+    Company(#[from] WantedlyCompanyError),
+
+    #[error("failed to normalize viewer: {0}")]
+    Viewer(#[from] WantedlyViewerError),
+
+    #[error("failed to normalize viewer affiliation: {0}")]
+    Affiliation(#[from] WantedlyViewerAffiliationError),
+
+    #[error("failed to normalize impression: {0}")]
+    Impression(#[from] WantedlyImpressionError),
+
+    #[error("failed to publish live events: {0}")]
+    LiveEvent(#[from] WantedlyLiveEventError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct NormalizeSummary {
+    pub raw_records: usize,
+    pub viewers: usize,
+    pub affiliations: usize,
+    /// 新しく現れた会社・インプレッションとして流したイベント数
+    pub live_events: u64,
+}
+
+/// raw テーブル全体から companies / viewers / affiliations / impressions を作り直す
+///
+/// 新しく現れた会社・インプレッションはライブフィードのイベントとして記録し、NOTIFY する。
+/// 会社もインプレッションもない状態からの初回の一括取り込みではイベントを作らない。
+/// raw の読み出しから書き込みまでを 1 トランザクションで行い、読んだ raw 行はコミットまでロックする
+/// （同時に走る取り込み・purge で、読んだ行と書いた行がずれないように）。
+pub async fn normalize_wantedly_profile_views(
+    pool: &PgPool,
+) -> Result<NormalizeSummary, WantedlyNormalizeError> {
+    let mut tx = pool.begin().await?;
+    lock_profile_view_normalization(&mut tx).await?;
+    let rows = list_profile_views_raw_ordered(&mut tx).await?;
+
+    let mut summary = NormalizeSummary {
+        raw_records: rows.len(),
+        ..Default::default()
+    };
+
+    let watermark = find_live_event_watermark(&mut *tx).await?;
+    for viewer_rows in rows.chunk_by(|a, b| a.viewer_user_id == b.viewer_user_id) {
+        summary.affiliations += normalize_one_viewer(&mut tx, viewer_rows).await?;
+        summary.viewers += 1;
+    }
+    if !watermark.is_empty() {
+        summary.live_events = publish_live_events_after(&mut tx, &watermark).await?;
+    }
+    delete_live_events_before(
+        &mut *tx,
+        Utc::now() - Duration::days(LIVE_EVENT_RETENTION_DAYS),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(summary)
+}
+
+async fn normalize_one_viewer(
+    conn: &mut PgConnection,
+    viewer_rows: &[WantedlyProfileViewRaw],
+) -> Result<usize, WantedlyNormalizeError> {
+    let Some(first) = viewer_rows.first() else {
+        return Ok(0);
+    };
+
+    let viewer_id = upsert_viewer(&mut *conn, &first.viewer_user_id).await?;
+    delete_viewer_affiliations(conn, viewer_id).await?;
+
+    let observations: Vec<AffiliationObservation<'_>> = viewer_rows
+        .iter()
+        .map(|r| AffiliationObservation {
+            raw_profile_view_id: r.id,
+            viewed_at: r.viewed_at,
+            company_page_url: r.viewer_company_page_url.as_deref(),
+            short_description: r.viewer_company_name_raw.as_deref(),
+        })
+        .collect();
+    let spans = build_affiliation_spans(&observations);
+
+    let mut current_company_id = None;
+    for span in &spans {
+        let company_id = match (&span.company_page_url, &span.key.company_slug) {
+            (Some(url), Some(slug)) => Some(upsert_company(&mut *conn, url, slug).await?),
+            _ => None,
+        };
+
+        let affiliation_id = insert_viewer_affiliation(
+            &mut *conn,
+            &NewWantedlyViewerAffiliation {
+                viewer_id,
+                kind: span.key.kind(),
+                company_id,
+                short_description: span.key.short_description.clone(),
+                first_seen_at: span.first_seen_at,
+                last_seen_at: span.last_seen_at,
+            },
+        )
+        .await?;
+
+        for raw in viewer_rows
+            .iter()
+            .filter(|r| span.raw_profile_view_ids.contains(&r.id))
+        {
+            upsert_impression(
+                &mut *conn,
+                &NewWantedlyImpression {
+                    viewer_id,
+                    company_id_at_view: company_id,
+                    impressed_at: raw.viewed_at,
+                    raw_profile_view_id: raw.id,
+                    affiliation_id: Some(affiliation_id),
+                },
+            )
+            .await?;
+        }
+
+        current_company_id = company_id;
+    }
+
+    update_viewer_current_company(&mut *conn, viewer_id, current_company_id).await?;
+
+    Ok(spans.len())
+}
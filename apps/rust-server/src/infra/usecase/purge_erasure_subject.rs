@@ -0,0 +1,82 @@
+use serde::Serialize;
+use sqlx::PgPool;
+use storage::erasure::{
+    ErasureCounts, ErasureError, ErasureSubjectKind, delete_erasure_suppressions,
+    insert_erasure_audit, insert_erasure_suppression, purge_company, purge_viewer,
+};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::infra::{
+    erasure::{ErasureKeyError, ErasureKeys, ErasureSubject},
+    usecase::normalize_wantedly_profile_views::{
+        NormalizeSummary, WantedlyNormalizeError, normalize_wantedly_profile_views,
+    },
+};
+
+#[derive(Debug, Error)]
+pub enum ErasurePurgeError {
+    #[error(transparent)]
+    Key(#[from] ErasureKeyError),
+
+    #[error("erasure failed: {0}")]
+    Erasure(#[from] ErasureError),
+
+    #[error("failed to rebuild normalized tables: {0}")]
+    Normalize(#[from] WantedlyNormalizeError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 削除結果（対象の値そのものは含めない）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErasureReport {
+    pub audit_id: i64,
+    pub subject_kind: ErasureSubjectKind,
+    pub subject_hash: String,
+    pub deleted: ErasureCounts,
+    pub normalized: NormalizeSummary,
+}
+
+/// 対象に関わる行を 1 トランザクションで削除し、再取り込みを止める記録と監査ログを残す
+///
+/// 記録には ERASURE_HASH_KEY での HMAC だけを残し、同じ対象を旧鍵・鍵なしで作った行は置き換える。
+/// 削除後に正規化し直し、残った閲覧者の所属履歴を作り直す。
+pub async fn purge_erasure_subject(
+    pool: &PgPool,
+    keys: &ErasureKeys,
+    subject: &ErasureSubject,
+    actor: &str,
+) -> Result<ErasureReport, ErasurePurgeError> {
+    let key = keys.current()?;
+    let subject_hash = key.subject_hash(subject);
+
+    let mut tx = pool.begin().await?;
+    let deleted = match subject {
+        ErasureSubject::Viewer(source_user_id) => purge_viewer(&mut tx, source_user_id).await?,
+        ErasureSubject::Company(company_slug) => purge_company(&mut tx, company_slug).await?,
+    };
+    delete_erasure_suppressions(&mut *tx, subject.kind(), &keys.superseded_hashes(subject)).await?;
+    insert_erasure_suppression(&mut *tx, subject.kind(), &subject_hash, key.id()).await?;
+    let audit_id = insert_erasure_audit(
+        &mut *tx,
+        subject.kind(),
+        &subject_hash,
+        key.id(),
+        actor,
+        &deleted,
+    )
+    .await?;
+    tx.commit().await?;
+
+    let normalized = normalize_wantedly_profile_views(pool).await?;
+
+    Ok(ErasureReport {
+        audit_id,
+        subject_kind: subject.kind(),
+        subject_hash,
+        deleted,
+        normalized,
+    })
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use sqlx::PgPool;
+use storage::backup::{
+    BACKUP_TABLES, count_table_rows, latest_applied_migration, restore_table_rows,
+};
+
+use crate::infra::backup::{BackupError, BackupManifest, read_archive};
+
+/// 検証済みのアーカイブを、同じスキーマまでマイグレーション済みの空の DB に 1 トランザクションで書き戻す
+pub async fn restore_insights_data(
+    pool: &PgPool,
+    input: &Path,
+) -> Result<BackupManifest, BackupError> {
+    let (manifest, tables) = read_archive(input)?;
+
+    let mut tx = pool.begin().await?;
+
+    let database = latest_applied_migration(&mut tx).await?;
+    if manifest.schema_version != database {
+        return Err(BackupError::SchemaMismatch {
+            archive: manifest.schema_version,
+            database,
+        });
+    }
+
+    for table in BACKUP_TABLES {
+        if count_table_rows(&mut tx, table).await? > 0 {
+            return Err(BackupError::NotEmpty(table.to_string()));
+        }
+    }
+
+    // 外部キーの参照先から順に入れる
+    for table_name in BACKUP_TABLES {
+        let Some(table) = tables.iter().find(|t| t.name == *table_name) else {
+            return Err(BackupError::MissingTable(table_name.to_string()));
+        };
+        let rows = table.rows()?;
+        restore_table_rows(&mut tx, table_name, &rows).await?;
+
+        let actual = count_table_rows(&mut tx, table_name).await? as u64;
+        if actual != rows.len() as u64 {
+            return Err(BackupError::RowCountMismatch {
+                table: table_name.to_string(),
+                expected: rows.len() as u64,
+                actual,
+            });
+        }
+    }
+
+    tx.commit().await?;
+    tracing::info!(path = %input.display(), schema_version = ?manifest.schema_version, "restored backup archive");
+
+    Ok(manifest)
+}
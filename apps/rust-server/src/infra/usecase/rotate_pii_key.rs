@@ -0,0 +1,41 @@
+use sqlx::PgPool;
+use storage::pii::{PiiKeyError, PiiRotationSummary, rotate_raw_pii_key};
+use thiserror::Error;
+
+use crate::infra::usecase::normalize_wantedly_profile_views::{
+    NormalizeSummary, WantedlyNormalizeError, normalize_wantedly_profile_views,
+};
+
+#[derive(Debug, Error)]
+pub enum PiiRotationError {
+    #[error("PII_ENCRYPTION_KEY is not set")]
+    MissingKey,
+
+    #[error("key rotation failed: {0}")]
+    Rotate(#[from] PiiKeyError),
+
+    #[error("failed to rebuild normalized tables: {0}")]
+    Normalize(#[from] WantedlyNormalizeError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// raw を新しい鍵で暗号化し直し、所属履歴など派生テーブルを新しい鍵で作り直す
+///
+/// pool は新しい鍵（PII_ENCRYPTION_KEY）をセッションに設定して接続していること。
+pub async fn rotate_pii_key(
+    pool: &PgPool,
+    old_key: Option<&str>,
+    new_key: Option<&str>,
+) -> Result<(PiiRotationSummary, NormalizeSummary), PiiRotationError> {
+    let new_key = new_key.ok_or(PiiRotationError::MissingKey)?;
+
+    let mut tx = pool.begin().await?;
+    let rotated = rotate_raw_pii_key(&mut tx, old_key, new_key).await?;
+    tx.commit().await?;
+
+    let normalized = normalize_wantedly_profile_views(pool).await?;
+
+    Ok((rotated, normalized))
+}
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use storage::wantedly::ViewerAffiliationKind;
+
+use crate::infra::wantedly::company::company_slug_from_page_url;
+
+/// 所属を区別するキー（companyPageUrl の slug と shortDescription の組）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffiliationKey {
+    pub company_slug: Option<String>,
+    pub short_description: Option<String>,
+}
+
+impl AffiliationKey {
+    pub fn kind(&self) -> ViewerAffiliationKind {
+        match (&self.company_slug, &self.short_description) {
+            (Some(_), _) => ViewerAffiliationKind::Company,
+            (None, Some(_)) => ViewerAffiliationKind::Freeform,
+            (None, None) => ViewerAffiliationKind::Unknown,
+        }
+    }
+}
+
+/// 同一閲覧ユーザーの raw レコード 1 件分の観測
+#[derive(Debug, Clone)]
+pub struct AffiliationObservation<'a> {
+    pub raw_profile_view_id: i64,
+    pub viewed_at: DateTime<Utc>,
+    pub company_page_url: Option<&'a str>,
+    pub short_description: Option<&'a str>,
+}
+
+/// 同じ所属が続いた区間
+#[derive(Debug, Clone, PartialEq)]
+pub struct AffiliationSpan {
+    pub key: AffiliationKey,
+    pub company_page_url: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub raw_profile_view_ids: Vec<i64>,
+}
+
+/// 閲覧日時順の観測列を、所属が変わるたびに区切った区間列にまとめる
+pub fn build_affiliation_spans(
+    observations: &[AffiliationObservation<'_>],
+) -> Vec<AffiliationSpan> {
+    let mut sorted: Vec<&AffiliationObservation<'_>> = observations.iter().collect();
+    sorted.sort_by_key(|o| (o.viewed_at, o.raw_profile_view_id));
+
+    let mut spans: Vec<AffiliationSpan> = Vec::new();
+
+    for obs in sorted {
+        let company_slug = obs.company_page_url.and_then(company_slug_from_page_url);
+        let company_page_url = company_slug
+            .as_ref()
+            .and(obs.company_page_url.map(|u| u.trim().to_string()));
+        let key = AffiliationKey {
+            company_slug,
+            short_description: obs
+                .short_description
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+        };
+
+        match spans.last_mut() {
+            Some(span) if span.key == key => {
+                span.last_seen_at = obs.viewed_at;
+                span.raw_profile_view_ids.push(obs.raw_profile_view_id);
+            }
+            _ => spans.push(AffiliationSpan {
+                key,
+                company_page_url,
+                first_seen_at: obs.viewed_at,
+                last_seen_at: obs.viewed_at,
+                raw_profile_view_ids: vec![obs.raw_profile_view_id],
+            }),
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 11, d, 0, 0, 0).unwrap()
+    }
+
+    fn obs<'a>(
+        id: i64,
+        d: u32,
+        url: Option<&'a str>,
+        desc: Option<&'a str>,
+    ) -> AffiliationObservation<'a> {
+        AffiliationObservation {
+            raw_profile_view_id: id,
+            viewed_at: day(d),
+            company_page_url: url,
+            short_description: desc,
+        }
+    }
+
+    const A: &str = "https://www.wantedly.com/companies/company_a";
+    const B: &str = "https://www.wantedly.com/companies/company_b";
+
+    #[test]
+    fn same_affiliation_is_merged_into_one_span() {
+        let spans = build_affiliation_spans(&[
+            obs(1, 1, Some(A), Some("Engineer")),
+            obs(2, 3, Some(A), Some("Engineer")),
+        ]);
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].key.kind(), ViewerAffiliationKind::Company);
+        assert_eq!(spans[0].first_seen_at, day(1));
+        assert_eq!(spans[0].last_seen_at, day(3));
+        assert_eq!(spans[0].raw_profile_view_ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn company_or_description_change_starts_new_span() {
+        let spans = build_affiliation_spans(&[
+            obs(3, 5, Some(B), Some("Engineer")),
+            obs(1, 1, Some(A), Some("Engineer")),
+            obs(2, 2, Some(A), Some("EM")),
+            obs(4, 6, None, Some("Freelance")),
+            obs(5, 7, None, Some("  ")),
+        ]);
+
+        let kinds: Vec<_> = spans.iter().map(|s| s.key.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ViewerAffiliationKind::Company,
+                ViewerAffiliationKind::Company,
+                ViewerAffiliationKind::Company,
+                ViewerAffiliationKind::Freeform,
+                ViewerAffiliationKind::Unknown,
+            ]
+        );
+        assert_eq!(spans[2].key.company_slug.as_deref(), Some("company_b"));
+        assert_eq!(spans[2].company_page_url.as_deref(), Some(B));
+        assert_eq!(spans[3].company_page_url, None);
+    }
+
+    #[test]
+    fn returning_to_previous_company_is_a_separate_span() {
+        let spans = build_affiliation_spans(&[
+            obs(1, 1, Some(A), None),
+            obs(2, 2, Some(B), None),
+            obs(3, 3, Some(A), None),
+        ]);
+
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].key, spans[2].key);
+    }
+}
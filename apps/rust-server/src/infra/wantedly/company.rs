@@ -0,0 +1,47 @@
+/// companyPageUrl（例: https://www.wantedly.com/companies/company_xyz）から slug を取り出す
+pub fn company_slug_from_page_url(company_page_url: &str) -> Option<String> {
+    let without_query = company_page_url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default();
+
+    let mut segments = without_query.split('/').filter(|s| !s.is_empty());
+    segments.find(|s| *s == "companies")?;
+
+    segments
+        .next()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slug_from_plain_url() {
+        let slug = company_slug_from_page_url("https://www.wantedly.com/companies/company_xyz");
+        assert_eq!(slug.as_deref(), Some("company_xyz"));
+    }
+
+    #[test]
+    fn slug_ignores_sub_pages_query_and_trailing_slash() {
+        let slug = company_slug_from_page_url(
+            "https://www.wantedly.com/companies/company_xyz/post_articles/?ref=profile#top",
+        );
+        assert_eq!(slug.as_deref(), Some("company_xyz"));
+    }
+
+    #[test]
+    fn slug_missing_for_non_company_url() {
+        assert_eq!(
+            company_slug_from_page_url("https://www.wantedly.com/id/someone"),
+            None
+        );
+        assert_eq!(
+            company_slug_from_page_url("https://www.wantedly.com/companies/"),
+            None
+        );
+    }
+}
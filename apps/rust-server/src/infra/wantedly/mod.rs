@@ -1,5 +1,8 @@
+pub mod affiliation;
+pub mod company;
 pub mod converter;
 pub mod dto;
 pub mod json;
+pub mod own_profile;
 
 // pub use json::*;
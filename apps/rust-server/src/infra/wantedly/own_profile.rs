@@ -0,0 +1,371 @@
+use scraper::{Html, Selector};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+use storage::wantedly::{WantedlyOwnProfileExperience, WantedlyOwnProfileVersion};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::infra::digest::sha256_hex;
+
+const HEADLINE_KEYS: &[&str] = &[
+    "headline",
+    "shortDescription",
+    "short_description",
+    "catchphrase",
+];
+const SKILL_KEYS: &[&str] = &["skills", "skillTags", "skill_tags"];
+const EXPERIENCE_KEYS: &[&str] = &[
+    "experiences",
+    "workHistories",
+    "work_histories",
+    "workExperiences",
+    "careers",
+];
+
+#[derive(Debug, Error)]
+pub enum OwnProfileParseError {
+    #[error("unsupported profile file: {0} (expected .json or .html)")]
+    UnsupportedFormat(String),
+
+    #[error("failed to parse JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("no headline, skills or experiences found in the profile")]
+    NoProfile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnProfileFormat {
+    Json,
+    Html,
+}
+
+impl OwnProfileFormat {
+    pub fn from_path(path: &Path) -> Result<Self, OwnProfileParseError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("html" | "htm") => Ok(Self::Html),
+            _ => Err(OwnProfileParseError::UnsupportedFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// 自分のプロフィールのうち、差分を取る対象（見出し・スキル・職歴）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct OwnProfileSnapshot {
+    pub headline: Option<String>,
+    pub skills: Vec<String>,
+    pub experiences: Vec<WantedlyOwnProfileExperience>,
+}
+
+impl OwnProfileSnapshot {
+    fn is_empty(&self) -> bool {
+        self.headline.is_none() && self.skills.is_empty() && self.experiences.is_empty()
+    }
+
+    /// 保存形式（JSON / HTML）や余計なフィールドに左右されない内容のハッシュ
+    pub fn content_sha256(&self) -> String {
+        let json = serde_json::to_vec(self).expect("snapshot serializes");
+        sha256_hex(&json)
+    }
+}
+
+impl From<&WantedlyOwnProfileVersion> for OwnProfileSnapshot {
+    fn from(version: &WantedlyOwnProfileVersion) -> Self {
+        Self {
+            headline: version.headline.clone(),
+            skills: version.skills.0.clone(),
+            experiences: version.experiences.0.clone(),
+        }
+    }
+}
+
+pub fn parse_own_profile(
+    content: &str,
+    format: OwnProfileFormat,
+) -> Result<OwnProfileSnapshot, OwnProfileParseError> {
+    match format {
+        OwnProfileFormat::Json => parse_own_profile_json(&serde_json::from_str(content)?),
+        OwnProfileFormat::Html => parse_own_profile_html(content),
+    }
+}
+
+/// プロフィールの JSON（API のレスポンスをそのまま保存したものなど）から取り出す。
+/// ルートにキーがなければ、見出し・スキル・職歴のうち 2 つ以上を持つ最初のオブジェクトを探す
+pub fn parse_own_profile_json(value: &Value) -> Result<OwnProfileSnapshot, OwnProfileParseError> {
+    let node = match value.as_object() {
+        Some(root) if profile_score(root) > 0 => Some(root),
+        _ => find_profile_node(value),
+    }
+    .ok_or(OwnProfileParseError::NoProfile)?;
+
+    let snapshot = snapshot_from_node(node);
+    if snapshot.is_empty() {
+        return Err(OwnProfileParseError::NoProfile);
+    }
+    Ok(snapshot)
+}
+
+/// 保存した HTML から取り出す。埋め込みの JSON（__NEXT_DATA__ など）、JSON-LD の Person、
+/// og:description（見出しのみ）の順に試す
+pub fn parse_own_profile_html(html: &str) -> Result<OwnProfileSnapshot, OwnProfileParseError> {
+    let document = Html::parse_document(html);
+
+    let embedded = Selector::parse(r#"script#__NEXT_DATA__, script[type="application/json"]"#)
+        .expect("valid selector");
+    for script in document.select(&embedded) {
+        let Ok(value) = serde_json::from_str::<Value>(&script.inner_html()) else {
+            continue;
+        };
+        if let Ok(snapshot) = parse_own_profile_json(&value) {
+            return Ok(snapshot);
+        }
+    }
+
+    let json_ld = Selector::parse(r#"script[type="application/ld+json"]"#).expect("valid selector");
+    for script in document.select(&json_ld) {
+        let Ok(value) = serde_json::from_str::<Value>(&script.inner_html()) else {
+            continue;
+        };
+        if let Some(snapshot) = snapshot_from_json_ld(&value) {
+            return Ok(snapshot);
+        }
+    }
+
+    let og = Selector::parse(r#"meta[property="og:description"]"#).expect("valid selector");
+    let headline = document
+        .select(&og)
+        .find_map(|meta| clean_text(meta.value().attr("content")));
+    match headline {
+        Some(headline) => Ok(OwnProfileSnapshot {
+            headline: Some(headline),
+            ..Default::default()
+        }),
+        None => Err(OwnProfileParseError::NoProfile),
+    }
+}
+
+fn profile_score(node: &Map<String, Value>) -> usize {
+    [HEADLINE_KEYS, SKILL_KEYS, EXPERIENCE_KEYS]
+        .iter()
+        .filter(|keys| keys.iter().any(|k| node.contains_key(*k)))
+        .count()
+}
+
+fn find_profile_node(value: &Value) -> Option<&Map<String, Value>> {
+    match value {
+        Value::Object(node) if profile_score(node) >= 2 => Some(node),
+        Value::Object(node) => node.values().find_map(find_profile_node),
+        Value::Array(items) => items.iter().find_map(find_profile_node),
+        _ => None,
+    }
+}
+
+fn first_value<'a>(node: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter()
+        .find_map(|k| node.get(*k))
+        .filter(|v| !v.is_null())
+}
+
+fn clean_text(value: Option<&str>) -> Option<String> {
+    value
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| !s.is_empty())
+}
+
+/// 文字列、または {"name": ...} のようなオブジェクトから名前を取り出す
+fn name_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => clean_text(Some(s)),
+        Value::Object(node) => clean_text(
+            first_value(node, &["name", "label", "skillName", "title"]).and_then(Value::as_str),
+        ),
+        _ => None,
+    }
+}
+
+fn text_field(node: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    first_value(node, keys).and_then(|v| match v {
+        Value::Number(n) => Some(n.to_string()),
+        _ => name_of(v),
+    })
+}
+
+fn skills_from(value: Option<&Value>) -> Vec<String> {
+    let mut skills = Vec::new();
+    for skill in value.and_then(Value::as_array).into_iter().flatten() {
+        if let Some(name) = name_of(skill)
+            && !skills.contains(&name)
+        {
+            skills.push(name);
+        }
+    }
+    skills
+}
+
+fn experience_from(value: &Value) -> Option<WantedlyOwnProfileExperience> {
+    let node = value.as_object()?;
+    let experience = WantedlyOwnProfileExperience {
+        company: text_field(
+            node,
+            &["company", "companyName", "company_name", "organization"],
+        )
+        .unwrap_or_default(),
+        title: text_field(node, &["title", "role", "position", "jobTitle"]),
+        started_on: text_field(
+            node,
+            &["startedOn", "started_on", "startDate", "start_date"],
+        ),
+        ended_on: text_field(node, &["endedOn", "ended_on", "endDate", "end_date"]),
+        description: text_field(node, &["description", "summary"]),
+    };
+
+    (!experience.company.is_empty() || experience.title.is_some()).then_some(experience)
+}
+
+fn snapshot_from_node(node: &Map<String, Value>) -> OwnProfileSnapshot {
+    OwnProfileSnapshot {
+        headline: text_field(node, HEADLINE_KEYS),
+        skills: skills_from(first_value(node, SKILL_KEYS)),
+        experiences: first_value(node, EXPERIENCE_KEYS)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(experience_from)
+            .collect(),
+    }
+}
+
+fn snapshot_from_json_ld(value: &Value) -> Option<OwnProfileSnapshot> {
+    if let Value::Array(items) = value {
+        return items.iter().find_map(snapshot_from_json_ld);
+    }
+    let node = value.as_object()?;
+    if node.get("@type").and_then(Value::as_str) != Some("Person") {
+        return None;
+    }
+
+    let experiences = match node.get("worksFor") {
+        Some(Value::Array(items)) => items.iter().filter_map(name_of).collect(),
+        Some(item) => name_of(item).into_iter().collect(),
+        None => Vec::new(),
+    }
+    .into_iter()
+    .map(|company| WantedlyOwnProfileExperience {
+        company,
+        ..Default::default()
+    })
+    .collect();
+
+    let snapshot = OwnProfileSnapshot {
+        headline: text_field(node, &["jobTitle", "description"]),
+        skills: skills_from(node.get("knowsAbout")),
+        experiences,
+    };
+    (!snapshot.is_empty()).then_some(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_profile_json_with_varied_keys() {
+        let value = json!({
+            "shortDescription": "  Rust で\nデータ基盤を作っています ",
+            "skills": ["Rust", {"name": "PostgreSQL"}, "Rust", ""],
+            "workHistories": [
+                {"company": {"name": "Acme"}, "role": "Backend", "startDate": "2021-04"},
+                {"note": "no company nor title"}
+            ]
+        });
+
+        let snapshot = parse_own_profile_json(&value).unwrap();
+
+        assert_eq!(
+            snapshot.headline.as_deref(),
+            Some("Rust で データ基盤を作っています")
+        );
+        assert_eq!(snapshot.skills, vec!["Rust", "PostgreSQL"]);
+        assert_eq!(snapshot.experiences.len(), 1);
+        assert_eq!(snapshot.experiences[0].company, "Acme");
+        assert_eq!(snapshot.experiences[0].title.as_deref(), Some("Backend"));
+        assert_eq!(
+            snapshot.experiences[0].started_on.as_deref(),
+            Some("2021-04")
+        );
+    }
+
+    #[test]
+    fn finds_nested_profile_node() {
+        let value = json!({"data": {"user": {"id": 1, "headline": "Engineer", "skills": ["Go"]}}});
+
+        let snapshot = parse_own_profile_json(&value).unwrap();
+
+        assert_eq!(snapshot.headline.as_deref(), Some("Engineer"));
+        assert_eq!(snapshot.skills, vec!["Go"]);
+    }
+
+    #[test]
+    fn rejects_json_without_profile_fields() {
+        assert!(matches!(
+            parse_own_profile_json(&json!({"data": {"id": 1}})),
+            Err(OwnProfileParseError::NoProfile)
+        ));
+    }
+
+    #[test]
+    fn parses_embedded_json_in_html() {
+        let html = r#"<html><head>
+            <script id="__NEXT_DATA__" type="application/json">
+              {"props": {"pageProps": {"profile": {"headline": "Data Engineer", "skills": [{"name": "SQL"}]}}}}
+            </script></head><body></body></html>"#;
+
+        let snapshot = parse_own_profile_html(html).unwrap();
+
+        assert_eq!(snapshot.headline.as_deref(), Some("Data Engineer"));
+        assert_eq!(snapshot.skills, vec!["SQL"]);
+    }
+
+    #[test]
+    fn falls_back_to_json_ld_and_og_description() {
+        let html = r#"<script type="application/ld+json">
+            {"@type": "Person", "jobTitle": "SRE", "knowsAbout": ["Kubernetes"], "worksFor": {"name": "Acme"}}
+            </script>"#;
+        let snapshot = parse_own_profile_html(html).unwrap();
+        assert_eq!(snapshot.headline.as_deref(), Some("SRE"));
+        assert_eq!(snapshot.experiences[0].company, "Acme");
+
+        let html = r#"<meta property="og:description" content="Hello">"#;
+        let snapshot = parse_own_profile_html(html).unwrap();
+        assert_eq!(snapshot.headline.as_deref(), Some("Hello"));
+        assert!(snapshot.skills.is_empty());
+    }
+
+    #[test]
+    fn content_hash_ignores_source_format() {
+        let from_json = parse_own_profile(
+            r#"{"headline": "A", "skills": ["B"]}"#,
+            OwnProfileFormat::Json,
+        )
+        .unwrap();
+        let from_html = parse_own_profile(
+            r#"<script type="application/json">{"x": {"headline": "A", "skills": ["B"]}}</script>"#,
+            OwnProfileFormat::Html,
+        )
+        .unwrap();
+
+        assert_eq!(from_json.content_sha256(), from_html.content_sha256());
+    }
+}
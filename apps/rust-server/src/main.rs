@@ -1,18 +1,52 @@
-use axum::{Router, extract::path};
+use axum::Router;
+use clap::Parser;
 use db::check_connection;
 use dotenvy::dotenv;
+use sqlx::PgPool;
 use std::fs;
 use std::net::SocketAddr;
+use std::path::Path;
 use tracing_subscriber::EnvFilter;
 
+mod cli;
 mod config;
 mod error;
 mod migrate;
 mod routes;
+mod state;
 
 mod infra;
 
+use cli::{
+    AskArgs, BackfillEmbeddingsArgs, BackupArgs, Cli, Command, CompactRawJsonArgs,
+    DetectAnomaliesArgs, EnrichCompaniesArgs, EvalArgs, ExportArgs, ImportOwnProfileArgs,
+    ImportSnapshotArgs, OWN_PROFILE_DIR, PurgeArgs, RepeatViewersArgs, ReportArgs, RestoreArgs,
+};
+use infra::analytics::period::jst_today;
+use infra::embedding::build_embedder;
+use infra::json_loader::load_json_file;
+use infra::live::run_live_listener;
+use infra::llm::build_llm_client;
+use infra::llm::recorded::{LlmRecordings, RecordingLlmClient, ReplayLlmClient};
+use infra::usecase::analyze_repeat_viewers::analyze_repeat_viewers;
+use infra::usecase::answer_question::answer_question;
+use infra::usecase::backfill_embeddings::backfill_embeddings;
+use infra::usecase::backup_insights_data::backup_insights_data;
+use infra::usecase::build_weekly_report::build_weekly_report;
+use infra::usecase::compact_wantedly_raw_json::{RawJsonRetentionPolicy, apply_raw_json_retention};
+use infra::usecase::detect_view_anomalies::{ViewAnomalyOptions, detect_view_anomalies};
+use infra::usecase::enrich_company_attributes::{
+    CompanyEnrichmentOptions, enrich_company_attributes,
+};
+use infra::usecase::evaluate_prompts::{EvalCases, EvalOptions, evaluate_prompts};
+use infra::usecase::export_wantedly_datasets::export_wantedly_datasets_to_dir;
+use infra::usecase::import_wantedly_own_profile::import_wantedly_own_profile_dir;
 use infra::usecase::import_wantedly_profile_views::import_wantedly_profile_views_from_file;
+use infra::usecase::normalize_wantedly_profile_views::normalize_wantedly_profile_views;
+use infra::usecase::purge_erasure_subject::purge_erasure_subject;
+use infra::usecase::restore_insights_data::restore_insights_data;
+use infra::usecase::rotate_pii_key::rotate_pii_key;
+use state::AppState;
 
 #[tokio::main]
 async fn main() {
@@ -23,12 +57,16 @@ async fn main() {
 }
 
 async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
     // .env読み込み
     dotenv().ok();
 
     // DB接続確認
     let db_url = config::build_database_url_from_env()?;
-    let pool = db::establish_connection(&db_url).await?;
+    let pool =
+        db::establish_connection_with_pii_key(&db_url, config::pii_encryption_key_from_env())
+            .await?;
 
     if let Err(e) = check_connection(&pool).await {
         eprintln!("database connection test failed: {}", e);
@@ -50,9 +88,40 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     tracing::info!("start Logging successfully");
 
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(pool).await,
+        Command::Export(args) => export(&pool, &args).await,
+        Command::Backup(args) => backup(&pool, &args).await,
+        Command::Restore(args) => restore(&pool, &args).await,
+        Command::RotatePiiKey => rotate(&pool).await,
+        Command::Purge(args) => purge(&pool, &args).await,
+        Command::CompactRawJson(args) => compact_raw_json(&pool, &args).await,
+        Command::RepeatViewers(args) => repeat_viewers(&pool, &args).await,
+        Command::ImportOwnProfile(args) => import_own_profile(&pool, &args).await,
+        Command::DetectAnomalies(args) => detect_anomalies(&pool, &args).await,
+        Command::Report(args) => report(&pool, &args).await,
+        Command::ImportSnapshot(args) => import_snapshot(&pool, &args).await,
+        Command::BackfillEmbeddings(args) => backfill(&pool, &args).await,
+        Command::Ask(args) => ask(&pool, &args).await,
+        Command::EnrichCompanies(args) => enrich_companies(&pool, &args).await,
+        Command::Eval(args) => evaluate(&pool, &args).await,
+    }
+}
+
+async fn serve(pool: PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    // 鍵の異なる行が混ざると upsert / 正規化で同じ閲覧者が分裂するため、先にローテーションさせる
+    let stale = storage::pii::count_raw_rows_outside_session_key(&pool).await?;
+    if stale > 0 {
+        return Err(format!(
+            "{stale} raw rows are not stored with the configured PII_ENCRYPTION_KEY; run `rotate-pii-key` first"
+        )
+        .into());
+    }
+
     // Wantedlyのプロフィールビュー生データをJSONファイルから読み込み、DBに挿入する例
     let dir_path = "local_data/profile_sources/wantedly/raw";
 
+    let erasure_keys = config::erasure_keys_from_env();
     let file_names = fs::read_dir(dir_path)?
         .filter_map(Result::ok)
         .filter(|e| e.path().is_file())
@@ -63,11 +132,50 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         let snapshot_at = filename_to_utc_from_jst(&file_name).ok_or("invalid filename format")?;
         let path = format!("{}/{}", dir_path, file_name);
 
-        import_wantedly_profile_views_from_file(&pool, &path, snapshot_at).await?;
+        import_wantedly_profile_views_from_file(&pool, &erasure_keys, &path, snapshot_at).await?;
     }
 
+    // 自分のプロフィールのスナップショット（置いてあれば）
+    let own_profile_dir = Path::new(OWN_PROFILE_DIR);
+    if own_profile_dir.is_dir() {
+        let results = import_wantedly_own_profile_dir(&pool, own_profile_dir).await?;
+        tracing::info!(files = results.len(), "imported own profile snapshots");
+    }
+
+    // raw から会社・閲覧ユーザー・所属履歴・インプレッションを正規化
+    let summary = normalize_wantedly_profile_views(&pool).await?;
+    tracing::info!(?summary, "normalized wantedly profile views");
+
+    // 直近の日次閲覧数の急増・急減を記録し直す
+    let options = ViewAnomalyOptions::resolve(None, None, None, None, jst_today())?;
+    let report = detect_view_anomalies(&pool, &options).await?;
+    tracing::info!(
+        anomalies = report.anomalies.len(),
+        "detected view anomalies"
+    );
+
+    // 保持期間を過ぎた raw_json を削る（RAW_JSON_RETENTION_DAYS 設定時のみ）
+    if let Some(older_than_days) = config::raw_json_retention_days_from_env()? {
+        let report = apply_raw_json_retention(
+            &pool,
+            RawJsonRetentionPolicy { older_than_days },
+            false,
+            false,
+        )
+        .await?;
+        tracing::info!(
+            rows = report.compaction.rows,
+            reclaimed_bytes = report.reclaimed_bytes,
+            "compacted raw_json past retention"
+        );
+    }
+
+    // 正規化の NOTIFY を受けてライブフィード（SSE）に流す
+    let state = AppState::new(pool, config::AppConfig::from_env()?);
+    tokio::spawn(run_live_listener(state.pool.clone(), state.live.clone()));
+
     // ルータ定義
-    let app: Router = routes::router();
+    let app: Router = routes::router(state);
 
     // アドレス
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
@@ -80,6 +188,249 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+async fn export(pool: &PgPool, args: &ExportArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let anonymize = args.anonymize_options(config::export_anonymize_key_from_env())?;
+    let written = export_wantedly_datasets_to_dir(
+        pool,
+        &args.datasets(),
+        args.format,
+        &args.filter(),
+        anonymize.as_ref(),
+        &args.out_dir,
+    )
+    .await?;
+
+    for path in written {
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+async fn backup(pool: &PgPool, args: &BackupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let output = args.output();
+    let manifest = backup_insights_data(pool, &output).await?;
+
+    for table in &manifest.tables {
+        println!("{}: {} rows", table.name, table.row_count);
+    }
+    println!("{}", output.display());
+
+    Ok(())
+}
+
+async fn restore(pool: &PgPool, args: &RestoreArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = restore_insights_data(pool, &args.input).await?;
+
+    for table in &manifest.tables {
+        println!("{}: {} rows", table.name, table.row_count);
+    }
+
+    Ok(())
+}
+
+async fn rotate(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let old_key = config::previous_pii_encryption_key_from_env();
+    let new_key = config::pii_encryption_key_from_env();
+
+    let (rotated, normalized) =
+        rotate_pii_key(pool, old_key.as_deref(), new_key.as_deref()).await?;
+    println!(
+        "re-encrypted {} raw rows, remapped {} viewers, rebuilt {} affiliations",
+        rotated.raw_rows, rotated.viewers, normalized.affiliations
+    );
+
+    Ok(())
+}
+
+async fn purge(pool: &PgPool, args: &PurgeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let subject = args.subject()?;
+    let report =
+        purge_erasure_subject(pool, &config::erasure_keys_from_env(), &subject, "cli").await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+async fn compact_raw_json(
+    pool: &PgPool,
+    args: &CompactRawJsonArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let policy = args.policy(config::raw_json_retention_days_from_env()?)?;
+    let report = apply_raw_json_retention(pool, policy, args.dry_run, args.vacuum).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+async fn repeat_viewers(
+    pool: &PgPool,
+    args: &RepeatViewersArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let options = args.options(jst_today())?;
+    let report = analyze_repeat_viewers(pool, &options).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+async fn import_own_profile(
+    pool: &PgPool,
+    args: &ImportOwnProfileArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let results = import_wantedly_own_profile_dir(pool, &args.dir).await?;
+
+    println!("{}", serde_json::to_string_pretty(&results)?);
+
+    Ok(())
+}
+
+async fn import_snapshot(
+    pool: &PgPool,
+    args: &ImportSnapshotArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file_name = args
+        .file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("invalid snapshot path")?;
+    let snapshot_at = filename_to_utc_from_jst(file_name).ok_or("invalid filename format")?;
+    let path = args.file.to_str().ok_or("invalid snapshot path")?;
+
+    let inserted = import_wantedly_profile_views_from_file(
+        pool,
+        &config::erasure_keys_from_env(),
+        path,
+        snapshot_at,
+    )
+    .await?;
+    let normalized = normalize_wantedly_profile_views(pool).await?;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&serde_json::json!({
+            "inserted": inserted,
+            "normalized": normalized,
+        }))?
+    );
+
+    Ok(())
+}
+
+async fn backfill(
+    pool: &PgPool,
+    args: &BackfillEmbeddingsArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let embedder = build_embedder(&config::embedder_config_from_env()?);
+    let report = backfill_embeddings(pool, embedder.as_ref(), args.batch_size).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+async fn ask(pool: &PgPool, args: &AskArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let embedder = build_embedder(&config::embedder_config_from_env()?);
+    let llm = build_llm_client(&config::llm_config_from_env()?);
+    let answer = answer_question(
+        pool,
+        embedder.as_ref(),
+        llm.as_ref(),
+        args.question.trim(),
+        args.top_k.max(1),
+    )
+    .await?;
+
+    println!("{}", serde_json::to_string_pretty(&answer)?);
+
+    Ok(())
+}
+
+async fn enrich_companies(
+    pool: &PgPool,
+    args: &EnrichCompaniesArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let llm = build_llm_client(&config::llm_config_from_env()?);
+    let options = CompanyEnrichmentOptions {
+        limit: args.limit.max(1),
+        refresh_stale: args.refresh_stale,
+    };
+    let report = enrich_company_attributes(pool, llm.as_ref(), &options).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+async fn evaluate(pool: &PgPool, args: &EvalArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let cases: EvalCases = serde_json::from_value(load_json_file(&args.cases)?)?;
+    let embedder = build_embedder(&config::embedder_config_from_env()?);
+    let options = EvalOptions {
+        top_k: args.top_k.max(1),
+    };
+    let existing = LlmRecordings::load(&args.recordings)?;
+
+    let reports = if args.record {
+        let llm = build_llm_client(&config::llm_config_from_env()?);
+        let recorder = RecordingLlmClient::new(llm, existing);
+        let reports =
+            evaluate_prompts(pool, embedder.as_ref(), &recorder, &cases, &options).await?;
+        recorder.recordings().save(&args.recordings)?;
+        reports
+    } else {
+        let recordings = existing.ok_or_else(|| {
+            format!(
+                "{} not found; run `eval --record` first",
+                args.recordings.display()
+            )
+        })?;
+        let replay = ReplayLlmClient::new(recordings);
+        evaluate_prompts(pool, embedder.as_ref(), &replay, &cases, &options).await?
+    };
+
+    println!("{}", serde_json::to_string_pretty(&reports)?);
+
+    if args.fail_on_regression && reports.iter().any(|r| r.regressed) {
+        return Err("evaluation score regressed from the previous run".into());
+    }
+
+    Ok(())
+}
+
+async fn detect_anomalies(
+    pool: &PgPool,
+    args: &DetectAnomaliesArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let options = args.options(jst_today())?;
+    let report = detect_view_anomalies(pool, &options).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+async fn report(pool: &PgPool, args: &ReportArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let period = args.period(jst_today())?;
+    let report = build_weekly_report(pool, period).await?;
+    let rendered = args.format.render(&report);
+
+    match &args.output {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, rendered)?;
+            println!("{}", path.display());
+        }
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
+
 use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
 
 fn filename_to_utc_from_jst(filename: &str) -> Option<DateTime<Utc>> {
@@ -92,8 +443,3 @@ fn filename_to_utc_from_jst(filename: &str) -> Option<DateTime<Utc>> {
     let utc_dt = jst_dt.with_timezone(&Utc);
     Some(utc_dt)
 }
-fn filename_to_utc(filename: &str) -> Option<DateTime<Utc>> {
-    let stem = filename.strip_suffix(".json")?;
-    let naive = NaiveDateTime::parse_from_str(stem, "%Y%m%d%H%M%S").ok()?;
-    Some(Utc.from_utc_datetime(&naive))
-}
@@ -0,0 +1,172 @@
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, header},
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    config::AppConfig,
+    error::{AppError, AppResult, ErrorBody},
+    infra::{
+        erasure::ErasureSubject,
+        usecase::purge_erasure_subject::{ErasureReport, purge_erasure_subject},
+    },
+    state::AppState,
+};
+
+/// Authorization: Bearer <ADMIN_TOKEN> を確認する。ADMIN_TOKEN 未設定なら管理 API は常に拒否
+pub fn require_admin(headers: &HeaderMap, config: &AppConfig) -> AppResult<()> {
+    let expected = config.admin_token.as_deref().ok_or_else(|| {
+        AppError::Forbidden("admin API is disabled (ADMIN_TOKEN is not set)".into())
+    })?;
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::Unauthorized("missing bearer token".into()))?;
+
+    if !constant_time_eq(token.as_bytes(), expected.as_bytes()) {
+        return Err(AppError::Unauthorized("invalid bearer token".into()));
+    }
+
+    Ok(())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// viewer_user_id と company_slug のどちらか一方だけを指定する
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ErasureRequest {
+    viewer_user_id: Option<String>,
+    company_slug: Option<String>,
+}
+
+impl ErasureRequest {
+    pub fn subject(&self) -> AppResult<ErasureSubject> {
+        let viewer = self
+            .viewer_user_id
+            .as_deref()
+            .and_then(ErasureSubject::viewer);
+        let company = self
+            .company_slug
+            .as_deref()
+            .and_then(ErasureSubject::company);
+
+        match (viewer, company) {
+            (Some(subject), None) | (None, Some(subject)) => Ok(subject),
+            _ => Err(AppError::BadRequest(
+                "specify exactly one of `viewer_user_id` or `company_slug`".into(),
+            )),
+        }
+    }
+}
+
+/// POST /admin/erasure  {"viewer_user_id": "..."} または {"company_slug": "..."}
+#[utoipa::path(
+    post,
+    path = "/admin/erasure",
+    operation_id = "erase_subject",
+    tag = "admin",
+    request_body = ErasureRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = ErasureReport),
+        (status = 400, description = "対象の指定が不正", body = ErrorBody),
+        (status = 401, description = "トークンがない、または一致しない", body = ErrorBody),
+        (status = 403, description = "ADMIN_TOKEN が未設定で無効", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn erasure_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ErasureRequest>,
+) -> AppResult<Json<ErasureReport>> {
+    require_admin(&headers, &state.config)?;
+    let subject = request.subject()?;
+
+    let report = purge_erasure_subject(
+        &state.pool,
+        &state.config.erasure_keys,
+        &subject,
+        "admin-api",
+    )
+    .await?;
+    tracing::info!(
+        audit_id = report.audit_id,
+        kind = subject.kind().as_str(),
+        "erasure completed"
+    );
+
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(admin_token: Option<&str>) -> AppConfig {
+        AppConfig {
+            admin_token: admin_token.map(str::to_string),
+            ..AppConfig::default()
+        }
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {token}").parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn admin_api_is_disabled_without_token() {
+        let result = require_admin(&bearer("anything"), &config(None));
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[test]
+    fn bearer_token_must_match() {
+        let config = config(Some("s3cret"));
+
+        assert!(require_admin(&bearer("s3cret"), &config).is_ok());
+        assert!(matches!(
+            require_admin(&bearer("wrong"), &config),
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            require_admin(&HeaderMap::new(), &config),
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn exactly_one_subject_is_required() {
+        let both = ErasureRequest {
+            viewer_user_id: Some("1".into()),
+            company_slug: Some("company_xyz".into()),
+        };
+        let neither = ErasureRequest {
+            viewer_user_id: None,
+            company_slug: Some(" ".into()),
+        };
+        let viewer = ErasureRequest {
+            viewer_user_id: Some("42".into()),
+            company_slug: None,
+        };
+
+        assert!(matches!(both.subject(), Err(AppError::BadRequest(_))));
+        assert!(matches!(neither.subject(), Err(AppError::BadRequest(_))));
+        assert_eq!(
+            viewer.subject().unwrap(),
+            ErasureSubject::Viewer("42".into())
+        );
+    }
+}
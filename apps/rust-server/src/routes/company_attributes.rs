@@ -0,0 +1,63 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use storage::wantedly::{
+    CompanyAttributeProvenance, WantedlyCompanyAttributes, WantedlyCompanyAttributesInput,
+    find_company_by_slug, upsert_company_attributes,
+};
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    state::AppState,
+};
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// PUT /companies/{company_slug}/attributes
+///
+/// 会社属性を手入力で置き換える（省略した項目は空になる）。手入力の値は AI による推定で上書きされない。
+#[utoipa::path(
+    put,
+    path = "/companies/{company_slug}/attributes",
+    operation_id = "put_company_attributes",
+    tag = "companies",
+    params(("company_slug" = String, Path, description = "会社の slug")),
+    request_body = WantedlyCompanyAttributesInput,
+    responses(
+        (status = 200, body = WantedlyCompanyAttributes),
+        (status = 404, description = "会社が見つからない", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn put_handler(
+    State(state): State<AppState>,
+    Path(company_slug): Path<String>,
+    Json(input): Json<WantedlyCompanyAttributesInput>,
+) -> AppResult<Json<WantedlyCompanyAttributes>> {
+    let company = find_company_by_slug(&state.pool, &company_slug)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("company not found: {company_slug}")))?;
+
+    let input = WantedlyCompanyAttributesInput {
+        name: trimmed(input.name),
+        domain: trimmed(input.domain),
+        industry: trimmed(input.industry).map(|v| v.to_lowercase()),
+        location: trimmed(input.location),
+        ..input
+    };
+    let attributes = upsert_company_attributes(
+        &state.pool,
+        company.id,
+        &input,
+        &CompanyAttributeProvenance::manual(),
+    )
+    .await?
+    .ok_or_else(|| AppError::Internal("manual attributes were not written".into()))?;
+
+    Ok(Json(attributes))
+}
@@ -0,0 +1,78 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use storage::wantedly::{
+    WantedlyCompanyDimension, WantedlyTimeBucket, list_view_breakdown_by_bucket,
+    list_view_breakdown_totals, list_view_time_series,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::analytics::{
+        breakdown::{CompanyBreakdown, build_company_breakdown},
+        period::{JstPeriod, jst_today},
+    },
+    state::AppState,
+};
+
+const DEFAULT_DAYS: u32 = 90;
+const MAX_DAYS: u32 = 1100;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BreakdownQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    #[serde(default)]
+    dimension: WantedlyCompanyDimension,
+    #[serde(default = "default_granularity")]
+    #[param(default = "month")]
+    granularity: WantedlyTimeBucket,
+}
+
+fn default_granularity() -> WantedlyTimeBucket {
+    WantedlyTimeBucket::Month
+}
+
+/// GET /analytics/companies/breakdown?dimension=industry|employee_band|funding_stage|location&granularity=day|week|month
+///
+/// 閲覧時点の所属会社の属性ごとに閲覧数・閲覧者数を集計する。期間の省略時は今日（JST）までの 90 日。
+#[utoipa::path(
+    get,
+    path = "/analytics/companies/breakdown",
+    operation_id = "get_company_breakdown",
+    tag = "analytics",
+    params(BreakdownQuery),
+    responses(
+        (status = 200, body = CompanyBreakdown),
+        (status = 400, description = "期間が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Query(query): Query<BreakdownQuery>,
+) -> AppResult<Json<CompanyBreakdown>> {
+    let period = JstPeriod::resolve(query.from, query.to, DEFAULT_DAYS, MAX_DAYS, jst_today())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let JstPeriod { from, to } = period;
+
+    let buckets = list_view_time_series(&state.pool, from, to, query.granularity).await?;
+    let totals = list_view_breakdown_totals(&state.pool, from, to, query.dimension).await?;
+    let rows =
+        list_view_breakdown_by_bucket(&state.pool, from, to, query.granularity, query.dimension)
+            .await?;
+
+    Ok(Json(build_company_breakdown(
+        period,
+        query.dimension,
+        query.granularity,
+        buckets,
+        totals,
+        rows,
+    )))
+}
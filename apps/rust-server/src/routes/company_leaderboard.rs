@@ -0,0 +1,121 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use storage::wantedly::{WantedlyLeaderboardSort, WantedlySortOrder, list_company_leaderboard};
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::analytics::{
+        leaderboard::{CompanyLeaderboard, CompanyLeaderboardEntry},
+        period::{JstPeriod, jst_today},
+    },
+    state::AppState,
+};
+
+const DEFAULT_DAYS: u32 = 30;
+const MAX_DAYS: u32 = 366;
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LeaderboardQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    #[serde(default)]
+    sort: WantedlyLeaderboardSort,
+    #[serde(default)]
+    order: WantedlySortOrder,
+    limit: Option<i64>,
+}
+
+impl LeaderboardQuery {
+    pub fn period(&self, today: NaiveDate) -> AppResult<JstPeriod> {
+        JstPeriod::resolve(self.from, self.to, DEFAULT_DAYS, MAX_DAYS, today)
+            .map_err(|e| AppError::BadRequest(e.to_string()))
+    }
+
+    pub fn limit(&self) -> AppResult<i64> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(AppError::BadRequest(format!(
+                "`limit` must be between 1 and {MAX_LIMIT}"
+            )));
+        }
+        Ok(limit)
+    }
+}
+
+/// GET /analytics/companies?from=YYYY-MM-DD&to=YYYY-MM-DD
+///     &sort=views|distinct_viewers|first_viewed_at|last_viewed_at|trend&order=asc|desc&limit=50
+///
+/// 期間の省略時は今日（JST）までの 30 日。前期間は直前の同じ長さの期間。
+#[utoipa::path(
+    get,
+    path = "/analytics/companies",
+    operation_id = "get_company_leaderboard",
+    tag = "analytics",
+    params(LeaderboardQuery),
+    responses(
+        (status = 200, body = CompanyLeaderboard),
+        (status = 400, description = "期間または件数が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Query(query): Query<LeaderboardQuery>,
+) -> AppResult<Json<CompanyLeaderboard>> {
+    let period = query.period(jst_today())?;
+    let limit = query.limit()?;
+
+    let rows = list_company_leaderboard(
+        &state.pool,
+        period.from,
+        period.to,
+        query.sort,
+        query.order,
+        limit,
+    )
+    .await?;
+
+    Ok(Json(CompanyLeaderboard {
+        period,
+        previous_period: period.previous(),
+        companies: rows
+            .into_iter()
+            .map(CompanyLeaderboardEntry::from)
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_is_bounded() {
+        let query = LeaderboardQuery {
+            limit: Some(0),
+            ..LeaderboardQuery::default()
+        };
+        assert!(matches!(query.limit(), Err(AppError::BadRequest(_))));
+        assert_eq!(LeaderboardQuery::default().limit().unwrap(), DEFAULT_LIMIT);
+    }
+
+    #[test]
+    fn reversed_period_is_bad_request() {
+        let query = LeaderboardQuery {
+            from: NaiveDate::from_ymd_opt(2025, 12, 2),
+            to: NaiveDate::from_ymd_opt(2025, 12, 1),
+            ..LeaderboardQuery::default()
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+
+        assert!(matches!(query.period(today), Err(AppError::BadRequest(_))));
+    }
+}
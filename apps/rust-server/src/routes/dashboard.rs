@@ -0,0 +1,438 @@
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use storage::wantedly::{
+    ViewerAffiliationKind, WantedlyCompanyLeaderboardRow, WantedlyLeaderboardSort,
+    WantedlyRecentViewRow, WantedlySnapshotImport, WantedlySortOrder, WantedlyTimeBucket,
+    WantedlyViewQuery, count_impressions, count_profile_views_raw, list_company_leaderboard,
+    list_recent_views, list_snapshot_imports, list_view_time_series, summarize_views,
+};
+
+use crate::{
+    error::{AppError, AppResult},
+    infra::{
+        analytics::period::{JstPeriod, format_jst_minute, jst_today},
+        report::{document::format_change, svg},
+    },
+    state::AppState,
+};
+
+const DEFAULT_DAYS: u32 = 30;
+const MAX_DAYS: u32 = 1100;
+/// これより長い期間は週ごとに集計して表示する
+const DAILY_CHART_MAX_DAYS: i64 = 92;
+const OVERVIEW_COMPANIES: i64 = 10;
+const OVERVIEW_VIEWS: i64 = 10;
+const COMPANY_PAGE_ROWS: i64 = 100;
+const VIEWER_PAGE_ROWS: i64 = 100;
+
+const NAV_LINKS: &[(&str, &str)] = &[
+    ("/dashboard", "概要"),
+    ("/dashboard/companies", "会社"),
+    ("/dashboard/viewers", "閲覧者"),
+    ("/dashboard/imports", "取り込み"),
+];
+
+const SORTS: &[(&str, &str)] = &[
+    ("views", "閲覧数"),
+    ("distinct_viewers", "閲覧者数"),
+    ("trend", "前期からの増加"),
+    ("last_viewed_at", "最後の閲覧"),
+    ("first_viewed_at", "最初の閲覧"),
+];
+
+/// バイナリに埋め込む静的ファイル（パス, Content-Type, 中身）
+const ASSETS: &[(&str, &str, &[u8])] = &[
+    (
+        "dashboard.css",
+        "text/css; charset=utf-8",
+        include_bytes!("../../assets/dashboard/dashboard.css"),
+    ),
+    (
+        "favicon.svg",
+        "image/svg+xml",
+        include_bytes!("../../assets/dashboard/favicon.svg"),
+    ),
+    (
+        "live.js",
+        "text/javascript; charset=utf-8",
+        include_bytes!("../../assets/dashboard/live.js"),
+    ),
+];
+
+/// 全ページ共通のヘッダ
+struct Layout {
+    active: &'static str,
+}
+
+impl Layout {
+    fn links(&self) -> &'static [(&'static str, &'static str)] {
+        NAV_LINKS
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DashboardQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    sort: Option<WantedlyLeaderboardSort>,
+}
+
+impl DashboardQuery {
+    fn period(&self) -> AppResult<JstPeriod> {
+        JstPeriod::resolve(self.from, self.to, DEFAULT_DAYS, MAX_DAYS, jst_today())
+            .map_err(|e| AppError::BadRequest(e.to_string()))
+    }
+}
+
+struct SummaryCard {
+    label: &'static str,
+    value: i64,
+    previous: i64,
+    change: String,
+}
+
+struct CompanyRow {
+    label: String,
+    page_url: String,
+    views: i64,
+    previous_views: i64,
+    distinct_viewers: i64,
+    first_viewed_at: String,
+    last_viewed_at: String,
+}
+
+impl From<WantedlyCompanyLeaderboardRow> for CompanyRow {
+    fn from(row: WantedlyCompanyLeaderboardRow) -> Self {
+        Self {
+            label: match row.name {
+                Some(name) if !name.trim().is_empty() => format!("{name} ({})", row.company_slug),
+                _ => row.company_slug,
+            },
+            page_url: row.company_page_url,
+            views: row.views,
+            previous_views: row.previous_views,
+            distinct_viewers: row.distinct_viewers,
+            first_viewed_at: format_jst_minute(row.first_viewed_at),
+            last_viewed_at: format_jst_minute(row.last_viewed_at),
+        }
+    }
+}
+
+struct ViewRow {
+    viewed_at: String,
+    viewer_user_id: String,
+    company: String,
+    affiliation: String,
+}
+
+impl From<WantedlyRecentViewRow> for ViewRow {
+    fn from(row: WantedlyRecentViewRow) -> Self {
+        let company = match (row.company_name, row.company_slug) {
+            (Some(name), Some(slug)) => format!("{name} ({slug})"),
+            (None, Some(slug)) => slug,
+            _ => String::new(),
+        };
+        let affiliation = match row.affiliation_kind {
+            Some(ViewerAffiliationKind::Unknown) | None => String::new(),
+            Some(_) => row.affiliation_short_description.unwrap_or_default(),
+        };
+
+        Self {
+            viewed_at: format_jst_minute(row.impressed_at),
+            viewer_user_id: row.viewer_user_id,
+            company,
+            affiliation,
+        }
+    }
+}
+
+struct ImportRow {
+    snapshot_at: String,
+    source_file: String,
+    record_count: i32,
+    imported_at: String,
+}
+
+impl From<WantedlySnapshotImport> for ImportRow {
+    fn from(import: WantedlySnapshotImport) -> Self {
+        Self {
+            snapshot_at: format_jst_minute(import.snapshot_at),
+            source_file: import.source_file,
+            record_count: import.record_count,
+            imported_at: format_jst_minute(import.imported_at),
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "dashboard/overview.html")]
+struct OverviewPage {
+    layout: Layout,
+    period: JstPeriod,
+    cards: Vec<SummaryCard>,
+    bucket_label: &'static str,
+    /// svg モジュールが組み立てた SVG（テキストはエスケープ済み）
+    chart: String,
+    companies: Vec<CompanyRow>,
+    views: Vec<ViewRow>,
+    last_import: Option<ImportRow>,
+}
+
+#[derive(Template)]
+#[template(path = "dashboard/companies.html")]
+struct CompaniesPage {
+    layout: Layout,
+    period: JstPeriod,
+    sorts: &'static [(&'static str, &'static str)],
+    sort: &'static str,
+    companies: Vec<CompanyRow>,
+}
+
+#[derive(Template)]
+#[template(path = "dashboard/viewers.html")]
+struct ViewersPage {
+    layout: Layout,
+    views: Vec<ViewRow>,
+}
+
+#[derive(Template)]
+#[template(path = "dashboard/imports.html")]
+struct ImportsPage {
+    layout: Layout,
+    raw_rows: i64,
+    impressions: i64,
+    imports: Vec<ImportRow>,
+}
+
+fn render(page: &impl Template) -> AppResult<Html<String>> {
+    page.render()
+        .map(Html)
+        .map_err(|e| AppError::Internal(format!("failed to render page: {e}")))
+}
+
+fn sort_value(sort: WantedlyLeaderboardSort) -> &'static str {
+    match sort {
+        WantedlyLeaderboardSort::Views => "views",
+        WantedlyLeaderboardSort::DistinctViewers => "distinct_viewers",
+        WantedlyLeaderboardSort::FirstViewedAt => "first_viewed_at",
+        WantedlyLeaderboardSort::LastViewedAt => "last_viewed_at",
+        WantedlyLeaderboardSort::Trend => "trend",
+    }
+}
+
+/// GET /dashboard?from=YYYY-MM-DD&to=YYYY-MM-DD
+///
+/// 期間の省略時は今日（JST）までの 30 日。閲覧数の推移・上位の会社・最近の閲覧・取り込み状況を 1 画面にまとめる。
+pub async fn overview_handler(
+    State(state): State<AppState>,
+    Query(query): Query<DashboardQuery>,
+) -> AppResult<Html<String>> {
+    let period = query.period()?;
+    let previous = period.previous();
+
+    let totals = summarize_views(&state.pool, period.from, period.to).await?;
+    let previous_totals = summarize_views(&state.pool, previous.from, previous.to).await?;
+    let card = |label, value, previous| SummaryCard {
+        label,
+        value,
+        previous,
+        change: format_change(value, previous),
+    };
+    let cards = vec![
+        card("閲覧数", totals.views, previous_totals.views),
+        card(
+            "閲覧者数",
+            totals.distinct_viewers,
+            previous_totals.distinct_viewers,
+        ),
+        card(
+            "会社数",
+            totals.distinct_companies,
+            previous_totals.distinct_companies,
+        ),
+    ];
+
+    let (bucket, bucket_label) = if period.days() <= DAILY_CHART_MAX_DAYS {
+        (WantedlyTimeBucket::Day, "日ごと")
+    } else {
+        (WantedlyTimeBucket::Week, "週ごと")
+    };
+    let series = list_view_time_series(&state.pool, period.from, period.to, bucket).await?;
+    let labels: Vec<String> = series
+        .iter()
+        .map(|b| b.bucket_start.format("%m/%d").to_string())
+        .collect();
+    let values: Vec<i64> = series.iter().map(|b| b.views).collect();
+    let chart = svg::column_chart("閲覧数", &labels, &values, None);
+
+    let companies = list_company_leaderboard(
+        &state.pool,
+        period.from,
+        period.to,
+        WantedlyLeaderboardSort::Views,
+        WantedlySortOrder::Desc,
+        OVERVIEW_COMPANIES,
+    )
+    .await?;
+    let views = list_recent_views(&state.pool, OVERVIEW_VIEWS).await?;
+    let last_import = list_snapshot_imports(&state.pool)
+        .await?
+        .pop()
+        .map(ImportRow::from);
+
+    render(&OverviewPage {
+        layout: Layout {
+            active: "/dashboard",
+        },
+        period,
+        cards,
+        bucket_label,
+        chart,
+        companies: companies.into_iter().map(CompanyRow::from).collect(),
+        views: views.into_iter().map(ViewRow::from).collect(),
+        last_import,
+    })
+}
+
+/// GET /dashboard/companies?from=YYYY-MM-DD&to=YYYY-MM-DD&sort=views|distinct_viewers|trend|...
+pub async fn companies_handler(
+    State(state): State<AppState>,
+    Query(query): Query<DashboardQuery>,
+) -> AppResult<Html<String>> {
+    let period = query.period()?;
+    let sort = query.sort.unwrap_or_default();
+
+    let companies = list_company_leaderboard(
+        &state.pool,
+        period.from,
+        period.to,
+        sort,
+        WantedlySortOrder::Desc,
+        COMPANY_PAGE_ROWS,
+    )
+    .await?;
+
+    render(&CompaniesPage {
+        layout: Layout {
+            active: "/dashboard/companies",
+        },
+        period,
+        sorts: SORTS,
+        sort: sort_value(sort),
+        companies: companies.into_iter().map(CompanyRow::from).collect(),
+    })
+}
+
+/// GET /dashboard/viewers
+pub async fn viewers_handler(State(state): State<AppState>) -> AppResult<Html<String>> {
+    let views = list_recent_views(&state.pool, VIEWER_PAGE_ROWS).await?;
+
+    render(&ViewersPage {
+        layout: Layout {
+            active: "/dashboard/viewers",
+        },
+        views: views.into_iter().map(ViewRow::from).collect(),
+    })
+}
+
+/// GET /dashboard/imports
+pub async fn imports_handler(State(state): State<AppState>) -> AppResult<Html<String>> {
+    let all = WantedlyViewQuery::default();
+    let raw_rows = count_profile_views_raw(&state.pool, &all).await?;
+    let impressions = count_impressions(&state.pool, &all).await?;
+    let mut imports = list_snapshot_imports(&state.pool).await?;
+    imports.reverse();
+
+    render(&ImportsPage {
+        layout: Layout {
+            active: "/dashboard/imports",
+        },
+        raw_rows,
+        impressions,
+        imports: imports.into_iter().map(ImportRow::from).collect(),
+    })
+}
+
+/// GET /dashboard/assets/{file}
+pub async fn asset_handler(Path(file): Path<String>) -> AppResult<Response> {
+    let (_, content_type, body) = ASSETS
+        .iter()
+        .find(|(name, _, _)| *name == file)
+        .ok_or_else(|| AppError::NotFound(format!("asset not found: {file}")))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, *content_type),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        *body,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_escape_text_and_mark_the_active_link() {
+        let page = ViewersPage {
+            layout: Layout {
+                active: "/dashboard/viewers",
+            },
+            views: vec![ViewRow {
+                viewed_at: "2025-11-03 10:00".into(),
+                viewer_user_id: "<b>u1</b>".into(),
+                company: "A & B".into(),
+                affiliation: String::new(),
+            }],
+        };
+
+        let html = page.render().unwrap();
+
+        assert!(html.contains("&#60;b&#62;u1&#60;/b&#62;"));
+        assert!(html.contains("A &#38; B"));
+        assert!(html.contains(r#"<a href="/dashboard/viewers" class="active">"#));
+        assert!(!html.contains(r#"<a href="/dashboard" class="active">"#));
+    }
+
+    #[test]
+    fn overview_embeds_the_chart_as_markup() {
+        let page = OverviewPage {
+            layout: Layout {
+                active: "/dashboard",
+            },
+            period: JstPeriod {
+                from: NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2025, 11, 3).unwrap(),
+            },
+            cards: Vec::new(),
+            bucket_label: "日ごと",
+            chart: svg::column_chart("閲覧数", &["11/01".into()], &[1], None),
+            companies: Vec::new(),
+            views: Vec::new(),
+            last_import: None,
+        };
+
+        let html = page.render().unwrap();
+
+        assert!(html.contains("<figure><svg xmlns="));
+        assert!(html.contains(r#"value="2025-11-01""#));
+        assert!(html.contains("まだ取り込まれていません。"));
+    }
+
+    #[test]
+    fn every_sort_option_round_trips() {
+        for (value, _) in SORTS {
+            let sort: WantedlyLeaderboardSort =
+                serde_json::from_value(serde_json::Value::String(value.to_string())).unwrap();
+            assert_eq!(sort_value(sort), *value);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use storage::documents::{
+    InsightDocument, InsightDocumentKind, NewInsightDocument, insert_document,
+};
+use storage::wantedly::find_company_by_slug;
+use utoipa::ToSchema;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody, require_text},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NewInsightDocumentRequest {
+    kind: InsightDocumentKind,
+    title: String,
+    body: String,
+    /// 関係する会社（Wantedly の slug）
+    company_slug: Option<String>,
+    /// 受け取った・書いた日時（省略すると今）
+    occurred_at: Option<DateTime<Utc>>,
+}
+
+/// POST /documents  {"kind": "scout_message", "title": "...", "body": "...", "company_slug": "..."}
+///
+/// RAG の資料にするスカウトやメモを登録する。埋め込みは backfill-embeddings で作る。
+#[utoipa::path(
+    post,
+    path = "/documents",
+    operation_id = "create_document",
+    tag = "rag",
+    request_body = NewInsightDocumentRequest,
+    responses(
+        (status = 201, body = InsightDocument),
+        (status = 400, description = "title または body が空", body = ErrorBody),
+        (status = 404, description = "会社が見つからない", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn create_handler(
+    State(state): State<AppState>,
+    Json(request): Json<NewInsightDocumentRequest>,
+) -> AppResult<(StatusCode, Json<InsightDocument>)> {
+    require_text("title", &request.title)?;
+    require_text("body", &request.body)?;
+
+    let company_id = match request
+        .company_slug
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(slug) => Some(
+            find_company_by_slug(&state.pool, slug)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("company not found: {slug}")))?
+                .id,
+        ),
+        None => None,
+    };
+
+    let document = insert_document(
+        &state.pool,
+        &NewInsightDocument {
+            kind: request.kind,
+            title: request.title.trim().to_string(),
+            body: request.body.trim().to_string(),
+            company_id,
+            occurred_at: request.occurred_at,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(document)))
+}
@@ -1,12 +1,24 @@
-use crate::error::{AppError, AppResult};
+use crate::error::{AppError, AppResult, ErrorBody};
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct EchoPayload {
     text: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/echo",
+    operation_id = "echo",
+    tag = "system",
+    request_body = EchoPayload,
+    responses(
+        (status = 200, body = EchoPayload),
+        (status = 400, description = "`text` が空", body = ErrorBody),
+    )
+)]
 pub async fn handler(Json(payload): Json<EchoPayload>) -> AppResult<Json<EchoPayload>> {
     if payload.text.trim().is_empty() {
         return Err(AppError::BadRequest("text must not be empty".into()));
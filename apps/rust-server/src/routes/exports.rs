@@ -0,0 +1,168 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use storage::wantedly::WantedlyExportFilter;
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::export::{
+        ExportDataset, ExportFormat,
+        anonymize::{AnonymizeOptions, CompanyMode, DescriptionMode},
+        prepare_export_table,
+        writer::write_export_table,
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    company: Option<String>,
+    #[serde(default)]
+    anonymize: bool,
+    #[serde(default)]
+    description_mode: DescriptionMode,
+    #[serde(default)]
+    company_mode: CompanyMode,
+}
+
+impl ExportQuery {
+    pub fn anonymize_options(&self, key: Option<&[u8]>) -> AppResult<Option<AnonymizeOptions>> {
+        if !self.anonymize {
+            return Ok(None);
+        }
+        let key = key.ok_or_else(|| {
+            AppError::Internal("anonymized export requires EXPORT_ANONYMIZE_KEY".into())
+        })?;
+
+        Ok(Some(AnonymizeOptions {
+            key: key.to_vec(),
+            description: self.description_mode,
+            company: self.company_mode,
+        }))
+    }
+
+    pub fn into_filter(self) -> AppResult<WantedlyExportFilter> {
+        if let (Some(from), Some(to)) = (self.from, self.to)
+            && from > to
+        {
+            return Err(AppError::BadRequest("`from` must not be after `to`".into()));
+        }
+
+        Ok(WantedlyExportFilter {
+            from: self.from,
+            to: self.to,
+            company_slug: self.company.filter(|c| !c.trim().is_empty()),
+        })
+    }
+}
+
+/// GET /exports/{dataset}?format=csv|jsonl|parquet&from=YYYY-MM-DD&to=YYYY-MM-DD&company=slug
+///     &anonymize=true&description_mode=strip|generalize&company_mode=keep|hash|bucket
+#[utoipa::path(
+    get,
+    path = "/exports/{dataset}",
+    operation_id = "export_dataset",
+    tag = "exports",
+    params(("dataset" = ExportDataset, Path, description = "データセット"), ExportQuery),
+    responses(
+        (status = 200, description = "`format` に応じたファイル（添付）", content(
+            (String = "text/csv; charset=utf-8"),
+            (String = "application/x-ndjson"),
+            (String = "application/vnd.apache.parquet"),
+        )),
+        (status = 400, description = "期間が不正", body = ErrorBody),
+        (status = 500, description = "匿名化の鍵が未設定など", body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Path(dataset): Path<ExportDataset>,
+    Query(query): Query<ExportQuery>,
+) -> AppResult<Response> {
+    let format = query.format;
+    let anonymize = query.anonymize_options(state.config.export_anonymize_key.as_deref())?;
+    let filter = query.into_filter()?;
+
+    let table = prepare_export_table(&state.pool, dataset, &filter, anonymize.as_ref()).await?;
+
+    let mut body = Vec::new();
+    write_export_table(&table, format, &mut body)?;
+
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        dataset.as_str(),
+        format.extension()
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversed_range_is_bad_request() {
+        let query = ExportQuery {
+            format: ExportFormat::Csv,
+            from: NaiveDate::from_ymd_opt(2025, 12, 1),
+            to: NaiveDate::from_ymd_opt(2025, 11, 1),
+            company: None,
+            anonymize: false,
+            description_mode: DescriptionMode::Strip,
+            company_mode: CompanyMode::Hash,
+        };
+
+        assert!(matches!(query.into_filter(), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn blank_company_is_ignored() {
+        let query = ExportQuery {
+            format: ExportFormat::Jsonl,
+            from: None,
+            to: None,
+            company: Some("  ".into()),
+            anonymize: false,
+            description_mode: DescriptionMode::Strip,
+            company_mode: CompanyMode::Hash,
+        };
+
+        assert_eq!(query.into_filter().unwrap().company_slug, None);
+    }
+
+    #[test]
+    fn anonymize_without_key_is_an_error() {
+        let query = ExportQuery {
+            format: ExportFormat::Csv,
+            from: None,
+            to: None,
+            company: None,
+            anonymize: true,
+            description_mode: DescriptionMode::Generalize,
+            company_mode: CompanyMode::Keep,
+        };
+
+        assert!(query.anonymize_options(None).is_err());
+
+        let options = query.anonymize_options(Some(b"key")).unwrap().unwrap();
+        assert_eq!(options.company, CompanyMode::Keep);
+    }
+}
@@ -0,0 +1,21 @@
+use async_graphql::http::GraphiQLSource;
+use axum::{Json, extract::State, response::Html};
+
+use crate::{infra::graphql::request_loader, state::AppState};
+
+/// POST /graphql  {"query": "...", "variables": {...}}
+///
+/// 検証・実行のエラーは GraphQL の慣習どおり 200 の `errors` で返す。
+pub async fn handler(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let request = request.data(request_loader(state.pool.clone()));
+
+    Json(state.graphql.execute(request).await)
+}
+
+/// GET /graphql（GraphiQL）
+pub async fn graphiql_handler() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
@@ -1,3 +1,11 @@
+/// GET /health
+#[utoipa::path(
+    get,
+    path = "/health",
+    operation_id = "health",
+    tag = "system",
+    responses((status = 200, description = "生存確認", body = String, content_type = "text/plain"))
+)]
 pub async fn handler() -> String {
     "ok".to_string()
 }
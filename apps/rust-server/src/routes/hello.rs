@@ -1,11 +1,19 @@
 use axum::Json;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct HelloResponse {
     message: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/hello",
+    operation_id = "hello",
+    tag = "system",
+    responses((status = 200, body = HelloResponse))
+)]
 pub async fn handler() -> Json<HelloResponse> {
     Json(HelloResponse {
         message: "hello from axum".into(),
@@ -0,0 +1,152 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use storage::hypotheses::{
+    NewProfileHypothesis, NewProfileIntervention, ProfileHypothesis, ProfileIntervention,
+    find_hypothesis_by_id, insert_hypothesis, insert_intervention, list_hypotheses,
+    list_interventions,
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody, require_text},
+    infra::{
+        analytics::{experiment::HypothesisAnalysis, period::jst_today},
+        usecase::analyze_profile_hypothesis::{
+            HypothesisAnalysisOptions, analyze_profile_hypothesis,
+        },
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HypothesisWithInterventions {
+    #[serde(flatten)]
+    hypothesis: ProfileHypothesis,
+    interventions: Vec<ProfileIntervention>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnalysisQuery {
+    window_days: Option<u32>,
+    confidence: Option<f64>,
+}
+
+/// POST /hypotheses  {"title": "...", "description": "...", "metric": "views", "expected_effect": "increase"}
+#[utoipa::path(
+    post,
+    path = "/hypotheses",
+    operation_id = "create_hypothesis",
+    tag = "hypotheses",
+    request_body = NewProfileHypothesis,
+    responses(
+        (status = 201, body = ProfileHypothesis),
+        (status = 400, description = "`title` が空", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn create_handler(
+    State(state): State<AppState>,
+    Json(new): Json<NewProfileHypothesis>,
+) -> AppResult<(StatusCode, Json<ProfileHypothesis>)> {
+    require_text("title", &new.title)?;
+
+    let hypothesis = insert_hypothesis(&state.pool, &new).await?;
+
+    Ok((StatusCode::CREATED, Json(hypothesis)))
+}
+
+/// GET /hypotheses
+#[utoipa::path(
+    get,
+    path = "/hypotheses",
+    operation_id = "list_hypotheses",
+    tag = "hypotheses",
+    responses(
+        (status = 200, body = Vec<HypothesisWithInterventions>),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn list_handler(
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<HypothesisWithInterventions>>> {
+    let hypotheses = list_hypotheses(&state.pool).await?;
+    let interventions = list_interventions(&state.pool).await?;
+
+    let items = hypotheses
+        .into_iter()
+        .map(|hypothesis| HypothesisWithInterventions {
+            interventions: interventions
+                .iter()
+                .filter(|i| i.hypothesis_id == hypothesis.id)
+                .cloned()
+                .collect(),
+            hypothesis,
+        })
+        .collect();
+
+    Ok(Json(items))
+}
+
+/// POST /hypotheses/{id}/interventions  {"description": "...", "changed_on": "YYYY-MM-DD"}
+#[utoipa::path(
+    post,
+    path = "/hypotheses/{id}/interventions",
+    operation_id = "create_intervention",
+    tag = "hypotheses",
+    params(("id" = i64, Path, description = "仮説の ID")),
+    request_body = NewProfileIntervention,
+    responses(
+        (status = 201, body = ProfileIntervention),
+        (status = 400, description = "`description` が空", body = ErrorBody),
+        (status = 404, description = "仮説が見つからない", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn create_intervention_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(new): Json<NewProfileIntervention>,
+) -> AppResult<(StatusCode, Json<ProfileIntervention>)> {
+    require_text("description", &new.description)?;
+
+    if find_hypothesis_by_id(&state.pool, id).await?.is_none() {
+        return Err(AppError::NotFound(format!("hypothesis not found: {id}")));
+    }
+    let intervention = insert_intervention(&state.pool, id, &new).await?;
+
+    Ok((StatusCode::CREATED, Json(intervention)))
+}
+
+/// GET /hypotheses/{id}/analysis?window_days=14&confidence=0.95
+#[utoipa::path(
+    get,
+    path = "/hypotheses/{id}/analysis",
+    operation_id = "get_hypothesis_analysis",
+    tag = "hypotheses",
+    params(("id" = i64, Path, description = "仮説の ID"), AnalysisQuery),
+    responses(
+        (status = 200, body = HypothesisAnalysis),
+        (status = 400, description = "窓の日数または信頼水準が不正", body = ErrorBody),
+        (status = 404, description = "仮説が見つからない", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn analysis_handler(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(query): Query<AnalysisQuery>,
+) -> AppResult<Json<HypothesisAnalysis>> {
+    let options = HypothesisAnalysisOptions::resolve(query.window_days, query.confidence)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let analysis = analyze_profile_hypothesis(&state.pool, id, &options, jst_today())
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("hypothesis not found: {id}")))?;
+
+    Ok(Json(analysis))
+}
@@ -0,0 +1,116 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, StreamExt};
+use storage::wantedly::{WantedlyLiveEvent, latest_live_event_id};
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::live::live_event_stream,
+    state::AppState,
+};
+
+const LAST_EVENT_ID: &str = "last-event-id";
+
+/// GET /live/events（Server-Sent Events）
+///
+/// 取り込みで新しく現れた会社（`company`）とインプレッション（`impression`）を流す。
+/// `Last-Event-ID` を付けて繋ぎ直すと、その id より後のイベントを DB から再送してから続ける。
+#[utoipa::path(
+    get,
+    path = "/live/events",
+    operation_id = "stream_live_events",
+    tag = "live",
+    params(
+        ("Last-Event-ID" = Option<i64>, Header, description = "最後に受け取ったイベントの id"),
+    ),
+    responses(
+        (status = 200, description = "`id` / `event`（impression | company）/ `data`（JSON）のイベントストリーム", content_type = "text/event-stream", body = String),
+        (status = 400, description = "Last-Event-ID が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let last_event_id = parse_last_event_id(&headers)?;
+
+    // 先に購読してから位置を決めるので、その間に届いたイベントも落とさない
+    let receiver = state.live.subscribe();
+    let (last_id, replay) = match last_event_id {
+        Some(id) => (id, true),
+        None => (latest_live_event_id(&state.pool).await?, false),
+    };
+
+    let stream = live_event_stream(state.pool.clone(), receiver, last_id, replay)
+        .map(|event| Ok(to_sse_event(&event)));
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn parse_last_event_id(headers: &HeaderMap) -> AppResult<Option<i64>> {
+    let Some(value) = headers.get(LAST_EVENT_ID) else {
+        return Ok(None);
+    };
+
+    value
+        .to_str()
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|id| *id >= 0)
+        .map(Some)
+        .ok_or_else(|| AppError::BadRequest("Last-Event-ID must be a non-negative integer".into()))
+}
+
+fn to_sse_event(event: &WantedlyLiveEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.kind.as_str())
+        .data(event.payload.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use chrono::Utc;
+    use storage::wantedly::WantedlyLiveEventKind;
+
+    #[test]
+    fn last_event_id_must_be_a_non_negative_integer() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_last_event_id(&headers).unwrap(), None);
+
+        headers.insert(LAST_EVENT_ID, HeaderValue::from_static(" 42 "));
+        assert_eq!(parse_last_event_id(&headers).unwrap(), Some(42));
+
+        for bad in ["abc", "-1", ""] {
+            headers.insert(LAST_EVENT_ID, HeaderValue::from_static(bad));
+            assert!(parse_last_event_id(&headers).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn sse_event_carries_id_kind_and_payload() {
+        let event = WantedlyLiveEvent {
+            id: 7,
+            kind: WantedlyLiveEventKind::Impression,
+            payload: serde_json::json!({ "impression_id": 3, "company_slug": "co_a" }),
+            created_at: Utc::now(),
+        };
+
+        let sse = format!("{:?}", to_sse_event(&event));
+
+        assert!(sse.contains("id: 7"), "{sse}");
+        assert!(sse.contains("event: impression"), "{sse}");
+        assert!(
+            sse.contains(r#"data: {\"company_slug\":\"co_a\",\"impression_id\":3}"#),
+            "{sse}"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use axum::{
+    http::header,
+    routing::{MethodRouter, get},
+};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        self,
+        security::{Http, HttpAuthScheme, SecurityScheme},
+    },
+};
+
+use storage::wantedly::{WantedlyLeaderboardSort, WantedlySortOrder};
+
+use crate::{
+    error::ErrorBody,
+    infra::{
+        export::{
+            ExportDataset, ExportFormat,
+            anonymize::{CompanyMode, DescriptionMode},
+        },
+        report::ReportFormat,
+    },
+    state::AppState,
+};
+
+/// ルートに付けた `#[utoipa::path]` 以外の部分（情報・共通スキーマ・認証方式）
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "profile-insights API",
+        description = "Wantedly のプロフィール閲覧データの集計 API。ハンドラが返すエラーは ErrorBody（クエリ・本文の形式エラーは axum のテキスト応答）。"
+    ),
+    // クエリ・パスにしか出てこない型は自動では集まらないので、ここで登録する
+    components(schemas(
+        ErrorBody,
+        WantedlyLeaderboardSort,
+        WantedlySortOrder,
+        ExportDataset,
+        ExportFormat,
+        DescriptionMode,
+        CompanyMode,
+        ReportFormat,
+    )),
+    modifiers(&AdminTokenScheme),
+    tags(
+        (name = "system", description = "疎通確認"),
+        (name = "analytics", description = "閲覧の集計"),
+        (name = "companies", description = "会社属性"),
+        (name = "views", description = "閲覧・インプレッションの一覧"),
+        (name = "viewers", description = "閲覧者"),
+        (name = "hypotheses", description = "プロフィール改善の仮説と施策"),
+        (name = "profile", description = "自分のプロフィール"),
+        (name = "reports", description = "レポート"),
+        (name = "exports", description = "データのエクスポート"),
+        (name = "rag", description = "スカウト・メモの登録と、資料を引用した質問応答"),
+        (name = "live", description = "取り込みのライブフィード（Server-Sent Events）"),
+        (name = "admin", description = "管理用（ADMIN_TOKEN が必要）"),
+    )
+)]
+pub struct ApiDoc;
+
+/// 管理 API の Bearer 認証を登録し、Cargo.toml 由来の空のライセンス欄を外す
+struct AdminTokenScheme;
+
+impl Modify for AdminTokenScheme {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        openapi.info.license = None;
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+    }
+}
+
+/// GET /openapi.json（仕様は起動時に 1 度だけ JSON にする）
+pub fn spec_route(spec: &openapi::OpenApi) -> MethodRouter<AppState> {
+    let body = spec
+        .to_pretty_json()
+        .expect("OpenAPI spec should serialize");
+
+    get(move || {
+        let body = body.clone();
+        async move { ([(header::CONTENT_TYPE, "application/json")], body) }
+    })
+}
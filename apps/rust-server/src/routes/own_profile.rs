@@ -0,0 +1,46 @@
+use axum::{Json, extract::State};
+use storage::wantedly::{WantedlyTimeBucket, list_own_profile_versions, list_view_time_series};
+
+use crate::{
+    error::{AppResult, ErrorBody},
+    infra::analytics::{
+        period::jst_today,
+        profile_versions::{OwnProfileTimeline, build_own_profile_timeline, jst_date},
+    },
+    state::AppState,
+};
+
+/// GET /profile/versions
+///
+/// 自分のプロフィールの版ごとに、直前の版との差分と公開されていた期間の閲覧数を返す。
+#[utoipa::path(
+    get,
+    path = "/profile/versions",
+    operation_id = "list_own_profile_versions",
+    tag = "profile",
+    responses(
+        (status = 200, body = OwnProfileTimeline),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn versions_handler(
+    State(state): State<AppState>,
+) -> AppResult<Json<OwnProfileTimeline>> {
+    let versions = list_own_profile_versions(&state.pool).await?;
+    let today = jst_today();
+
+    let daily = match versions.first() {
+        Some(first) if jst_date(first.captured_at) <= today => {
+            list_view_time_series(
+                &state.pool,
+                jst_date(first.captured_at),
+                today,
+                WantedlyTimeBucket::Day,
+            )
+            .await?
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(Json(build_own_profile_timeline(&versions, &daily, today)))
+}
@@ -0,0 +1,192 @@
+use std::convert::Infallible;
+
+use axum::{
+    Json,
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::{
+        llm::TextStream,
+        usecase::answer_question::{RagAnswerStream, RagPassage, stream_answer},
+    },
+    state::AppState,
+};
+
+const DEFAULT_TOP_K: usize = 8;
+const MAX_TOP_K: usize = 20;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RagQuestion {
+    question: String,
+    /// 資料にする件数（既定 8、最大 20）
+    top_k: Option<usize>,
+}
+
+impl RagQuestion {
+    fn top_k(&self) -> AppResult<usize> {
+        let top_k = self.top_k.unwrap_or(DEFAULT_TOP_K);
+        if !(1..=MAX_TOP_K).contains(&top_k) {
+            return Err(AppError::BadRequest(format!(
+                "`top_k` must be between 1 and {MAX_TOP_K}"
+            )));
+        }
+        Ok(top_k)
+    }
+}
+
+/// `sources` イベントの data
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RagSources {
+    prompt_version: &'static str,
+    /// 回答を生成する LLM のモデル
+    model: String,
+    passages: Vec<RagPassage>,
+}
+
+/// POST /rag/answer  {"question": "...", "top_k": 8}（Server-Sent Events）
+///
+/// 質問に近い会社・閲覧者の自己紹介・スカウトとメモを資料にして LLM に答えさせる。
+/// 最初に `sources`（資料の一覧）、続けて回答の断片を `delta`、最後に `done` を流す。
+/// 生成の途中で失敗したときは `error` を流して終わる。
+#[utoipa::path(
+    post,
+    path = "/rag/answer",
+    operation_id = "answer_question",
+    tag = "rag",
+    request_body = RagQuestion,
+    responses(
+        (status = 200, description = "`sources`（RagSources）/ `delta`（{\"text\"}）/ `done` / `error`（{\"message\"}）のイベントストリーム", content_type = "text/event-stream", body = String),
+        (status = 400, description = "質問が空、または件数が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Json(request): Json<RagQuestion>,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let top_k = request.top_k()?;
+    let question = request.question.trim();
+    if question.is_empty() {
+        return Err(AppError::BadRequest("`question` must not be empty".into()));
+    }
+
+    // 検索と LLM への接続までは通常のエラー応答、それ以降はストリーム中の error イベントで返す
+    let answer = stream_answer(
+        &state.pool,
+        state.embedder.as_ref(),
+        state.llm.as_ref(),
+        question,
+        top_k,
+    )
+    .await?;
+
+    Ok(
+        Sse::new(answer_events(answer).map(|event| Ok(event.to_sse_event())))
+            .keep_alive(KeepAlive::default()),
+    )
+}
+
+/// ストリームに流すイベント
+enum AnswerEvent {
+    Sources(RagSources),
+    Delta(String),
+    Error(String),
+    Done,
+}
+
+impl AnswerEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            AnswerEvent::Sources(_) => "sources",
+            AnswerEvent::Delta(_) => "delta",
+            AnswerEvent::Error(_) => "error",
+            AnswerEvent::Done => "done",
+        }
+    }
+
+    fn to_sse_event(&self) -> Event {
+        let data = match self {
+            AnswerEvent::Sources(sources) => serde_json::to_string(sources).unwrap_or_default(),
+            AnswerEvent::Delta(text) => json!({ "text": text }).to_string(),
+            AnswerEvent::Error(message) => json!({ "message": message }).to_string(),
+            AnswerEvent::Done => "{}".to_string(),
+        };
+        Event::default().event(self.name()).data(data)
+    }
+}
+
+enum Phase {
+    Sources(RagSources, TextStream),
+    Deltas(TextStream),
+    Finished,
+}
+
+fn answer_events(answer: RagAnswerStream) -> impl Stream<Item = AnswerEvent> {
+    let sources = RagSources {
+        prompt_version: answer.prompt_version,
+        model: answer.model,
+        passages: answer.passages,
+    };
+
+    stream::unfold(Phase::Sources(sources, answer.deltas), |phase| async move {
+        match phase {
+            Phase::Sources(sources, deltas) => {
+                Some((AnswerEvent::Sources(sources), Phase::Deltas(deltas)))
+            }
+            Phase::Deltas(mut deltas) => match deltas.next().await {
+                Some(Ok(text)) => Some((AnswerEvent::Delta(text), Phase::Deltas(deltas))),
+                Some(Err(e)) => Some((AnswerEvent::Error(e.to_string()), Phase::Finished)),
+                None => Some((AnswerEvent::Done, Phase::Finished)),
+            },
+            Phase::Finished => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::llm::LlmError;
+
+    fn answer(deltas: Vec<Result<String, LlmError>>) -> RagAnswerStream {
+        RagAnswerStream {
+            prompt_version: "rag-test",
+            model: "m".into(),
+            passages: vec![],
+            deltas: stream::iter(deltas).boxed(),
+        }
+    }
+
+    async fn event_names(answer: RagAnswerStream) -> Vec<&'static str> {
+        answer_events(answer)
+            .map(|event| event.name())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn events_start_with_sources_and_end_with_done() {
+        let names = event_names(answer(vec![Ok("a".into()), Ok("b".into())])).await;
+
+        assert_eq!(names, vec!["sources", "delta", "delta", "done"]);
+    }
+
+    #[tokio::test]
+    async fn stream_stops_at_first_error() {
+        let names = event_names(answer(vec![
+            Ok("a".into()),
+            Err(LlmError::InvalidResponse("broken".into())),
+            Ok("b".into()),
+        ]))
+        .await;
+
+        assert_eq!(names, vec!["sources", "delta", "error"]);
+    }
+}
@@ -0,0 +1,58 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::{
+        analytics::{period::jst_today, repeat::RepeatViewerReport},
+        usecase::analyze_repeat_viewers::{RepeatViewerOptions, analyze_repeat_viewers},
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RepeatViewerQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    min_view_days: Option<i64>,
+    limit: Option<i64>,
+}
+
+/// GET /analytics/repeat-viewers?from=YYYY-MM-DD&to=YYYY-MM-DD&min_view_days=2&limit=100
+///
+/// 期間の省略時は今日（JST）までの 365 日。
+#[utoipa::path(
+    get,
+    path = "/analytics/repeat-viewers",
+    operation_id = "get_repeat_viewers",
+    tag = "analytics",
+    params(RepeatViewerQuery),
+    responses(
+        (status = 200, body = RepeatViewerReport),
+        (status = 400, description = "期間・日数・件数が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Query(query): Query<RepeatViewerQuery>,
+) -> AppResult<Json<RepeatViewerReport>> {
+    let options = RepeatViewerOptions::resolve(
+        query.from,
+        query.to,
+        query.min_view_days,
+        query.limit,
+        jst_today(),
+    )
+    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let report = analyze_repeat_viewers(&state.pool, &options).await?;
+
+    Ok(Json(report))
+}
@@ -0,0 +1,94 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::usecase::find_similar_companies::{
+        SimilarCompanyList, SimilarTo, find_similar_companies,
+    },
+    state::AppState,
+};
+
+const DEFAULT_LIMIT: i64 = 10;
+const MAX_LIMIT: i64 = 50;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SimilarCompanyQuery {
+    /// 基準にする会社（q とどちらか一方）
+    slug: Option<String>,
+    /// 自由文（slug とどちらか一方）
+    q: Option<String>,
+    /// true: 閲覧のあった会社だけ / false: 閲覧のなかった会社だけ
+    has_viewed: Option<bool>,
+    limit: Option<i64>,
+}
+
+impl SimilarCompanyQuery {
+    fn limit(&self) -> AppResult<i64> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(AppError::BadRequest(format!(
+                "`limit` must be between 1 and {MAX_LIMIT}"
+            )));
+        }
+        Ok(limit)
+    }
+
+    fn target(&self) -> AppResult<SimilarTo<'_>> {
+        let slug = self
+            .slug
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        let q = self.q.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+        match (slug, q) {
+            (Some(slug), None) => Ok(SimilarTo::Company(slug)),
+            (None, Some(q)) => Ok(SimilarTo::Text(q)),
+            _ => Err(AppError::BadRequest(
+                "specify exactly one of `slug` or `q`".into(),
+            )),
+        }
+    }
+}
+
+/// GET /companies/similar?slug=...|q=...&has_viewed=true|false&limit=10
+///
+/// 会社の埋め込み（backfill-embeddings で作ったもの）がコサイン距離で近い順に返す。
+/// 各社に、プロフィールを閲覧したことがあるか（has_viewed）と閲覧数を付ける。
+#[utoipa::path(
+    get,
+    path = "/companies/similar",
+    operation_id = "list_similar_companies",
+    tag = "companies",
+    params(SimilarCompanyQuery),
+    responses(
+        (status = 200, body = SimilarCompanyList),
+        (status = 400, description = "slug と q の指定、または件数が不正", body = ErrorBody),
+        (status = 404, description = "会社、またはその会社の埋め込みが見つからない", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Query(query): Query<SimilarCompanyQuery>,
+) -> AppResult<Json<SimilarCompanyList>> {
+    let limit = query.limit()?;
+    let target = query.target()?;
+
+    let list = find_similar_companies(
+        &state.pool,
+        state.embedder.as_ref(),
+        target,
+        query.has_viewed,
+        limit,
+    )
+    .await?;
+
+    Ok(Json(list))
+}
@@ -0,0 +1,64 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use storage::wantedly::WantedlyAnomalyKind;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::{
+        analytics::{
+            anomaly::ViewAnomaly,
+            period::{JstPeriod, jst_today},
+        },
+        usecase::detect_view_anomalies::list_view_anomalies_with_companies,
+    },
+    state::AppState,
+};
+
+const DEFAULT_DAYS: u32 = 90;
+const MAX_DAYS: u32 = 1100;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ViewAnomalyQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    kind: Option<WantedlyAnomalyKind>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ViewAnomalyList {
+    period: JstPeriod,
+    anomalies: Vec<ViewAnomaly>,
+}
+
+/// GET /analytics/anomalies?from=YYYY-MM-DD&to=YYYY-MM-DD&kind=spike|drop
+///
+/// 検出ジョブ（serve 起動時 / detect-anomalies）が記録した異常を返す。期間の省略時は今日（JST）までの 90 日。
+#[utoipa::path(
+    get,
+    path = "/analytics/anomalies",
+    operation_id = "list_view_anomalies",
+    tag = "analytics",
+    params(ViewAnomalyQuery),
+    responses(
+        (status = 200, body = ViewAnomalyList),
+        (status = 400, description = "期間が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Query(query): Query<ViewAnomalyQuery>,
+) -> AppResult<Json<ViewAnomalyList>> {
+    let period = JstPeriod::resolve(query.from, query.to, DEFAULT_DAYS, MAX_DAYS, jst_today())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let anomalies = list_view_anomalies_with_companies(&state.pool, period, query.kind).await?;
+
+    Ok(Json(ViewAnomalyList { period, anomalies }))
+}
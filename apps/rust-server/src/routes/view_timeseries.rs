@@ -0,0 +1,69 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use storage::wantedly::{WantedlyTimeBucket, list_view_time_series, list_views_by_weekday};
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::analytics::{
+        period::{JstPeriod, jst_today},
+        timeseries::ViewTimeSeries,
+    },
+    state::AppState,
+};
+
+const DEFAULT_DAYS: u32 = 90;
+const MAX_DAYS: u32 = 1100;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimeSeriesQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    #[serde(default)]
+    granularity: WantedlyTimeBucket,
+}
+
+impl TimeSeriesQuery {
+    pub fn period(&self, today: NaiveDate) -> AppResult<JstPeriod> {
+        JstPeriod::resolve(self.from, self.to, DEFAULT_DAYS, MAX_DAYS, today)
+            .map_err(|e| AppError::BadRequest(e.to_string()))
+    }
+}
+
+/// GET /analytics/views?from=YYYY-MM-DD&to=YYYY-MM-DD&granularity=day|week|month
+///
+/// 期間の省略時は今日（JST）までの 90 日。閲覧のない区間も 0 で返す。
+#[utoipa::path(
+    get,
+    path = "/analytics/views",
+    operation_id = "get_view_time_series",
+    tag = "analytics",
+    params(TimeSeriesQuery),
+    responses(
+        (status = 200, body = ViewTimeSeries),
+        (status = 400, description = "期間が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Query(query): Query<TimeSeriesQuery>,
+) -> AppResult<Json<ViewTimeSeries>> {
+    let period = query.period(jst_today())?;
+
+    let buckets =
+        list_view_time_series(&state.pool, period.from, period.to, query.granularity).await?;
+    let weekdays = list_views_by_weekday(&state.pool, period.from, period.to).await?;
+
+    Ok(Json(ViewTimeSeries::new(
+        period,
+        query.granularity,
+        buckets,
+        weekdays,
+    )))
+}
@@ -0,0 +1,50 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use serde::Serialize;
+use storage::wantedly::{
+    WantedlyViewer, WantedlyViewerAffiliationSummary, find_viewer_by_source_user_id,
+    list_viewer_affiliation_timeline,
+};
+use utoipa::ToSchema;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    state::AppState,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ViewerAffiliationTimeline {
+    viewer: WantedlyViewer,
+    affiliations: Vec<WantedlyViewerAffiliationSummary>,
+}
+
+/// GET /viewers/{source_user_id}/affiliations
+#[utoipa::path(
+    get,
+    path = "/viewers/{source_user_id}/affiliations",
+    operation_id = "get_viewer_affiliations",
+    tag = "viewers",
+    params(("source_user_id" = String, Path, description = "Wantedly のユーザー ID")),
+    responses(
+        (status = 200, body = ViewerAffiliationTimeline),
+        (status = 404, description = "閲覧者が見つからない", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Path(source_user_id): Path<String>,
+) -> AppResult<Json<ViewerAffiliationTimeline>> {
+    let viewer = find_viewer_by_source_user_id(&state.pool, &source_user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("viewer not found: {source_user_id}")))?;
+
+    let affiliations = list_viewer_affiliation_timeline(&state.pool, viewer.id).await?;
+
+    Ok(Json(ViewerAffiliationTimeline {
+        viewer,
+        affiliations,
+    }))
+}
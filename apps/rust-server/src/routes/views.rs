@@ -0,0 +1,181 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use storage::wantedly::{
+    ViewerAffiliationKind, WantedlyImpression, WantedlyKeysetCursor, WantedlyProfileViewRaw,
+    WantedlySortOrder, WantedlyViewQuery, count_impressions, count_profile_views_raw,
+    list_impressions_page, list_profile_views_raw_page,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::pagination::{Page, decode_cursor},
+    state::AppState,
+};
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ViewListQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    company: Option<String>,
+    viewer: Option<String>,
+    affiliation_kind: Option<ViewerAffiliationKind>,
+    #[serde(default)]
+    order: WantedlySortOrder,
+    limit: Option<usize>,
+    /// 前のページの `next_cursor`
+    cursor: Option<String>,
+}
+
+impl ViewListQuery {
+    /// (絞り込み条件, 1 ページの件数)
+    pub fn into_query(self) -> AppResult<(WantedlyViewQuery, usize)> {
+        if let (Some(from), Some(to)) = (self.from, self.to)
+            && from > to
+        {
+            return Err(AppError::BadRequest("`from` must not be after `to`".into()));
+        }
+
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(AppError::BadRequest(format!(
+                "`limit` must be between 1 and {MAX_LIMIT}"
+            )));
+        }
+
+        let after = self
+            .cursor
+            .as_deref()
+            .map(|c| decode_cursor(c).ok_or_else(|| AppError::BadRequest("invalid cursor".into())))
+            .transpose()?;
+
+        let query = WantedlyViewQuery {
+            from: self.from,
+            to: self.to,
+            company_slug: self.company.filter(|c| !c.trim().is_empty()),
+            viewer_user_id: self.viewer.filter(|v| !v.trim().is_empty()),
+            affiliation_kind: self.affiliation_kind,
+            order: self.order,
+            after,
+        };
+
+        Ok((query, limit))
+    }
+}
+
+/// GET /impressions?from=YYYY-MM-DD&to=YYYY-MM-DD&company=slug&viewer=userId
+///     &affiliation_kind=company|freeform|unknown&order=asc|desc&limit=50&cursor=...
+#[utoipa::path(
+    get,
+    path = "/impressions",
+    operation_id = "list_impressions",
+    tag = "views",
+    params(ViewListQuery),
+    responses(
+        (status = 200, body = Page<WantedlyImpression>),
+        (status = 400, description = "期間・件数・カーソルが不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn impressions_handler(
+    State(state): State<AppState>,
+    Query(query): Query<ViewListQuery>,
+) -> AppResult<Json<Page<WantedlyImpression>>> {
+    let (query, limit) = query.into_query()?;
+
+    let rows = list_impressions_page(&state.pool, &query, limit as i64 + 1).await?;
+    let total = count_impressions(&state.pool, &query).await?;
+
+    Ok(Json(Page::from_overfetched(rows, limit, total, |row| {
+        WantedlyKeysetCursor {
+            at: row.impressed_at,
+            id: row.id,
+        }
+    })))
+}
+
+/// GET /raw-views（クエリは /impressions と同じ。日付は viewed_at に掛かる）
+#[utoipa::path(
+    get,
+    path = "/raw-views",
+    operation_id = "list_raw_views",
+    tag = "views",
+    params(ViewListQuery),
+    responses(
+        (status = 200, body = Page<WantedlyProfileViewRaw>),
+        (status = 400, description = "期間・件数・カーソルが不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn raw_views_handler(
+    State(state): State<AppState>,
+    Query(query): Query<ViewListQuery>,
+) -> AppResult<Json<Page<WantedlyProfileViewRaw>>> {
+    let (query, limit) = query.into_query()?;
+
+    let rows = list_profile_views_raw_page(&state.pool, &query, limit as i64 + 1).await?;
+    let total = count_profile_views_raw(&state.pool, &query).await?;
+
+    Ok(Json(Page::from_overfetched(rows, limit, total, |row| {
+        WantedlyKeysetCursor {
+            at: row.viewed_at,
+            id: row.id,
+        }
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_defaults_and_is_bounded() {
+        let (_, limit) = ViewListQuery::default().into_query().unwrap();
+        assert_eq!(limit, DEFAULT_LIMIT);
+
+        for limit in [0, MAX_LIMIT + 1] {
+            let query = ViewListQuery {
+                limit: Some(limit),
+                ..ViewListQuery::default()
+            };
+            assert!(matches!(query.into_query(), Err(AppError::BadRequest(_))));
+        }
+    }
+
+    #[test]
+    fn invalid_cursor_is_bad_request() {
+        let query = ViewListQuery {
+            cursor: Some("not-a-cursor".into()),
+            ..ViewListQuery::default()
+        };
+
+        assert!(matches!(query.into_query(), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn blank_filters_are_ignored() {
+        let query = ViewListQuery {
+            company: Some(" ".into()),
+            viewer: Some("".into()),
+            affiliation_kind: Some(ViewerAffiliationKind::Freeform),
+            ..ViewListQuery::default()
+        };
+
+        let (query, _) = query.into_query().unwrap();
+        assert_eq!(query.company_slug, None);
+        assert_eq!(query.viewer_user_id, None);
+        assert_eq!(
+            query.affiliation_kind,
+            Some(ViewerAffiliationKind::Freeform)
+        );
+        assert_eq!(query.order, WantedlySortOrder::Desc);
+    }
+}
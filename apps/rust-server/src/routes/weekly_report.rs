@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    error::{AppError, AppResult, ErrorBody},
+    infra::{
+        analytics::period::jst_today,
+        report::ReportFormat,
+        usecase::build_weekly_report::{build_weekly_report, resolve_report_period},
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WeeklyReportQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+/// GET /reports/weekly?from=YYYY-MM-DD&to=YYYY-MM-DD&format=markdown|html
+///
+/// 期間の省略時は今日（JST）までの 7 日。前の同じ長さの期間と比べたレポートを返す。
+#[utoipa::path(
+    get,
+    path = "/reports/weekly",
+    operation_id = "get_weekly_report",
+    tag = "reports",
+    params(WeeklyReportQuery),
+    responses(
+        (status = 200, description = "`format` に応じたレポート", content(
+            (String = "text/markdown; charset=utf-8"),
+            (String = "text/html; charset=utf-8"),
+        )),
+        (status = 400, description = "期間が不正", body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    )
+)]
+pub async fn handler(
+    State(state): State<AppState>,
+    Query(query): Query<WeeklyReportQuery>,
+) -> AppResult<Response> {
+    let period = resolve_report_period(query.from, query.to, jst_today())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let report = build_weekly_report(&state.pool, period).await?;
+
+    let disposition = format!(
+        "inline; filename=\"report-{}-{}.{}\"",
+        period.from,
+        period.to,
+        query.format.extension()
+    );
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        query.format.render(&report),
+    )
+        .into_response())
+}
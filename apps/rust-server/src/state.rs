@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+
+use crate::{
+    config::AppConfig,
+    infra::{
+        embedding::{Embedder, build_embedder},
+        graphql::{InsightsSchema, build_schema},
+        live::LiveFeed,
+        llm::{LlmClient, build_llm_client},
+    },
+};
+
+/// ルータ全体で共有する状態
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub config: Arc<AppConfig>,
+    pub graphql: InsightsSchema,
+    pub live: LiveFeed,
+    pub embedder: Arc<dyn Embedder>,
+    pub llm: Arc<dyn LlmClient>,
+}
+
+impl AppState {
+    pub fn new(pool: PgPool, config: AppConfig) -> Self {
+        Self {
+            graphql: build_schema(pool.clone()),
+            live: LiveFeed::new(),
+            embedder: build_embedder(&config.embedder),
+            llm: build_llm_client(&config.llm),
+            pool,
+            config: Arc::new(config),
+        }
+    }
+}
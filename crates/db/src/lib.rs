@@ -1,3 +1,5 @@
+use sqlx::postgres::PgPoolOptions;
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -7,6 +9,30 @@ pub async fn establish_connection(url: &str) -> Result<sqlx::PgPool, sqlx::Error
     Ok(pool)
 }
 
+/// 個人情報カラムの暗号鍵をセッション設定 app.pii_key に入れた接続プールを作る
+pub async fn establish_connection_with_pii_key(
+    url: &str,
+    pii_key: Option<String>,
+) -> Result<sqlx::PgPool, sqlx::Error> {
+    let Some(pii_key) = pii_key else {
+        return establish_connection(url).await;
+    };
+
+    PgPoolOptions::new()
+        .after_connect(move |conn, _meta| {
+            let pii_key = pii_key.clone();
+            Box::pin(async move {
+                sqlx::query("SELECT set_config('app.pii_key', $1, false)")
+                    .bind(pii_key)
+                    .execute(conn)
+                    .await?;
+                Ok(())
+            })
+        })
+        .connect(url)
+        .await
+}
+
 pub async fn check_connection(pool: &sqlx::PgPool) -> Result<(), sqlx::Error> {
     sqlx::query("SELECT 1").execute(pool).await.map(|_| ())
 }
@@ -0,0 +1,116 @@
+use serde_json::Value;
+use sqlx::{PgConnection, types::Json};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BackupStorageError {
+    #[error("table is not part of backups: {0}")]
+    UnknownTable(String),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// バックアップ対象テーブル（外部キーの参照先が先に来る順。復元はこの順、削除は逆順）
+pub const BACKUP_TABLES: &[&str] = &[
+    "wantedly_companies",
+    "wantedly_company_attributes",
+    "wantedly_viewers",
+    "wantedly_profile_view_raw",
+    "wantedly_viewer_affiliations",
+    "wantedly_impressions",
+    "wantedly_snapshot_imports",
+    "erasure_suppressions",
+    "erasure_audit_log",
+    "profile_hypotheses",
+    "profile_interventions",
+    "wantedly_own_profile_versions",
+    "wantedly_view_anomalies",
+    "wantedly_view_anomaly_companies",
+    "insight_documents",
+    "eval_runs",
+];
+
+/// 1 回の INSERT に渡す行数
+const RESTORE_CHUNK_SIZE: usize = 1000;
+
+fn checked_table(table: &str) -> Result<&'static str, BackupStorageError> {
+    BACKUP_TABLES
+        .iter()
+        .copied()
+        .find(|t| *t == table)
+        .ok_or_else(|| BackupStorageError::UnknownTable(table.to_string()))
+}
+
+/// 適用済みマイグレーションのうち最新のバージョン
+pub async fn latest_applied_migration(
+    conn: &mut PgConnection,
+) -> Result<Option<i64>, BackupStorageError> {
+    let version = sqlx::query_scalar(
+        r#"
+        SELECT MAX(version)
+        FROM _sqlx_migrations
+        WHERE success
+        "#,
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(version)
+}
+
+pub async fn count_table_rows(
+    conn: &mut PgConnection,
+    table: &str,
+) -> Result<i64, BackupStorageError> {
+    let table = checked_table(table)?;
+    let count = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+        .fetch_one(conn)
+        .await?;
+
+    Ok(count)
+}
+
+/// テーブル全行を id 順の JSON テキスト（row_to_json）で返す
+pub async fn dump_table_rows(
+    conn: &mut PgConnection,
+    table: &str,
+) -> Result<Vec<String>, BackupStorageError> {
+    let table = checked_table(table)?;
+    let rows = sqlx::query_scalar(&format!(
+        "SELECT row_to_json(t)::text FROM {table} t ORDER BY t.id"
+    ))
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows)
+}
+
+/// dump_table_rows の出力を、同じスキーマのテーブルに id ごと書き戻す
+pub async fn restore_table_rows(
+    conn: &mut PgConnection,
+    table: &str,
+    rows: &[Value],
+) -> Result<u64, BackupStorageError> {
+    let table = checked_table(table)?;
+
+    let mut inserted = 0;
+    for chunk in rows.chunks(RESTORE_CHUNK_SIZE) {
+        let result = sqlx::query(&format!(
+            "INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1)"
+        ))
+        .bind(Json(chunk))
+        .execute(&mut *conn)
+        .await?;
+        inserted += result.rows_affected();
+    }
+
+    // id を明示して入れたので、シーケンスを最大値に合わせる
+    sqlx::query(&format!(
+        "SELECT setval(pg_get_serial_sequence('{table}', 'id'), COALESCE(MAX(id), 1), MAX(id) IS NOT NULL) FROM {table}"
+    ))
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(inserted)
+}
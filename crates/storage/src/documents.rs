@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use pgvector::Vector;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum InsightDocumentError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: insight_document_kind ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "insight_document_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum InsightDocumentKind {
+    /// 受け取ったスカウト
+    ScoutMessage,
+    /// 自分のメモ
+    Note,
+}
+
+/// db-shema: insight_documents
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct InsightDocument {
+    pub id: i64,
+    pub kind: InsightDocumentKind,
+    pub title: String,
+    pub body: String,
+    pub company_id: Option<i64>,
+    pub occurred_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewInsightDocument {
+    pub kind: InsightDocumentKind,
+    pub title: String,
+    pub body: String,
+    pub company_id: Option<i64>,
+    /// None なら今
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+/// 文書の埋め込みの材料と、保存済みの埋め込みの版
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InsightDocumentEmbeddingSource {
+    pub document_id: i64,
+    pub kind: InsightDocumentKind,
+    pub title: String,
+    pub body: String,
+    pub embedded_model: Option<String>,
+    pub embedded_sha256: Option<String>,
+}
+
+pub async fn insert_document(
+    executor: impl PgExecutor<'_>,
+    new: &NewInsightDocument,
+) -> Result<InsightDocument, InsightDocumentError> {
+    let row = sqlx::query_as::<_, InsightDocument>(
+        r#"
+        INSERT INTO insight_documents (kind, title, body, company_id, occurred_at)
+        VALUES ($1, $2, $3, $4, COALESCE($5, NOW()))
+        RETURNING id, kind, title, body, company_id, occurred_at, created_at
+        "#,
+    )
+    .bind(new.kind)
+    .bind(&new.title)
+    .bind(&new.body)
+    .bind(new.company_id)
+    .bind(new.occurred_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn list_document_embedding_sources(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<InsightDocumentEmbeddingSource>, InsightDocumentError> {
+    let rows = sqlx::query_as::<_, InsightDocumentEmbeddingSource>(
+        r#"
+        SELECT
+            d.id AS document_id,
+            d.kind,
+            d.title,
+            d.body,
+            e.model AS embedded_model,
+            e.content_sha256 AS embedded_sha256
+        FROM insight_documents d
+        LEFT JOIN insight_document_embeddings e ON e.document_id = d.id
+        ORDER BY d.id
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn upsert_document_embedding(
+    executor: impl PgExecutor<'_>,
+    document_id: i64,
+    model: &str,
+    content_sha256: &str,
+    embedding: &[f32],
+) -> Result<(), InsightDocumentError> {
+    sqlx::query(
+        r#"
+        INSERT INTO insight_document_embeddings (document_id, model, content_sha256, embedding)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (document_id)
+        DO UPDATE SET
+            model          = EXCLUDED.model,
+            content_sha256 = EXCLUDED.content_sha256,
+            embedding      = EXCLUDED.embedding,
+            embedded_at    = NOW()
+        "#,
+    )
+    .bind(document_id)
+    .bind(model)
+    .bind(content_sha256)
+    .bind(Vector::from(embedding.to_vec()))
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,324 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgExecutor, types::Json};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum ErasureError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: erasure_subject_kind ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "erasure_subject_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ErasureSubjectKind {
+    Viewer,
+    Company,
+}
+
+impl ErasureSubjectKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErasureSubjectKind::Viewer => "viewer",
+            ErasureSubjectKind::Company => "company",
+        }
+    }
+}
+
+/// db-shema: erasure_suppressions
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ErasureSuppression {
+    pub id: i64,
+    pub subject_kind: ErasureSubjectKind,
+    pub subject_hash: String,
+    /// ハッシュに使った鍵の指紋
+    pub key_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// db-shema: erasure_audit_log
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ErasureAuditEntry {
+    pub id: i64,
+    pub subject_kind: ErasureSubjectKind,
+    pub subject_hash: String,
+    pub key_id: String,
+    pub actor: String,
+    pub deleted_counts: Json<ErasureCounts>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// テーブルごとの削除件数
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ErasureCounts {
+    pub raw_rows: u64,
+    pub impressions: u64,
+    pub affiliations: u64,
+    pub viewers: u64,
+    pub companies: u64,
+    pub company_attributes: u64,
+}
+
+/// userId（暗号化時はブラインドインデックスで照合）に関わる行をすべて削除する
+pub async fn purge_viewer(
+    conn: &mut PgConnection,
+    source_user_id: &str,
+) -> Result<ErasureCounts, ErasureError> {
+    let raw_ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT id
+        FROM wantedly_profile_view_raw
+        WHERE viewer_user_id = pii_blind_index($1)
+        "#,
+    )
+    .bind(source_user_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let viewer_ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT id
+        FROM wantedly_viewers
+        WHERE source_user_id = pii_blind_index($1)
+        "#,
+    )
+    .bind(source_user_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    purge_raw_rows(conn, &raw_ids, &viewer_ids).await
+}
+
+/// 会社 slug に関わる行（その会社からの閲覧・属性・会社そのもの）をすべて削除する
+///
+/// その会社からの閲覧しかなかった閲覧者も削除する。
+pub async fn purge_company(
+    conn: &mut PgConnection,
+    company_slug: &str,
+) -> Result<ErasureCounts, ErasureError> {
+    let company_id: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM wantedly_companies WHERE company_slug = $1")
+            .bind(company_slug)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    // 正規化前の行も拾うため、URL の slug でも照合する
+    let raw_ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT r.id
+        FROM wantedly_profile_view_raw r
+        WHERE substring(r.viewer_company_page_url FROM '/companies/([^/?#]+)') = $1
+        UNION
+        SELECT i.raw_profile_view_id
+        FROM wantedly_impressions i
+        WHERE i.company_id_at_view = $2
+        "#,
+    )
+    .bind(company_slug)
+    .bind(company_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let viewer_ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT viewer_id
+        FROM wantedly_impressions
+        WHERE raw_profile_view_id = ANY($1)
+        UNION
+        SELECT viewer_id
+        FROM wantedly_viewer_affiliations
+        WHERE company_id = $2
+        "#,
+    )
+    .bind(&raw_ids)
+    .bind(company_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut counts = purge_raw_rows(conn, &raw_ids, &viewer_ids).await?;
+
+    if let Some(company_id) = company_id {
+        sqlx::query("UPDATE wantedly_viewers SET company_id = NULL WHERE company_id = $1")
+            .bind(company_id)
+            .execute(&mut *conn)
+            .await?;
+
+        counts.company_attributes =
+            sqlx::query("DELETE FROM wantedly_company_attributes WHERE company_id = $1")
+                .bind(company_id)
+                .execute(&mut *conn)
+                .await?
+                .rows_affected();
+
+        counts.companies = sqlx::query("DELETE FROM wantedly_companies WHERE id = $1")
+            .bind(company_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+    }
+
+    Ok(counts)
+}
+
+/// raw 行と、そこから派生したインプレッション・関係する閲覧者の所属履歴を削除する
+///
+/// raw 行が 1 件も残らなくなった閲覧者は閲覧者ごと削除する。残った閲覧者の所属履歴は
+/// 呼び出し側で正規化し直して作り直す。
+async fn purge_raw_rows(
+    conn: &mut PgConnection,
+    raw_ids: &[i64],
+    viewer_ids: &[i64],
+) -> Result<ErasureCounts, ErasureError> {
+    let impressions = sqlx::query(
+        r#"
+        DELETE FROM wantedly_impressions
+        WHERE raw_profile_view_id = ANY($1)
+        "#,
+    )
+    .bind(raw_ids)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    sqlx::query(
+        r#"
+        UPDATE wantedly_impressions
+        SET affiliation_id = NULL
+        WHERE viewer_id = ANY($1)
+        "#,
+    )
+    .bind(viewer_ids)
+    .execute(&mut *conn)
+    .await?;
+
+    let affiliations = sqlx::query(
+        r#"
+        DELETE FROM wantedly_viewer_affiliations
+        WHERE viewer_id = ANY($1)
+        "#,
+    )
+    .bind(viewer_ids)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    let raw_rows = sqlx::query(
+        r#"
+        DELETE FROM wantedly_profile_view_raw
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(raw_ids)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    let viewers = sqlx::query(
+        r#"
+        DELETE FROM wantedly_viewers v
+        WHERE v.id = ANY($1)
+          AND NOT EXISTS (SELECT 1 FROM wantedly_impressions i WHERE i.viewer_id = v.id)
+        "#,
+    )
+    .bind(viewer_ids)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    Ok(ErasureCounts {
+        raw_rows,
+        impressions,
+        affiliations,
+        viewers,
+        ..ErasureCounts::default()
+    })
+}
+
+pub async fn insert_erasure_suppression(
+    executor: impl PgExecutor<'_>,
+    subject_kind: ErasureSubjectKind,
+    subject_hash: &str,
+    key_id: &str,
+) -> Result<(), ErasureError> {
+    sqlx::query(
+        r#"
+        INSERT INTO erasure_suppressions (subject_kind, subject_hash, key_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (subject_kind, subject_hash) DO NOTHING
+        "#,
+    )
+    .bind(subject_kind)
+    .bind(subject_hash)
+    .bind(key_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// 同じ対象を旧い鍵で作った行を消す
+pub async fn delete_erasure_suppressions(
+    executor: impl PgExecutor<'_>,
+    subject_kind: ErasureSubjectKind,
+    subject_hashes: &[String],
+) -> Result<u64, ErasureError> {
+    let deleted = sqlx::query(
+        r#"
+        DELETE FROM erasure_suppressions
+        WHERE subject_kind = $1
+          AND subject_hash = ANY($2)
+        "#,
+    )
+    .bind(subject_kind)
+    .bind(subject_hashes)
+    .execute(executor)
+    .await?
+    .rows_affected();
+
+    Ok(deleted)
+}
+
+pub async fn insert_erasure_audit(
+    executor: impl PgExecutor<'_>,
+    subject_kind: ErasureSubjectKind,
+    subject_hash: &str,
+    key_id: &str,
+    actor: &str,
+    counts: &ErasureCounts,
+) -> Result<i64, ErasureError> {
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO erasure_audit_log (subject_kind, subject_hash, key_id, actor, deleted_counts)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(subject_kind)
+    .bind(subject_hash)
+    .bind(key_id)
+    .bind(actor)
+    .bind(Json(counts))
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn list_erasure_suppressions(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<ErasureSuppression>, ErasureError> {
+    let rows = sqlx::query_as::<_, ErasureSuppression>(
+        r#"
+        SELECT id, subject_kind, subject_hash, key_id, created_at
+        FROM erasure_suppressions
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum EvalRunError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: eval_suite ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "eval_suite", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EvalSuite {
+    /// 資料を引用した質問応答
+    Rag,
+    /// 会社属性の AI 推定
+    Enrichment,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewEvalRun {
+    pub suite: EvalSuite,
+    pub prompt_version: String,
+    pub model: String,
+    pub embedding_model: Option<String>,
+    pub cases_sha256: String,
+    pub case_count: i32,
+    pub retrieval_recall: Option<f64>,
+    pub score: Option<f64>,
+    pub results: serde_json::Value,
+}
+
+/// db-shema: eval_runs（ケースごとの結果を除く）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EvalRunSummary {
+    pub id: i64,
+    pub suite: EvalSuite,
+    pub prompt_version: String,
+    pub model: String,
+    pub embedding_model: Option<String>,
+    pub cases_sha256: String,
+    pub case_count: i32,
+    pub retrieval_recall: Option<f64>,
+    pub score: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub async fn insert_eval_run(
+    executor: impl PgExecutor<'_>,
+    new: &NewEvalRun,
+) -> Result<EvalRunSummary, EvalRunError> {
+    let row = sqlx::query_as::<_, EvalRunSummary>(
+        r#"
+        INSERT INTO eval_runs (
+            suite, prompt_version, model, embedding_model, cases_sha256, case_count,
+            retrieval_recall, score, results
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id, suite, prompt_version, model, embedding_model, cases_sha256, case_count,
+            retrieval_recall, score, created_at
+        "#,
+    )
+    .bind(new.suite)
+    .bind(&new.prompt_version)
+    .bind(&new.model)
+    .bind(&new.embedding_model)
+    .bind(&new.cases_sha256)
+    .bind(new.case_count)
+    .bind(new.retrieval_recall)
+    .bind(new.score)
+    .bind(&new.results)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(row)
+}
+
+/// 同じ評価ケースでの直近の実行（なければ None）
+pub async fn find_latest_eval_run(
+    executor: impl PgExecutor<'_>,
+    suite: EvalSuite,
+    cases_sha256: &str,
+) -> Result<Option<EvalRunSummary>, EvalRunError> {
+    let row = sqlx::query_as::<_, EvalRunSummary>(
+        r#"
+        SELECT id, suite, prompt_version, model, embedding_model, cases_sha256, case_count,
+            retrieval_recall, score, created_at
+        FROM eval_runs
+        WHERE suite = $1 AND cases_sha256 = $2
+        ORDER BY id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(suite)
+    .bind(cases_sha256)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(row)
+}
@@ -0,0 +1,182 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum HypothesisError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: hypothesis_metric ENUM
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema,
+)]
+#[sqlx(type_name = "hypothesis_metric", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum HypothesisMetric {
+    #[default]
+    Views,
+    DistinctViewers,
+    DistinctCompanies,
+}
+
+/// db-shema: hypothesis_expected_effect ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "hypothesis_expected_effect", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum HypothesisExpectedEffect {
+    Increase,
+    Decrease,
+    NoChange,
+}
+
+/// db-shema: profile_hypotheses
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ProfileHypothesis {
+    pub id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub metric: HypothesisMetric,
+    pub expected_effect: HypothesisExpectedEffect,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct NewProfileHypothesis {
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub metric: HypothesisMetric,
+    pub expected_effect: HypothesisExpectedEffect,
+}
+
+/// db-shema: profile_interventions
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ProfileIntervention {
+    pub id: i64,
+    pub hypothesis_id: i64,
+    pub description: String,
+    pub changed_on: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct NewProfileIntervention {
+    pub description: String,
+    pub changed_on: NaiveDate,
+}
+
+pub async fn insert_hypothesis(
+    executor: impl PgExecutor<'_>,
+    new: &NewProfileHypothesis,
+) -> Result<ProfileHypothesis, HypothesisError> {
+    let row = sqlx::query_as::<_, ProfileHypothesis>(
+        r#"
+        INSERT INTO profile_hypotheses (title, description, metric, expected_effect)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, title, description, metric, expected_effect, created_at
+        "#,
+    )
+    .bind(&new.title)
+    .bind(&new.description)
+    .bind(new.metric)
+    .bind(new.expected_effect)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn list_hypotheses(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<ProfileHypothesis>, HypothesisError> {
+    let rows = sqlx::query_as::<_, ProfileHypothesis>(
+        r#"
+        SELECT id, title, description, metric, expected_effect, created_at
+        FROM profile_hypotheses
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn find_hypothesis_by_id(
+    executor: impl PgExecutor<'_>,
+    id: i64,
+) -> Result<Option<ProfileHypothesis>, HypothesisError> {
+    let row = sqlx::query_as::<_, ProfileHypothesis>(
+        r#"
+        SELECT id, title, description, metric, expected_effect, created_at
+        FROM profile_hypotheses
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn insert_intervention(
+    executor: impl PgExecutor<'_>,
+    hypothesis_id: i64,
+    new: &NewProfileIntervention,
+) -> Result<ProfileIntervention, HypothesisError> {
+    let row = sqlx::query_as::<_, ProfileIntervention>(
+        r#"
+        INSERT INTO profile_interventions (hypothesis_id, description, changed_on)
+        VALUES ($1, $2, $3)
+        RETURNING id, hypothesis_id, description, changed_on, created_at
+        "#,
+    )
+    .bind(hypothesis_id)
+    .bind(&new.description)
+    .bind(new.changed_on)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(row)
+}
+
+/// 全仮説の介入（hypothesis_id、変更日の順）
+pub async fn list_interventions(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<ProfileIntervention>, HypothesisError> {
+    let rows = sqlx::query_as::<_, ProfileIntervention>(
+        r#"
+        SELECT id, hypothesis_id, description, changed_on, created_at
+        FROM profile_interventions
+        ORDER BY hypothesis_id, changed_on, id
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn list_interventions_for_hypothesis(
+    executor: impl PgExecutor<'_>,
+    hypothesis_id: i64,
+) -> Result<Vec<ProfileIntervention>, HypothesisError> {
+    let rows = sqlx::query_as::<_, ProfileIntervention>(
+        r#"
+        SELECT id, hypothesis_id, description, changed_on, created_at
+        FROM profile_interventions
+        WHERE hypothesis_id = $1
+        ORDER BY changed_on, id
+        "#,
+    )
+    .bind(hypothesis_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -1,2 +1,9 @@
+pub mod backup;
+pub mod documents;
+pub mod erasure;
+pub mod evaluations;
+pub mod hypotheses;
+pub mod pii;
 pub mod prelude;
+pub mod retrieval;
 pub mod wantedly;
@@ -0,0 +1,130 @@
+use sqlx::{PgConnection, PgExecutor};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PiiKeyError {
+    #[error("rows are encrypted with an unknown key (key id {0}); set PII_ENCRYPTION_KEY_PREVIOUS")]
+    UnknownKey(String),
+
+    #[error("session key (app.pii_key) does not match the rotation target key")]
+    SessionKeyMismatch,
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PiiRotationSummary {
+    pub raw_rows: u64,
+    pub viewers: u64,
+}
+
+/// セッションの鍵（未設定なら平文）と異なる状態で保存されている raw 行の数
+pub async fn count_raw_rows_outside_session_key(
+    executor: impl PgExecutor<'_>,
+) -> Result<i64, PiiKeyError> {
+    let count = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM wantedly_profile_view_raw
+        WHERE pii_key_id IS DISTINCT FROM pii_key_id()
+        "#,
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(count)
+}
+
+/// raw の個人情報カラムを new_key で暗号化し直す（平文の行は暗号化する）
+///
+/// ブラインドインデックスも作り直すため、wantedly_viewers.source_user_id も追従させる。
+/// 所属履歴は正規化のたびに現在の鍵で作り直されるので、ローテーション後に正規化し直すこと。
+pub async fn rotate_raw_pii_key(
+    conn: &mut PgConnection,
+    old_key: Option<&str>,
+    new_key: &str,
+) -> Result<PiiRotationSummary, PiiKeyError> {
+    let session_matches: Option<bool> =
+        sqlx::query_scalar("SELECT pii_key_id() = pii_key_fingerprint($1)")
+            .bind(new_key)
+            .fetch_one(&mut *conn)
+            .await?;
+    if session_matches != Some(true) {
+        return Err(PiiKeyError::SessionKeyMismatch);
+    }
+
+    let unknown: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT pii_key_id
+        FROM wantedly_profile_view_raw
+        WHERE pii_key_id IS NOT NULL
+          AND pii_key_id <> pii_key_fingerprint($2)
+          AND pii_key_id IS DISTINCT FROM pii_key_fingerprint($1)
+        LIMIT 1
+        "#,
+    )
+    .bind(old_key)
+    .bind(new_key)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(key_id) = unknown {
+        return Err(PiiKeyError::UnknownKey(key_id));
+    }
+
+    let remapped: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        WITH src AS (
+            SELECT
+                id,
+                viewer_user_id AS old_index,
+                CASE WHEN pii_key_id IS NULL THEN viewer_user_id
+                     ELSE pii_decrypt_with(viewer_user_id_enc, $1) END AS user_id,
+                CASE WHEN pii_key_id IS NULL THEN viewer_company_name_raw
+                     ELSE pii_decrypt_with(viewer_company_name_raw_enc, $1) END AS company_name_raw,
+                CASE WHEN pii_key_id IS NULL THEN raw_json::text
+                     ELSE pii_decrypt_with(raw_json_enc, $1) END AS raw_json
+            FROM wantedly_profile_view_raw
+            WHERE pii_key_id IS DISTINCT FROM pii_key_fingerprint($2)
+        )
+        UPDATE wantedly_profile_view_raw r
+        SET
+            viewer_user_id              = pii_blind_index_with(src.user_id, $2),
+            viewer_user_id_enc          = pii_encrypt_with(src.user_id, $2),
+            viewer_company_name_raw     = NULL,
+            viewer_company_name_raw_enc = pii_encrypt_with(src.company_name_raw, $2),
+            raw_json                    = NULL,
+            raw_json_enc                = pii_encrypt_with(src.raw_json, $2),
+            pii_key_id                  = pii_key_fingerprint($2)
+        FROM src
+        WHERE r.id = src.id
+        RETURNING src.old_index, r.viewer_user_id
+        "#,
+    )
+    .bind(old_key)
+    .bind(new_key)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let (old_indexes, new_indexes): (Vec<String>, Vec<String>) = remapped.iter().cloned().unzip();
+    let viewers = sqlx::query(
+        r#"
+        UPDATE wantedly_viewers v
+        SET source_user_id = m.new_index
+        FROM (
+            SELECT DISTINCT old_index, new_index
+            FROM UNNEST($1::text[], $2::text[]) AS t(old_index, new_index)
+        ) m
+        WHERE v.source_user_id = m.old_index
+        "#,
+    )
+    .bind(&old_indexes)
+    .bind(&new_indexes)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(PiiRotationSummary {
+        raw_rows: remapped.len() as u64,
+        viewers: viewers.rows_affected(),
+    })
+}
@@ -1,4 +1,6 @@
 pub use crate::wantedly::WantedlyCompany;
 pub use crate::wantedly::WantedlyImpression;
 pub use crate::wantedly::WantedlyProfileViewRaw;
+pub use crate::wantedly::WantedlySnapshotImport;
 pub use crate::wantedly::WantedlyViewer;
+pub use crate::wantedly::WantedlyViewerAffiliation;
@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use pgvector::Vector;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+
+use crate::documents::InsightDocumentKind;
+use crate::wantedly::{CompanyEmployeeBand, CompanyFundingStage};
+
+#[derive(Debug, Error)]
+pub enum RetrievalError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RetrievedCompany {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub name: Option<String>,
+    pub domain: Option<String>,
+    pub industry: Option<String>,
+    pub employee_band: Option<CompanyEmployeeBand>,
+    pub funding_stage: Option<CompanyFundingStage>,
+    pub location: Option<String>,
+    pub views: i64,
+    pub distance: f64,
+}
+
+/// 閲覧者の自己紹介（本文は所属履歴から復号して引く）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RetrievedViewerDescription {
+    pub viewer_id: i64,
+    pub short_description: String,
+    /// その自己紹介のときの所属会社
+    pub company_slug: Option<String>,
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RetrievedDocument {
+    pub document_id: i64,
+    pub kind: InsightDocumentKind,
+    pub title: String,
+    pub body: String,
+    pub company_slug: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub distance: f64,
+}
+
+/// 会社の埋め込みの近傍 k 件
+///
+/// RAG の検索はどれも HNSW インデックスで近い順に引く。同じトランザクションで先に
+/// `widen_hnsw_search` を呼び、探索幅を k まで広げておく。
+pub async fn search_companies(
+    conn: &mut PgConnection,
+    embedding: &[f32],
+    model: &str,
+    k: i64,
+) -> Result<Vec<RetrievedCompany>, RetrievalError> {
+    let rows = sqlx::query_as::<_, RetrievedCompany>(
+        r#"
+        SELECT
+            n.company_id,
+            c.company_slug,
+            a.name,
+            a.domain,
+            a.industry,
+            a.employee_band,
+            a.funding_stage,
+            a.location,
+            (
+                SELECT COUNT(*)
+                FROM wantedly_impressions i
+                WHERE i.company_id_at_view = n.company_id
+            ) AS views,
+            n.distance
+        FROM (
+            SELECT e.company_id, e.embedding <=> $1 AS distance
+            FROM wantedly_company_embeddings e
+            WHERE e.model = $2
+            ORDER BY e.embedding <=> $1
+            LIMIT $3
+        ) n
+        JOIN wantedly_companies c ON c.id = n.company_id
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = n.company_id
+        ORDER BY n.distance, n.company_id
+        "#,
+    )
+    .bind(Vector::from(embedding.to_vec()))
+    .bind(model)
+    .bind(k)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn search_viewer_descriptions(
+    conn: &mut PgConnection,
+    embedding: &[f32],
+    model: &str,
+    k: i64,
+) -> Result<Vec<RetrievedViewerDescription>, RetrievalError> {
+    // 埋め込みには本文がないので、pii_digest が一致する所属履歴から引く
+    let rows = sqlx::query_as::<_, RetrievedViewerDescription>(
+        r#"
+        SELECT n.viewer_id, d.short_description, d.company_slug, n.distance
+        FROM (
+            SELECT e.viewer_id, e.content_digest, e.embedding <=> $1 AS distance
+            FROM wantedly_viewer_description_embeddings e
+            WHERE e.model = $2
+            ORDER BY e.embedding <=> $1
+            LIMIT $3
+        ) n
+        CROSS JOIN LATERAL (
+            SELECT t.short_description, c.company_slug
+            FROM (
+                SELECT
+                    a.company_id,
+                    a.last_seen_at,
+                    btrim(COALESCE(a.short_description, pii_decrypt(a.short_description_enc)))
+                        AS short_description
+                FROM wantedly_viewer_affiliations a
+                WHERE a.viewer_id = n.viewer_id
+            ) t
+            LEFT JOIN wantedly_companies c ON c.id = t.company_id
+            WHERE pii_digest(t.short_description) = n.content_digest
+            ORDER BY t.last_seen_at DESC
+            LIMIT 1
+        ) d
+        ORDER BY n.distance, n.viewer_id
+        "#,
+    )
+    .bind(Vector::from(embedding.to_vec()))
+    .bind(model)
+    .bind(k)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn search_documents(
+    conn: &mut PgConnection,
+    embedding: &[f32],
+    model: &str,
+    k: i64,
+) -> Result<Vec<RetrievedDocument>, RetrievalError> {
+    let rows = sqlx::query_as::<_, RetrievedDocument>(
+        r#"
+        SELECT
+            n.document_id,
+            d.kind,
+            d.title,
+            d.body,
+            c.company_slug,
+            d.occurred_at,
+            n.distance
+        FROM (
+            SELECT e.document_id, e.embedding <=> $1 AS distance
+            FROM insight_document_embeddings e
+            WHERE e.model = $2
+            ORDER BY e.embedding <=> $1
+            LIMIT $3
+        ) n
+        JOIN insight_documents d ON d.id = n.document_id
+        LEFT JOIN wantedly_companies c ON c.id = d.company_id
+        ORDER BY n.distance, n.document_id
+        "#,
+    )
+    .bind(Vector::from(embedding.to_vec()))
+    .bind(model)
+    .bind(k)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows)
+}
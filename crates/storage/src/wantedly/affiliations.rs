@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyViewerAffiliationError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: viewer_affiliation_kind ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "viewer_affiliation_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ViewerAffiliationKind {
+    Company,
+    Freeform,
+    Unknown,
+}
+
+/// db-shema: wantedly_viewer_affiliations
+///
+/// 暗号化時の short_description は short_description_enc 側にあり、このカラムは NULL
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyViewerAffiliation {
+    pub id: i64,
+    pub viewer_id: i64,
+    pub kind: ViewerAffiliationKind,
+    pub company_id: Option<i64>,
+    pub short_description: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWantedlyViewerAffiliation {
+    pub viewer_id: i64,
+    pub kind: ViewerAffiliationKind,
+    pub company_id: Option<i64>,
+    pub short_description: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// 所属履歴の 1 区間と、その会社情報・区間内の閲覧数
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyViewerAffiliationSummary {
+    pub id: i64,
+    pub kind: ViewerAffiliationKind,
+    pub company_id: Option<i64>,
+    pub company_slug: Option<String>,
+    pub company_page_url: Option<String>,
+    pub short_description: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub impression_count: i64,
+}
+
+pub async fn insert_viewer_affiliation(
+    executor: impl PgExecutor<'_>,
+    new: &NewWantedlyViewerAffiliation,
+) -> Result<i64, WantedlyViewerAffiliationError> {
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO wantedly_viewer_affiliations (
+            viewer_id,
+            kind,
+            company_id,
+            short_description,
+            short_description_enc,
+            first_seen_at,
+            last_seen_at
+        )
+        VALUES ($1, $2, $3, pii_plaintext($4), pii_encrypt($4), $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(new.viewer_id)
+    .bind(new.kind)
+    .bind(new.company_id)
+    .bind(&new.short_description)
+    .bind(new.first_seen_at)
+    .bind(new.last_seen_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+/// 閲覧ユーザーの所属履歴を作り直す前に、インプレッションからの参照ごと削除する
+pub async fn delete_viewer_affiliations(
+    conn: &mut sqlx::PgConnection,
+    viewer_id: i64,
+) -> Result<u64, WantedlyViewerAffiliationError> {
+    sqlx::query(
+        r#"
+        UPDATE wantedly_impressions
+        SET affiliation_id = NULL
+        WHERE viewer_id = $1
+        "#,
+    )
+    .bind(viewer_id)
+    .execute(&mut *conn)
+    .await?;
+
+    let deleted = sqlx::query(
+        r#"
+        DELETE FROM wantedly_viewer_affiliations
+        WHERE viewer_id = $1
+        "#,
+    )
+    .bind(viewer_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(deleted.rows_affected())
+}
+
+/// 所属履歴を古い順に返す（閲覧数は各区間に帰属したインプレッション数）
+pub async fn list_viewer_affiliation_timeline(
+    executor: impl PgExecutor<'_>,
+    viewer_id: i64,
+) -> Result<Vec<WantedlyViewerAffiliationSummary>, WantedlyViewerAffiliationError> {
+    let rows = sqlx::query_as::<_, WantedlyViewerAffiliationSummary>(
+        r#"
+        SELECT
+            a.id,
+            a.kind,
+            a.company_id,
+            c.company_slug,
+            c.company_page_url,
+            COALESCE(a.short_description, pii_decrypt(a.short_description_enc))
+                AS short_description,
+            a.first_seen_at,
+            a.last_seen_at,
+            COUNT(i.id) AS impression_count
+        FROM wantedly_viewer_affiliations a
+        LEFT JOIN wantedly_companies c ON c.id = a.company_id
+        LEFT JOIN wantedly_impressions i ON i.affiliation_id = a.id
+        WHERE a.viewer_id = $1
+        GROUP BY a.id, c.id
+        ORDER BY a.first_seen_at, a.id
+        "#,
+    )
+    .bind(viewer_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyViewAnomalyError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: wantedly_anomaly_kind ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "wantedly_anomaly_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WantedlyAnomalyKind {
+    Spike,
+    Drop,
+}
+
+/// db-shema: wantedly_view_anomalies
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyViewAnomaly {
+    pub id: i64,
+    pub day: NaiveDate,
+    pub kind: WantedlyAnomalyKind,
+    pub views: i64,
+    pub baseline_median: f64,
+    pub baseline_scale: f64,
+    pub score: f64,
+    pub window_days: i32,
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewWantedlyViewAnomaly {
+    pub day: NaiveDate,
+    pub kind: WantedlyAnomalyKind,
+    pub views: i64,
+    pub baseline_median: f64,
+    pub baseline_scale: f64,
+    pub score: f64,
+    pub window_days: i32,
+}
+
+/// 異常のあった日に多く閲覧した会社
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyViewAnomalyCompany {
+    #[serde(skip)]
+    pub anomaly_id: i64,
+    pub rank: i32,
+    pub company_id: i64,
+    pub company_slug: String,
+    pub name: Option<String>,
+    pub views: i64,
+}
+
+/// from〜to（JST の暦日）の異常を anomalies で置き換え、各日の上位 top_companies 社を記録する
+pub async fn replace_view_anomalies(
+    conn: &mut PgConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+    anomalies: &[NewWantedlyViewAnomaly],
+    top_companies: i64,
+) -> Result<u64, WantedlyViewAnomalyError> {
+    // 会社の行は ON DELETE CASCADE で消える
+    sqlx::query("DELETE FROM wantedly_view_anomalies WHERE day BETWEEN $1 AND $2")
+        .bind(from)
+        .bind(to)
+        .execute(&mut *conn)
+        .await?;
+
+    for anomaly in anomalies {
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO wantedly_view_anomalies (
+                day, kind, views, baseline_median, baseline_scale, score, window_days
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(anomaly.day)
+        .bind(anomaly.kind)
+        .bind(anomaly.views)
+        .bind(anomaly.baseline_median)
+        .bind(anomaly.baseline_scale)
+        .bind(anomaly.score)
+        .bind(anomaly.window_days)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO wantedly_view_anomaly_companies (anomaly_id, company_id, views, rank)
+            SELECT $1, t.company_id, t.views, ROW_NUMBER() OVER (ORDER BY t.views DESC, t.company_id)
+            FROM (
+                SELECT i.company_id_at_view AS company_id, COUNT(*) AS views
+                FROM wantedly_impressions i
+                WHERE i.company_id_at_view IS NOT NULL
+                  AND i.impressed_at >= ($2::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+                  AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+                GROUP BY i.company_id_at_view
+                ORDER BY views DESC, company_id
+                LIMIT $3
+            ) t
+            "#,
+        )
+        .bind(id)
+        .bind(anomaly.day)
+        .bind(top_companies)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(anomalies.len() as u64)
+}
+
+/// from〜to の異常を日付順に（kind 指定時はその種類だけ）
+pub async fn list_view_anomalies(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    kind: Option<WantedlyAnomalyKind>,
+) -> Result<Vec<WantedlyViewAnomaly>, WantedlyViewAnomalyError> {
+    let rows = sqlx::query_as::<_, WantedlyViewAnomaly>(
+        r#"
+        SELECT id, day, kind, views, baseline_median, baseline_scale, score, window_days, detected_at
+        FROM wantedly_view_anomalies
+        WHERE day BETWEEN $1 AND $2
+          AND ($3::wantedly_anomaly_kind IS NULL OR kind = $3)
+        ORDER BY day
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .bind(kind)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn list_view_anomaly_companies(
+    executor: impl PgExecutor<'_>,
+    anomaly_ids: &[i64],
+) -> Result<Vec<WantedlyViewAnomalyCompany>, WantedlyViewAnomalyError> {
+    let rows = sqlx::query_as::<_, WantedlyViewAnomalyCompany>(
+        r#"
+        SELECT ac.anomaly_id, ac.rank, ac.company_id, c.company_slug, a.name, ac.views
+        FROM wantedly_view_anomaly_companies ac
+        JOIN wantedly_companies c ON c.id = ac.company_id
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = c.id
+        WHERE ac.anomaly_id = ANY($1)
+        ORDER BY ac.anomaly_id, ac.rank
+        "#,
+    )
+    .bind(anomaly_ids)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
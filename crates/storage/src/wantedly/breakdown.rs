@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::wantedly::WantedlyTimeBucket;
+
+#[derive(Debug, Error)]
+pub enum WantedlyBreakdownError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 閲覧を分ける会社属性（閲覧時点の所属会社の wantedly_company_attributes）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WantedlyCompanyDimension {
+    #[default]
+    Industry,
+    EmployeeBand,
+    FundingStage,
+    Location,
+}
+
+impl WantedlyCompanyDimension {
+    fn column(self) -> &'static str {
+        match self {
+            WantedlyCompanyDimension::Industry => "a.industry",
+            WantedlyCompanyDimension::EmployeeBand => "a.employee_band::text",
+            WantedlyCompanyDimension::FundingStage => "a.funding_stage::text",
+            WantedlyCompanyDimension::Location => "a.location",
+        }
+    }
+}
+
+/// 属性の値ごとの期間全体の集計（value が NULL は属性不明、または会社不明）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyBreakdownTotalRow {
+    pub value: Option<String>,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub distinct_companies: i64,
+}
+
+/// 属性の値ごと・区間ごとの集計（閲覧のない組み合わせは返さない）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyBreakdownBucketRow {
+    pub bucket_start: NaiveDate,
+    pub value: Option<String>,
+    pub views: i64,
+    pub distinct_viewers: i64,
+}
+
+pub async fn list_view_breakdown_totals(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    dimension: WantedlyCompanyDimension,
+) -> Result<Vec<WantedlyBreakdownTotalRow>, WantedlyBreakdownError> {
+    let rows = sqlx::query_as::<_, WantedlyBreakdownTotalRow>(&format!(
+        r#"
+        SELECT
+            {value} AS value,
+            COUNT(*) AS views,
+            COUNT(DISTINCT i.viewer_id) AS distinct_viewers,
+            COUNT(DISTINCT i.company_id_at_view) AS distinct_companies
+        FROM wantedly_impressions i
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = i.company_id_at_view
+        WHERE i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+          AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        GROUP BY 1
+        ORDER BY views DESC, value NULLS LAST
+        "#,
+        value = dimension.column(),
+    ))
+    .bind(from)
+    .bind(to)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// bucket_start は list_view_time_series と同じく、期間の始まりで切り詰めた区間の始まり
+pub async fn list_view_breakdown_by_bucket(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    bucket: WantedlyTimeBucket,
+    dimension: WantedlyCompanyDimension,
+) -> Result<Vec<WantedlyBreakdownBucketRow>, WantedlyBreakdownError> {
+    let rows = sqlx::query_as::<_, WantedlyBreakdownBucketRow>(&format!(
+        r#"
+        SELECT
+            GREATEST(
+                date_trunc($3, i.impressed_at AT TIME ZONE 'Asia/Tokyo')::date,
+                $1::date
+            ) AS bucket_start,
+            {value} AS value,
+            COUNT(*) AS views,
+            COUNT(DISTINCT i.viewer_id) AS distinct_viewers
+        FROM wantedly_impressions i
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = i.company_id_at_view
+        WHERE i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+          AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        GROUP BY 1, 2
+        ORDER BY 1, views DESC
+        "#,
+        value = dimension.column(),
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(bucket.as_str())
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -1,6 +1,14 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyCompanyError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
 
 /// db-shema: wantedly_companies
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -12,14 +20,58 @@ pub struct WantedlyCompany {
 }
 
 /// db-shema: company_attribute_source ENUM
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "company_attribute_source", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum CompanyAttributeSource {
     Ai,
+    /// 手入力。AI による推定で上書きしない
+    Manual,
+}
+
+/// db-shema: company_employee_band ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "company_employee_band")]
+pub enum CompanyEmployeeBand {
+    #[sqlx(rename = "1-10")]
+    #[serde(rename = "1-10")]
+    E1To10,
+    #[sqlx(rename = "11-50")]
+    #[serde(rename = "11-50")]
+    E11To50,
+    #[sqlx(rename = "51-200")]
+    #[serde(rename = "51-200")]
+    E51To200,
+    #[sqlx(rename = "201-500")]
+    #[serde(rename = "201-500")]
+    E201To500,
+    #[sqlx(rename = "501-1000")]
+    #[serde(rename = "501-1000")]
+    E501To1000,
+    #[sqlx(rename = "1001-5000")]
+    #[serde(rename = "1001-5000")]
+    E1001To5000,
+    #[sqlx(rename = "5001+")]
+    #[serde(rename = "5001+")]
+    E5001Plus,
+}
+
+/// db-shema: company_funding_stage ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "company_funding_stage", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CompanyFundingStage {
+    Bootstrapped,
+    Seed,
+    SeriesA,
+    SeriesB,
+    SeriesCPlus,
+    Public,
+    Acquired,
 }
 
 /// db-shema: wantedly_company_attributes
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct WantedlyCompanyAttributes {
     pub id: i64,
     pub company_id: i64,
@@ -28,4 +80,213 @@ pub struct WantedlyCompanyAttributes {
     pub source: CompanyAttributeSource,
     pub confidence: Option<f32>, // NUMERIC(3,2) → とりあえず f32
     pub updated_at: DateTime<Utc>,
+    pub industry: Option<String>,
+    pub employee_band: Option<CompanyEmployeeBand>,
+    pub funding_stage: Option<CompanyFundingStage>,
+    pub location: Option<String>,
+    /// AI による推定のプロンプトの版（手入力なら null）
+    pub prompt_version: Option<String>,
+    /// AI による推定に使ったモデル（手入力なら null）
+    pub model: Option<String>,
+}
+
+/// 会社属性の書き込み内容（None はその項目を空にする）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WantedlyCompanyAttributesInput {
+    pub name: Option<String>,
+    pub domain: Option<String>,
+    pub industry: Option<String>,
+    pub employee_band: Option<CompanyEmployeeBand>,
+    pub funding_stage: Option<CompanyFundingStage>,
+    pub location: Option<String>,
+}
+
+/// 会社属性をどう得たか
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompanyAttributeProvenance {
+    pub source: CompanyAttributeSource,
+    /// 推定の確からしさ 0.00 〜 1.00（手入力なら None）
+    pub confidence: Option<f32>,
+    pub prompt_version: Option<String>,
+    pub model: Option<String>,
+}
+
+impl CompanyAttributeProvenance {
+    pub fn manual() -> Self {
+        Self {
+            source: CompanyAttributeSource::Manual,
+            confidence: None,
+            prompt_version: None,
+            model: None,
+        }
+    }
+}
+
+pub async fn upsert_company(
+    executor: impl PgExecutor<'_>,
+    company_page_url: &str,
+    company_slug: &str,
+) -> Result<i64, WantedlyCompanyError> {
+    // slug を会社の識別キーとし、URL は最初に観測したものを残す
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO wantedly_companies (company_page_url, company_slug)
+        VALUES ($1, $2)
+        ON CONFLICT (company_slug)
+        DO UPDATE SET company_slug = EXCLUDED.company_slug
+        RETURNING id
+        "#,
+    )
+    .bind(company_page_url)
+    .bind(company_slug)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn find_company_by_id(
+    executor: impl PgExecutor<'_>,
+    id: i64,
+) -> Result<Option<WantedlyCompany>, WantedlyCompanyError> {
+    let company = sqlx::query_as::<_, WantedlyCompany>(
+        r#"
+        SELECT id, company_page_url, company_slug, created_at
+        FROM wantedly_companies
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(company)
+}
+
+pub async fn find_company_by_slug(
+    executor: impl PgExecutor<'_>,
+    company_slug: &str,
+) -> Result<Option<WantedlyCompany>, WantedlyCompanyError> {
+    let company = sqlx::query_as::<_, WantedlyCompany>(
+        r#"
+        SELECT id, company_page_url, company_slug, created_at
+        FROM wantedly_companies
+        WHERE company_slug = $1
+        "#,
+    )
+    .bind(company_slug)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(company)
+}
+
+/// 会社属性を書き込む。手入力（manual）の行は AI による推定では上書きしない（その場合は None）
+pub async fn upsert_company_attributes(
+    executor: impl PgExecutor<'_>,
+    company_id: i64,
+    input: &WantedlyCompanyAttributesInput,
+    provenance: &CompanyAttributeProvenance,
+) -> Result<Option<WantedlyCompanyAttributes>, WantedlyCompanyError> {
+    let row = sqlx::query_as::<_, WantedlyCompanyAttributes>(
+        r#"
+        INSERT INTO wantedly_company_attributes (
+            company_id, name, domain, industry, employee_band, funding_stage, location,
+            source, confidence, prompt_version, model
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9::numeric(3,2), $10, $11)
+        ON CONFLICT (company_id) DO UPDATE SET
+            name = EXCLUDED.name,
+            domain = EXCLUDED.domain,
+            industry = EXCLUDED.industry,
+            employee_band = EXCLUDED.employee_band,
+            funding_stage = EXCLUDED.funding_stage,
+            location = EXCLUDED.location,
+            source = EXCLUDED.source,
+            confidence = EXCLUDED.confidence,
+            prompt_version = EXCLUDED.prompt_version,
+            model = EXCLUDED.model,
+            updated_at = NOW()
+        WHERE wantedly_company_attributes.source <> 'manual' OR EXCLUDED.source = 'manual'
+        RETURNING id, company_id, name, domain, source, confidence::float4 AS confidence,
+            updated_at, industry, employee_band, funding_stage, location, prompt_version, model
+        "#,
+    )
+    .bind(company_id)
+    .bind(&input.name)
+    .bind(&input.domain)
+    .bind(&input.industry)
+    .bind(input.employee_band)
+    .bind(input.funding_stage)
+    .bind(&input.location)
+    .bind(provenance.source)
+    .bind(provenance.confidence)
+    .bind(&provenance.prompt_version)
+    .bind(&provenance.model)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(row)
+}
+
+/// id をまとめて引く（見つからない id は結果に含まれない）
+pub async fn list_companies_by_ids(
+    executor: impl PgExecutor<'_>,
+    ids: &[i64],
+) -> Result<Vec<WantedlyCompany>, WantedlyCompanyError> {
+    let rows = sqlx::query_as::<_, WantedlyCompany>(
+        r#"
+        SELECT id, company_page_url, company_slug, created_at
+        FROM wantedly_companies
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(ids)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// slug 順に limit 件（after_slug を渡すとその次から）
+pub async fn list_companies(
+    executor: impl PgExecutor<'_>,
+    after_slug: Option<&str>,
+    limit: i64,
+) -> Result<Vec<WantedlyCompany>, WantedlyCompanyError> {
+    let rows = sqlx::query_as::<_, WantedlyCompany>(
+        r#"
+        SELECT id, company_page_url, company_slug, created_at
+        FROM wantedly_companies
+        WHERE $1::text IS NULL OR company_slug > $1
+        ORDER BY company_slug
+        LIMIT $2
+        "#,
+    )
+    .bind(after_slug)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 会社属性を会社 id でまとめて引く（属性のない会社は結果に含まれない）
+pub async fn list_company_attributes_by_company_ids(
+    executor: impl PgExecutor<'_>,
+    company_ids: &[i64],
+) -> Result<Vec<WantedlyCompanyAttributes>, WantedlyCompanyError> {
+    let rows = sqlx::query_as::<_, WantedlyCompanyAttributes>(
+        r#"
+        SELECT id, company_id, name, domain, source, confidence::float4 AS confidence,
+            updated_at, industry, employee_band, funding_stage, location, prompt_version, model
+        FROM wantedly_company_attributes
+        WHERE company_id = ANY($1)
+        "#,
+    )
+    .bind(company_ids)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
 }
@@ -0,0 +1,300 @@
+use chrono::{DateTime, Utc};
+use pgvector::Vector;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::{CompanyEmployeeBand, CompanyFundingStage};
+
+/// 埋め込みの次元（マイグレーションの vector(1536) と合わせる）
+pub const EMBEDDING_DIMENSIONS: usize = 1536;
+
+#[derive(Debug, Error)]
+pub enum WantedlyEmbeddingError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 会社の埋め込み文書の材料と、保存済みの埋め込みの版
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyCompanyEmbeddingSource {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub name: Option<String>,
+    pub domain: Option<String>,
+    pub industry: Option<String>,
+    pub employee_band: Option<CompanyEmployeeBand>,
+    pub funding_stage: Option<CompanyFundingStage>,
+    pub location: Option<String>,
+    pub embedded_model: Option<String>,
+    pub embedded_sha256: Option<String>,
+}
+
+/// 閲覧者ごとの自己紹介（所属履歴に現れたもの、復号済み）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyViewerDescription {
+    pub viewer_id: i64,
+    pub short_description: String,
+    /// pii_digest(short_description)（PII の鍵があれば鍵付き HMAC）
+    pub content_digest: String,
+}
+
+/// db-shema: wantedly_viewer_description_embeddings（ベクトル以外）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyViewerDescriptionEmbeddingKey {
+    pub id: i64,
+    pub viewer_id: i64,
+    pub content_digest: String,
+    pub model: String,
+    pub embedded_at: DateTime<Utc>,
+}
+
+/// 類似企業の検索結果（distance はコサイン距離。0 に近いほど似ている）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlySimilarCompany {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub name: Option<String>,
+    pub industry: Option<String>,
+    pub distance: f64,
+    /// この会社に所属する人がプロフィールを閲覧したことがあるか
+    pub has_viewed: bool,
+    pub views: i64,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+}
+
+pub async fn list_company_embedding_sources(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<WantedlyCompanyEmbeddingSource>, WantedlyEmbeddingError> {
+    let rows = sqlx::query_as::<_, WantedlyCompanyEmbeddingSource>(
+        r#"
+        SELECT
+            c.id AS company_id,
+            c.company_slug,
+            a.name,
+            a.domain,
+            a.industry,
+            a.employee_band,
+            a.funding_stage,
+            a.location,
+            e.model AS embedded_model,
+            e.content_sha256 AS embedded_sha256
+        FROM wantedly_companies c
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = c.id
+        LEFT JOIN wantedly_company_embeddings e ON e.company_id = c.id
+        ORDER BY c.id
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn upsert_company_embedding(
+    executor: impl PgExecutor<'_>,
+    company_id: i64,
+    model: &str,
+    content_sha256: &str,
+    embedding: &[f32],
+) -> Result<(), WantedlyEmbeddingError> {
+    sqlx::query(
+        r#"
+        INSERT INTO wantedly_company_embeddings (company_id, model, content_sha256, embedding)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (company_id)
+        DO UPDATE SET
+            model          = EXCLUDED.model,
+            content_sha256 = EXCLUDED.content_sha256,
+            embedding      = EXCLUDED.embedding,
+            embedded_at    = NOW()
+        "#,
+    )
+    .bind(company_id)
+    .bind(model)
+    .bind(content_sha256)
+    .bind(Vector::from(embedding.to_vec()))
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// 所属履歴に現れた（閲覧者, 自己紹介）の組（前後の空白を除き、空のものは除く）
+pub async fn list_viewer_descriptions(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<WantedlyViewerDescription>, WantedlyEmbeddingError> {
+    let rows = sqlx::query_as::<_, WantedlyViewerDescription>(
+        r#"
+        SELECT DISTINCT viewer_id, short_description, pii_digest(short_description) AS content_digest
+        FROM (
+            SELECT
+                a.viewer_id,
+                btrim(COALESCE(a.short_description, pii_decrypt(a.short_description_enc)))
+                    AS short_description
+            FROM wantedly_viewer_affiliations a
+        ) d
+        WHERE d.short_description <> ''
+        ORDER BY viewer_id, short_description
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn list_viewer_description_embedding_keys(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<WantedlyViewerDescriptionEmbeddingKey>, WantedlyEmbeddingError> {
+    let rows = sqlx::query_as::<_, WantedlyViewerDescriptionEmbeddingKey>(
+        r#"
+        SELECT id, viewer_id, content_digest, model, embedded_at
+        FROM wantedly_viewer_description_embeddings
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn upsert_viewer_description_embedding(
+    executor: impl PgExecutor<'_>,
+    viewer_id: i64,
+    content_digest: &str,
+    model: &str,
+    embedding: &[f32],
+) -> Result<(), WantedlyEmbeddingError> {
+    sqlx::query(
+        r#"
+        INSERT INTO wantedly_viewer_description_embeddings (
+            viewer_id, content_digest, model, embedding
+        )
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (viewer_id, content_digest)
+        DO UPDATE SET
+            model       = EXCLUDED.model,
+            embedding   = EXCLUDED.embedding,
+            embedded_at = NOW()
+        "#,
+    )
+    .bind(viewer_id)
+    .bind(content_digest)
+    .bind(model)
+    .bind(Vector::from(embedding.to_vec()))
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_viewer_description_embeddings(
+    executor: impl PgExecutor<'_>,
+    ids: &[i64],
+) -> Result<u64, WantedlyEmbeddingError> {
+    let deleted =
+        sqlx::query("DELETE FROM wantedly_viewer_description_embeddings WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(executor)
+            .await?
+            .rows_affected();
+
+    Ok(deleted)
+}
+
+/// このトランザクションの HNSW の探索幅を candidates 件まで広げる
+///
+/// 既定の hnsw.ef_search（40）のままだと、それより多い近傍を引けない。
+pub async fn widen_hnsw_search(
+    conn: &mut PgConnection,
+    candidates: i64,
+) -> Result<(), WantedlyEmbeddingError> {
+    sqlx::query("SELECT set_config('hnsw.ef_search', $1, true)")
+        .bind(candidates.clamp(40, 1000).to_string())
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// model で作った会社の埋め込み（なければ None）
+pub async fn find_company_embedding(
+    executor: impl PgExecutor<'_>,
+    company_id: i64,
+    model: &str,
+) -> Result<Option<Vec<f32>>, WantedlyEmbeddingError> {
+    let embedding: Option<Vector> = sqlx::query_scalar(
+        r#"
+        SELECT embedding
+        FROM wantedly_company_embeddings
+        WHERE company_id = $1 AND model = $2
+        "#,
+    )
+    .bind(company_id)
+    .bind(model)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(embedding.map(Vec::from))
+}
+
+/// embedding にコサイン距離の近い会社を limit 件
+///
+/// HNSW インデックスで近い順に candidates 件を引き、has_viewed の絞り込みはその後にかける。
+/// hnsw.ef_search を candidates まで広げるので、トランザクションの中で呼ぶ。
+pub async fn list_similar_companies(
+    conn: &mut PgConnection,
+    embedding: &[f32],
+    model: &str,
+    exclude_company_id: Option<i64>,
+    has_viewed: Option<bool>,
+    candidates: i64,
+    limit: i64,
+) -> Result<Vec<WantedlySimilarCompany>, WantedlyEmbeddingError> {
+    widen_hnsw_search(&mut *conn, candidates).await?;
+
+    let rows = sqlx::query_as::<_, WantedlySimilarCompany>(
+        r#"
+        SELECT
+            n.company_id,
+            c.company_slug,
+            a.name,
+            a.industry,
+            n.distance,
+            v.views > 0 AS has_viewed,
+            v.views,
+            v.last_viewed_at
+        FROM (
+            SELECT e.company_id, e.embedding <=> $1 AS distance
+            FROM wantedly_company_embeddings e
+            WHERE e.model = $2
+              AND e.company_id IS DISTINCT FROM $3
+            ORDER BY e.embedding <=> $1
+            LIMIT $5
+        ) n
+        JOIN wantedly_companies c ON c.id = n.company_id
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = n.company_id
+        CROSS JOIN LATERAL (
+            SELECT COUNT(*) AS views, MAX(i.impressed_at) AS last_viewed_at
+            FROM wantedly_impressions i
+            WHERE i.company_id_at_view = n.company_id
+        ) v
+        WHERE $4::boolean IS NULL OR (v.views > 0) = $4
+        ORDER BY n.distance, n.company_id
+        LIMIT $6
+        "#,
+    )
+    .bind(Vector::from(embedding.to_vec()))
+    .bind(model)
+    .bind(exclude_company_id)
+    .bind(has_viewed)
+    .bind(candidates)
+    .bind(limit)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows)
+}
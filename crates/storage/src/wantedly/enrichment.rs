@@ -0,0 +1,166 @@
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WantedlyEnrichmentError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// AI で属性を推定する会社
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyEnrichmentCandidate {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub company_page_url: String,
+    /// 同じプロンプトの版・モデルで続けて却下された回数
+    pub failures: i32,
+}
+
+/// その会社に所属していたときの閲覧者の自己紹介
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyCompanyViewerDescription {
+    pub company_id: i64,
+    pub short_description: String,
+}
+
+/// 属性のない会社を slug 順に limit 社
+///
+/// refresh_stale なら、別のプロンプトの版・モデルで推定した AI の行も対象にする（手入力の行は対象外）。
+/// 同じ版・モデルで却下され、次の推定時刻が来ていない会社は除く（先頭の会社が却下され続けても次の会社に進む）。
+pub async fn list_enrichment_candidates(
+    executor: impl PgExecutor<'_>,
+    prompt_version: &str,
+    model: &str,
+    refresh_stale: bool,
+    limit: i64,
+) -> Result<Vec<WantedlyEnrichmentCandidate>, WantedlyEnrichmentError> {
+    let rows = sqlx::query_as::<_, WantedlyEnrichmentCandidate>(
+        r#"
+        SELECT
+            c.id AS company_id,
+            c.company_slug,
+            c.company_page_url,
+            COALESCE(f.failures, 0) AS failures
+        FROM wantedly_companies c
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = c.id
+        LEFT JOIN wantedly_company_enrichment_failures f
+            ON f.company_id = c.id AND f.prompt_version = $1 AND f.model = $2
+        WHERE (
+                a.id IS NULL
+                OR (
+                    $3
+                    AND a.source = 'ai'
+                    AND (a.prompt_version IS DISTINCT FROM $1 OR a.model IS DISTINCT FROM $2)
+                )
+           )
+          AND (f.company_id IS NULL OR f.next_attempt_at <= NOW())
+        ORDER BY c.company_slug
+        LIMIT $4
+        "#,
+    )
+    .bind(prompt_version)
+    .bind(model)
+    .bind(refresh_stale)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 応答の却下を記録し、retry_after の後まで候補から外す（failures は候補の回数 + 1 を渡す）
+pub async fn record_enrichment_failure(
+    executor: impl PgExecutor<'_>,
+    company_id: i64,
+    prompt_version: &str,
+    model: &str,
+    failures: i32,
+    error: &str,
+    retry_after: Duration,
+) -> Result<(), WantedlyEnrichmentError> {
+    sqlx::query(
+        r#"
+        INSERT INTO wantedly_company_enrichment_failures (
+            company_id, prompt_version, model, failures, last_error, next_attempt_at
+        )
+        VALUES ($1, $2, $3, $4, $5, NOW() + make_interval(secs => $6))
+        ON CONFLICT (company_id) DO UPDATE SET
+            prompt_version = EXCLUDED.prompt_version,
+            model = EXCLUDED.model,
+            failures = EXCLUDED.failures,
+            last_error = EXCLUDED.last_error,
+            last_attempted_at = NOW(),
+            next_attempt_at = EXCLUDED.next_attempt_at
+        "#,
+    )
+    .bind(company_id)
+    .bind(prompt_version)
+    .bind(model)
+    .bind(failures)
+    .bind(error)
+    .bind(retry_after.num_seconds() as f64)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// 属性を書き込めた会社の却下の記録を消す
+pub async fn clear_enrichment_failure(
+    executor: impl PgExecutor<'_>,
+    company_id: i64,
+) -> Result<(), WantedlyEnrichmentError> {
+    sqlx::query("DELETE FROM wantedly_company_enrichment_failures WHERE company_id = $1")
+        .bind(company_id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+/// 会社ごとに、所属履歴に現れた自己紹介を新しい順に per_company 件まで（同じ文は 1 件にまとめる）
+pub async fn list_company_viewer_descriptions(
+    executor: impl PgExecutor<'_>,
+    company_ids: &[i64],
+    per_company: i64,
+) -> Result<Vec<WantedlyCompanyViewerDescription>, WantedlyEnrichmentError> {
+    let rows = sqlx::query_as::<_, WantedlyCompanyViewerDescription>(
+        r#"
+        SELECT company_id, short_description
+        FROM (
+            SELECT
+                t.company_id,
+                t.short_description,
+                ROW_NUMBER() OVER (
+                    PARTITION BY t.company_id
+                    ORDER BY t.last_seen_at DESC, t.short_description
+                ) AS rn
+            FROM (
+                SELECT DISTINCT ON (a.company_id, d.short_description)
+                    a.company_id,
+                    d.short_description,
+                    a.last_seen_at
+                FROM wantedly_viewer_affiliations a
+                CROSS JOIN LATERAL (
+                    SELECT btrim(COALESCE(a.short_description, pii_decrypt(a.short_description_enc)))
+                        AS short_description
+                ) d
+                WHERE a.company_id = ANY($1)
+                  AND d.short_description <> ''
+                ORDER BY a.company_id, d.short_description, a.last_seen_at DESC
+            ) t
+        ) r
+        WHERE rn <= $2
+        ORDER BY company_id, rn
+        "#,
+    )
+    .bind(company_ids)
+    .bind(per_company)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
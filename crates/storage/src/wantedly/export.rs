@@ -0,0 +1,232 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+
+use crate::wantedly::ViewerAffiliationKind;
+
+#[derive(Debug, Error)]
+pub enum WantedlyExportError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// エクスポート共通の絞り込み条件（日付は JST の暦日、両端を含む）
+#[derive(Debug, Clone, Default)]
+pub struct WantedlyExportFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub company_slug: Option<String>,
+}
+
+/// export: raw_views
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyRawViewExportRow {
+    pub raw_profile_view_id: i64,
+    pub viewer_user_id: String,
+    pub viewer_company_page_url: Option<String>,
+    pub viewer_company_name_raw: Option<String>,
+    pub viewed_at_raw: String,
+    pub viewed_at: DateTime<Utc>,
+    pub raw_json: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// export: impressions
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyImpressionExportRow {
+    pub impression_id: i64,
+    pub impressed_at: DateTime<Utc>,
+    pub viewer_id: i64,
+    pub viewer_user_id: String,
+    pub company_id_at_view: Option<i64>,
+    pub company_slug_at_view: Option<String>,
+    pub affiliation_kind: Option<ViewerAffiliationKind>,
+    pub affiliation_short_description: Option<String>,
+    pub raw_profile_view_id: i64,
+}
+
+/// export: companies（属性は未取得なら NULL）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyCompanyExportRow {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub company_page_url: String,
+    pub name: Option<String>,
+    pub domain: Option<String>,
+    pub attribute_source: Option<String>,
+    pub confidence: Option<f64>,
+    pub industry: Option<String>,
+    pub employee_band: Option<String>,
+    pub funding_stage: Option<String>,
+    pub location: Option<String>,
+    pub total_views: i64,
+    pub distinct_viewers: i64,
+    pub first_viewed_at: Option<DateTime<Utc>>,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// export: daily_views（JST の暦日ごとの集計）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyDailyViewsExportRow {
+    pub date: NaiveDate,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub distinct_companies: i64,
+}
+
+pub async fn export_raw_views(
+    executor: impl PgExecutor<'_>,
+    filter: &WantedlyExportFilter,
+) -> Result<Vec<WantedlyRawViewExportRow>, WantedlyExportError> {
+    let rows = sqlx::query_as::<_, WantedlyRawViewExportRow>(
+        r#"
+        SELECT
+            r.id AS raw_profile_view_id,
+            COALESCE(pii_decrypt(r.viewer_user_id_enc), r.viewer_user_id) AS viewer_user_id,
+            r.viewer_company_page_url,
+            COALESCE(r.viewer_company_name_raw, pii_decrypt(r.viewer_company_name_raw_enc))
+                AS viewer_company_name_raw,
+            r.viewed_at_raw,
+            r.viewed_at,
+            COALESCE(r.raw_json, pii_decrypt(r.raw_json_enc)::jsonb) AS raw_json,
+            r.created_at
+        FROM wantedly_profile_view_raw r
+        LEFT JOIN wantedly_impressions i ON i.raw_profile_view_id = r.id
+        LEFT JOIN wantedly_companies c ON c.id = i.company_id_at_view
+        WHERE ($1::date IS NULL OR r.viewed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo')
+          AND ($2::date IS NULL OR r.viewed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo')
+          AND ($3::text IS NULL OR c.company_slug = $3)
+        ORDER BY r.viewed_at, r.id
+        "#,
+    )
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(&filter.company_slug)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn export_impressions(
+    executor: impl PgExecutor<'_>,
+    filter: &WantedlyExportFilter,
+) -> Result<Vec<WantedlyImpressionExportRow>, WantedlyExportError> {
+    let rows = sqlx::query_as::<_, WantedlyImpressionExportRow>(
+        r#"
+        SELECT
+            i.id AS impression_id,
+            i.impressed_at,
+            i.viewer_id,
+            COALESCE(pii_decrypt(r.viewer_user_id_enc), v.source_user_id) AS viewer_user_id,
+            i.company_id_at_view,
+            c.company_slug AS company_slug_at_view,
+            a.kind AS affiliation_kind,
+            COALESCE(a.short_description, pii_decrypt(a.short_description_enc))
+                AS affiliation_short_description,
+            i.raw_profile_view_id
+        FROM wantedly_impressions i
+        JOIN wantedly_viewers v ON v.id = i.viewer_id
+        JOIN wantedly_profile_view_raw r ON r.id = i.raw_profile_view_id
+        LEFT JOIN wantedly_companies c ON c.id = i.company_id_at_view
+        LEFT JOIN wantedly_viewer_affiliations a ON a.id = i.affiliation_id
+        WHERE ($1::date IS NULL OR i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo')
+          AND ($2::date IS NULL OR i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo')
+          AND ($3::text IS NULL OR c.company_slug = $3)
+        ORDER BY i.impressed_at, i.id
+        "#,
+    )
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(&filter.company_slug)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 期間指定がある場合は、期間内に閲覧のあった会社だけを返す
+pub async fn export_companies(
+    executor: impl PgExecutor<'_>,
+    filter: &WantedlyExportFilter,
+) -> Result<Vec<WantedlyCompanyExportRow>, WantedlyExportError> {
+    let rows = sqlx::query_as::<_, WantedlyCompanyExportRow>(
+        r#"
+        WITH views AS (
+            SELECT
+                i.company_id_at_view AS company_id,
+                COUNT(*) AS total_views,
+                COUNT(DISTINCT i.viewer_id) AS distinct_viewers,
+                MIN(i.impressed_at) AS first_viewed_at,
+                MAX(i.impressed_at) AS last_viewed_at
+            FROM wantedly_impressions i
+            WHERE i.company_id_at_view IS NOT NULL
+              AND ($1::date IS NULL OR i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo')
+              AND ($2::date IS NULL OR i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo')
+            GROUP BY i.company_id_at_view
+        )
+        SELECT
+            c.id AS company_id,
+            c.company_slug,
+            c.company_page_url,
+            a.name,
+            a.domain,
+            a.source::text AS attribute_source,
+            a.confidence::float8 AS confidence,
+            a.industry,
+            a.employee_band::text AS employee_band,
+            a.funding_stage::text AS funding_stage,
+            a.location,
+            COALESCE(v.total_views, 0) AS total_views,
+            COALESCE(v.distinct_viewers, 0) AS distinct_viewers,
+            v.first_viewed_at,
+            v.last_viewed_at,
+            c.created_at
+        FROM wantedly_companies c
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = c.id
+        LEFT JOIN views v ON v.company_id = c.id
+        WHERE ($3::text IS NULL OR c.company_slug = $3)
+          AND (($1::date IS NULL AND $2::date IS NULL) OR v.company_id IS NOT NULL)
+        ORDER BY c.id
+        "#,
+    )
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(&filter.company_slug)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn export_daily_views(
+    executor: impl PgExecutor<'_>,
+    filter: &WantedlyExportFilter,
+) -> Result<Vec<WantedlyDailyViewsExportRow>, WantedlyExportError> {
+    let rows = sqlx::query_as::<_, WantedlyDailyViewsExportRow>(
+        r#"
+        SELECT
+            (i.impressed_at AT TIME ZONE 'Asia/Tokyo')::date AS date,
+            COUNT(*) AS views,
+            COUNT(DISTINCT i.viewer_id) AS distinct_viewers,
+            COUNT(DISTINCT i.company_id_at_view) AS distinct_companies
+        FROM wantedly_impressions i
+        LEFT JOIN wantedly_companies c ON c.id = i.company_id_at_view
+        WHERE ($1::date IS NULL OR i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo')
+          AND ($2::date IS NULL OR i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo')
+          AND ($3::text IS NULL OR c.company_slug = $3)
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+    )
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(&filter.company_slug)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WantedlySnapshotImportError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: wantedly_snapshot_imports
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlySnapshotImport {
+    pub id: i64,
+    pub source_file: String,
+    pub file_sha256: String,
+    pub snapshot_at: DateTime<Utc>,
+    pub record_count: i32,
+    pub imported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWantedlySnapshotImport {
+    pub source_file: String,
+    pub file_sha256: String,
+    pub snapshot_at: DateTime<Utc>,
+    pub record_count: i32,
+}
+
+pub async fn find_snapshot_import_by_sha256(
+    executor: impl PgExecutor<'_>,
+    file_sha256: &str,
+) -> Result<Option<WantedlySnapshotImport>, WantedlySnapshotImportError> {
+    let row = sqlx::query_as::<_, WantedlySnapshotImport>(
+        r#"
+        SELECT id, source_file, file_sha256, snapshot_at, record_count, imported_at
+        FROM wantedly_snapshot_imports
+        WHERE file_sha256 = $1
+        "#,
+    )
+    .bind(file_sha256)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn insert_snapshot_import(
+    executor: impl PgExecutor<'_>,
+    new: &NewWantedlySnapshotImport,
+) -> Result<i64, WantedlySnapshotImportError> {
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO wantedly_snapshot_imports (source_file, file_sha256, snapshot_at, record_count)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(&new.source_file)
+    .bind(&new.file_sha256)
+    .bind(new.snapshot_at)
+    .bind(new.record_count)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn list_snapshot_imports(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<WantedlySnapshotImport>, WantedlySnapshotImportError> {
+    let rows = sqlx::query_as::<_, WantedlySnapshotImport>(
+        r#"
+        SELECT id, source_file, file_sha256, snapshot_at, record_count, imported_at
+        FROM wantedly_snapshot_imports
+        ORDER BY snapshot_at, id
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// スナップショット日時が from〜to（JST の暦日、両端を含む）の取り込み
+pub async fn list_snapshot_imports_between(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<WantedlySnapshotImport>, WantedlySnapshotImportError> {
+    let rows = sqlx::query_as::<_, WantedlySnapshotImport>(
+        r#"
+        SELECT id, source_file, file_sha256, snapshot_at, record_count, imported_at
+        FROM wantedly_snapshot_imports
+        WHERE snapshot_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+          AND snapshot_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        ORDER BY snapshot_at, id
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -1,14 +1,133 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyImpressionError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
 
 /// db-shema: wantedly_impressions
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct WantedlyImpression {
     pub id: i64,
     pub viewer_id: i64,
-    pub company_id_at_view: i64,
+    pub company_id_at_view: Option<i64>,
     pub impressed_at: DateTime<Utc>,
     pub raw_profile_view_id: i64,
     pub created_at: DateTime<Utc>,
+    pub affiliation_id: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWantedlyImpression {
+    pub viewer_id: i64,
+    pub company_id_at_view: Option<i64>,
+    pub impressed_at: DateTime<Utc>,
+    pub raw_profile_view_id: i64,
+    pub affiliation_id: Option<i64>,
+}
+
+/// raw レコード 1 件につきインプレッション 1 件（raw 側の upsert に追従して更新する）
+pub async fn upsert_impression(
+    executor: impl PgExecutor<'_>,
+    new: &NewWantedlyImpression,
+) -> Result<i64, WantedlyImpressionError> {
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO wantedly_impressions (
+            viewer_id,
+            company_id_at_view,
+            impressed_at,
+            raw_profile_view_id,
+            affiliation_id
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (raw_profile_view_id)
+        DO UPDATE SET
+            viewer_id          = EXCLUDED.viewer_id,
+            company_id_at_view = EXCLUDED.company_id_at_view,
+            impressed_at       = EXCLUDED.impressed_at,
+            affiliation_id     = EXCLUDED.affiliation_id
+        RETURNING id
+        "#,
+    )
+    .bind(new.viewer_id)
+    .bind(new.company_id_at_view)
+    .bind(new.impressed_at)
+    .bind(new.raw_profile_view_id)
+    .bind(new.affiliation_id)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+const IMPRESSION_COLUMNS: &str = r#"
+    i.id,
+    i.viewer_id,
+    i.company_id_at_view,
+    i.impressed_at,
+    i.raw_profile_view_id,
+    i.created_at,
+    i.affiliation_id"#;
+
+/// 会社（閲覧時点の所属）ごとに新しい順で最大 limit 件
+pub async fn list_impressions_by_company_ids(
+    executor: impl PgExecutor<'_>,
+    company_ids: &[i64],
+    limit: i64,
+) -> Result<Vec<WantedlyImpression>, WantedlyImpressionError> {
+    // 会社ごとに wantedly_impressions_company_time_idx を新しい方から limit 件だけ読む
+    let rows = sqlx::query_as::<_, WantedlyImpression>(&format!(
+        r#"
+        SELECT i.*
+        FROM UNNEST($1::bigint[]) AS k(company_id)
+        CROSS JOIN LATERAL (
+            SELECT {IMPRESSION_COLUMNS}
+            FROM wantedly_impressions i
+            WHERE i.company_id_at_view = k.company_id
+            ORDER BY i.impressed_at DESC, i.id DESC
+            LIMIT $2
+        ) i
+        ORDER BY i.company_id_at_view, i.impressed_at DESC, i.id DESC
+        "#
+    ))
+    .bind(company_ids)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 閲覧者ごとに新しい順で最大 limit 件
+pub async fn list_impressions_by_viewer_ids(
+    executor: impl PgExecutor<'_>,
+    viewer_ids: &[i64],
+    limit: i64,
+) -> Result<Vec<WantedlyImpression>, WantedlyImpressionError> {
+    let rows = sqlx::query_as::<_, WantedlyImpression>(&format!(
+        r#"
+        SELECT i.*
+        FROM UNNEST($1::bigint[]) AS k(viewer_id)
+        CROSS JOIN LATERAL (
+            SELECT {IMPRESSION_COLUMNS}
+            FROM wantedly_impressions i
+            WHERE i.viewer_id = k.viewer_id
+            ORDER BY i.impressed_at DESC, i.id DESC
+            LIMIT $2
+        ) i
+        ORDER BY i.viewer_id, i.impressed_at DESC, i.id DESC
+        "#
+    ))
+    .bind(viewer_ids)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
 }
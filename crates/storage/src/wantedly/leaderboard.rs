@@ -0,0 +1,202 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::wantedly::WantedlySortOrder;
+
+#[derive(Debug, Error)]
+pub enum WantedlyLeaderboardError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 会社ランキングの並び替えキー
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WantedlyLeaderboardSort {
+    #[default]
+    Views,
+    DistinctViewers,
+    FirstViewedAt,
+    LastViewedAt,
+    /// 直前の同じ長さの期間からの閲覧数の増減
+    Trend,
+}
+
+impl WantedlyLeaderboardSort {
+    fn order_by(self) -> &'static str {
+        match self {
+            WantedlyLeaderboardSort::Views => "cur.views",
+            WantedlyLeaderboardSort::DistinctViewers => "cur.distinct_viewers",
+            WantedlyLeaderboardSort::FirstViewedAt => "cur.first_viewed_at",
+            WantedlyLeaderboardSort::LastViewedAt => "cur.last_viewed_at",
+            WantedlyLeaderboardSort::Trend => "cur.views - COALESCE(prev.views, 0)",
+        }
+    }
+}
+
+/// 期間内に閲覧のあった会社ごとの集計（previous_* は直前の同じ長さの期間）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyCompanyLeaderboardRow {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub company_page_url: String,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub first_viewed_at: DateTime<Utc>,
+    pub last_viewed_at: DateTime<Utc>,
+    pub previous_views: i64,
+    pub previous_distinct_viewers: i64,
+    pub name: Option<String>,
+    pub domain: Option<String>,
+    pub attribute_source: Option<String>,
+    pub confidence: Option<f64>,
+}
+
+/// from〜to（JST の暦日、両端を含む）の会社ランキング
+pub async fn list_company_leaderboard(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    sort: WantedlyLeaderboardSort,
+    order: WantedlySortOrder,
+    limit: i64,
+) -> Result<Vec<WantedlyCompanyLeaderboardRow>, WantedlyLeaderboardError> {
+    let dir = match order {
+        WantedlySortOrder::Asc => "ASC",
+        WantedlySortOrder::Desc => "DESC",
+    };
+
+    let rows = sqlx::query_as::<_, WantedlyCompanyLeaderboardRow>(&format!(
+        r#"
+        WITH bounds AS (
+            SELECT
+                ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo' AS cur_start,
+                ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo' AS cur_end,
+                ($1::date - ($2::date - $1::date + 1))::timestamp AT TIME ZONE 'Asia/Tokyo'
+                    AS prev_start
+        ),
+        cur AS (
+            SELECT
+                i.company_id_at_view AS company_id,
+                COUNT(*) AS views,
+                COUNT(DISTINCT i.viewer_id) AS distinct_viewers,
+                MIN(i.impressed_at) AS first_viewed_at,
+                MAX(i.impressed_at) AS last_viewed_at
+            FROM wantedly_impressions i, bounds b
+            WHERE i.company_id_at_view IS NOT NULL
+              AND i.impressed_at >= b.cur_start
+              AND i.impressed_at < b.cur_end
+            GROUP BY i.company_id_at_view
+        ),
+        prev AS (
+            SELECT
+                i.company_id_at_view AS company_id,
+                COUNT(*) AS views,
+                COUNT(DISTINCT i.viewer_id) AS distinct_viewers
+            FROM wantedly_impressions i, bounds b
+            WHERE i.company_id_at_view IS NOT NULL
+              AND i.impressed_at >= b.prev_start
+              AND i.impressed_at < b.cur_start
+            GROUP BY i.company_id_at_view
+        )
+        SELECT
+            c.id AS company_id,
+            c.company_slug,
+            c.company_page_url,
+            cur.views,
+            cur.distinct_viewers,
+            cur.first_viewed_at,
+            cur.last_viewed_at,
+            COALESCE(prev.views, 0) AS previous_views,
+            COALESCE(prev.distinct_viewers, 0) AS previous_distinct_viewers,
+            a.name,
+            a.domain,
+            a.source::text AS attribute_source,
+            a.confidence::float8 AS confidence
+        FROM cur
+        JOIN wantedly_companies c ON c.id = cur.company_id
+        LEFT JOIN prev ON prev.company_id = cur.company_id
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = c.id
+        ORDER BY {sort} {dir}, c.id
+        LIMIT $3
+        "#,
+        sort = sort.order_by(),
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 初めて閲覧のあった日が期間内の会社
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyNewCompanyRow {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub company_page_url: String,
+    pub first_viewed_at: DateTime<Utc>,
+    /// 期間内の閲覧数
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub name: Option<String>,
+}
+
+/// from〜to（JST の暦日、両端を含む）に初めて閲覧のあった会社を、初回閲覧の古い順に返す
+pub async fn list_new_companies(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    limit: i64,
+) -> Result<Vec<WantedlyNewCompanyRow>, WantedlyLeaderboardError> {
+    let rows = sqlx::query_as::<_, WantedlyNewCompanyRow>(
+        r#"
+        WITH bounds AS (
+            SELECT
+                ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo' AS cur_start,
+                ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo' AS cur_end
+        ),
+        per_company AS (
+            SELECT
+                i.company_id_at_view AS company_id,
+                MIN(i.impressed_at) AS first_viewed_at,
+                COUNT(*) FILTER (
+                    WHERE i.impressed_at >= b.cur_start AND i.impressed_at < b.cur_end
+                ) AS views,
+                COUNT(DISTINCT i.viewer_id) FILTER (
+                    WHERE i.impressed_at >= b.cur_start AND i.impressed_at < b.cur_end
+                ) AS distinct_viewers
+            FROM wantedly_impressions i, bounds b
+            WHERE i.company_id_at_view IS NOT NULL
+              AND i.impressed_at < b.cur_end
+            GROUP BY i.company_id_at_view
+        )
+        SELECT
+            c.id AS company_id,
+            c.company_slug,
+            c.company_page_url,
+            p.first_viewed_at,
+            p.views,
+            p.distinct_viewers,
+            a.name
+        FROM per_company p, bounds b, wantedly_companies c
+        LEFT JOIN wantedly_company_attributes a ON a.company_id = c.id
+        WHERE c.id = p.company_id
+          AND p.first_viewed_at >= b.cur_start
+        ORDER BY p.first_viewed_at, c.id
+        LIMIT $3
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
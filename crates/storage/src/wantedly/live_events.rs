@@ -0,0 +1,184 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+/// 新しいイベントを知らせる NOTIFY のチャネル（payload は最後のイベント id）
+pub const LIVE_EVENTS_CHANNEL: &str = "wantedly_live_events";
+
+#[derive(Debug, Error)]
+pub enum WantedlyLiveEventError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// db-shema: wantedly_live_event_kind ENUM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "wantedly_live_event_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WantedlyLiveEventKind {
+    Impression,
+    Company,
+}
+
+impl WantedlyLiveEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WantedlyLiveEventKind::Impression => "impression",
+            WantedlyLiveEventKind::Company => "company",
+        }
+    }
+}
+
+/// db-shema: wantedly_live_events
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyLiveEvent {
+    pub id: i64,
+    pub kind: WantedlyLiveEventKind,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 正規化の前の時点で最大の id（これより大きい行を「新しく現れた」とみなす）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct WantedlyLiveEventWatermark {
+    pub impression_id: i64,
+    pub company_id: i64,
+}
+
+impl WantedlyLiveEventWatermark {
+    /// 会社もインプレッションもまだない（初回の一括取り込み）
+    pub fn is_empty(&self) -> bool {
+        self.impression_id == 0 && self.company_id == 0
+    }
+}
+
+pub async fn find_live_event_watermark(
+    executor: impl PgExecutor<'_>,
+) -> Result<WantedlyLiveEventWatermark, WantedlyLiveEventError> {
+    let watermark = sqlx::query_as::<_, WantedlyLiveEventWatermark>(
+        r#"
+        SELECT
+            (SELECT COALESCE(MAX(id), 0) FROM wantedly_impressions) AS impression_id,
+            (SELECT COALESCE(MAX(id), 0) FROM wantedly_companies) AS company_id
+        "#,
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(watermark)
+}
+
+/// watermark より後に作られた会社・インプレッションをイベントとして記録し、NOTIFY する
+///
+/// 会社のイベントを先に、インプレッションは閲覧日時の順に積む。
+/// NOTIFY はトランザクションのコミット時に届く。
+pub async fn publish_live_events_after(
+    conn: &mut PgConnection,
+    watermark: &WantedlyLiveEventWatermark,
+) -> Result<u64, WantedlyLiveEventError> {
+    let companies = sqlx::query(
+        r#"
+        INSERT INTO wantedly_live_events (kind, company_id, payload)
+        SELECT
+            'company',
+            c.id,
+            jsonb_build_object(
+                'company_id', c.id,
+                'company_slug', c.company_slug,
+                'company_page_url', c.company_page_url
+            )
+        FROM wantedly_companies c
+        WHERE c.id > $1
+        ORDER BY c.id
+        "#,
+    )
+    .bind(watermark.company_id)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    let impressions = sqlx::query(
+        r#"
+        INSERT INTO wantedly_live_events (kind, impression_id, company_id, payload)
+        SELECT
+            'impression',
+            i.id,
+            i.company_id_at_view,
+            jsonb_build_object(
+                'impression_id', i.id,
+                'viewer_id', i.viewer_id,
+                'company_id', i.company_id_at_view,
+                'company_slug', c.company_slug,
+                'impressed_at', i.impressed_at
+            )
+        FROM wantedly_impressions i
+        LEFT JOIN wantedly_companies c ON c.id = i.company_id_at_view
+        WHERE i.id > $1
+        ORDER BY i.impressed_at, i.id
+        "#,
+    )
+    .bind(watermark.impression_id)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    let published = companies + impressions;
+    if published > 0 {
+        sqlx::query("SELECT pg_notify($1, (SELECT MAX(id) FROM wantedly_live_events)::text)")
+            .bind(LIVE_EVENTS_CHANNEL)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(published)
+}
+
+/// after_id より後のイベントを古い順に最大 limit 件
+pub async fn list_live_events_after(
+    executor: impl PgExecutor<'_>,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<WantedlyLiveEvent>, WantedlyLiveEventError> {
+    let rows = sqlx::query_as::<_, WantedlyLiveEvent>(
+        r#"
+        SELECT id, kind, payload, created_at
+        FROM wantedly_live_events
+        WHERE id > $1
+        ORDER BY id
+        LIMIT $2
+        "#,
+    )
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 最新のイベント id（まだなければ 0）
+pub async fn latest_live_event_id(
+    executor: impl PgExecutor<'_>,
+) -> Result<i64, WantedlyLiveEventError> {
+    let id = sqlx::query_scalar("SELECT COALESCE(MAX(id), 0) FROM wantedly_live_events")
+        .fetch_one(executor)
+        .await?;
+
+    Ok(id)
+}
+
+/// before より前に記録したイベントを削除する（再送できる範囲を保つ）
+pub async fn delete_live_events_before(
+    executor: impl PgExecutor<'_>,
+    before: DateTime<Utc>,
+) -> Result<u64, WantedlyLiveEventError> {
+    let deleted = sqlx::query("DELETE FROM wantedly_live_events WHERE created_at < $1")
+        .bind(before)
+        .execute(executor)
+        .await?
+        .rows_affected();
+
+    Ok(deleted)
+}
@@ -1,9 +1,37 @@
+pub mod affiliations;
+pub mod anomalies;
+pub mod breakdown;
 pub mod companies;
+pub mod embeddings;
+pub mod enrichment;
+pub mod export;
+pub mod imports;
 pub mod impressions;
+pub mod leaderboard;
+pub mod live_events;
+pub mod own_profile;
 pub mod raw;
+pub mod repeat;
+pub mod retention;
+pub mod timeseries;
 pub mod viewers;
+pub mod views;
 
+pub use affiliations::*;
+pub use anomalies::*;
+pub use breakdown::*;
 pub use companies::*;
+pub use embeddings::*;
+pub use enrichment::*;
+pub use export::*;
+pub use imports::*;
 pub use impressions::*;
+pub use leaderboard::*;
+pub use live_events::*;
+pub use own_profile::*;
 pub use raw::*;
+pub use repeat::*;
+pub use retention::*;
+pub use timeseries::*;
 pub use viewers::*;
+pub use views::*;
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor, types::Json};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyOwnProfileError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 職歴の 1 件。日付は "2020-04" のような表記ゆれがあるため文字列のまま持つ
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct WantedlyOwnProfileExperience {
+    pub company: String,
+    pub title: Option<String>,
+    pub started_on: Option<String>,
+    pub ended_on: Option<String>,
+    pub description: Option<String>,
+}
+
+/// db-shema: wantedly_own_profile_versions
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyOwnProfileVersion {
+    pub id: i64,
+    pub captured_at: DateTime<Utc>,
+    pub source_file: String,
+    pub source_format: String,
+    pub file_sha256: String,
+    pub content_sha256: String,
+    pub headline: Option<String>,
+    pub skills: Json<Vec<String>>,
+    pub experiences: Json<Vec<WantedlyOwnProfileExperience>>,
+    pub imported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWantedlyOwnProfileVersion {
+    pub captured_at: DateTime<Utc>,
+    pub source_file: String,
+    pub source_format: String,
+    pub file_sha256: String,
+    pub content_sha256: String,
+    pub headline: Option<String>,
+    pub skills: Vec<String>,
+    pub experiences: Vec<WantedlyOwnProfileExperience>,
+}
+
+const OWN_PROFILE_VERSION_COLUMNS: &str = "id, captured_at, source_file, source_format, \
+    file_sha256, content_sha256, headline, skills, experiences, imported_at";
+
+pub async fn find_own_profile_version_by_file_sha256(
+    executor: impl PgExecutor<'_>,
+    file_sha256: &str,
+) -> Result<Option<WantedlyOwnProfileVersion>, WantedlyOwnProfileError> {
+    let sql = format!(
+        "SELECT {OWN_PROFILE_VERSION_COLUMNS} FROM wantedly_own_profile_versions WHERE file_sha256 = $1"
+    );
+    let row = sqlx::query_as::<_, WantedlyOwnProfileVersion>(&sql)
+        .bind(file_sha256)
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(row)
+}
+
+/// captured_at より前で最も新しいバージョン
+pub async fn find_own_profile_version_before(
+    executor: impl PgExecutor<'_>,
+    captured_at: DateTime<Utc>,
+) -> Result<Option<WantedlyOwnProfileVersion>, WantedlyOwnProfileError> {
+    let sql = format!(
+        r#"
+        SELECT {OWN_PROFILE_VERSION_COLUMNS}
+        FROM wantedly_own_profile_versions
+        WHERE captured_at < $1
+        ORDER BY captured_at DESC, id DESC
+        LIMIT 1
+        "#
+    );
+    let row = sqlx::query_as::<_, WantedlyOwnProfileVersion>(&sql)
+        .bind(captured_at)
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(row)
+}
+
+pub async fn insert_own_profile_version(
+    executor: impl PgExecutor<'_>,
+    new: &NewWantedlyOwnProfileVersion,
+) -> Result<i64, WantedlyOwnProfileError> {
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO wantedly_own_profile_versions (
+            captured_at, source_file, source_format, file_sha256, content_sha256,
+            headline, skills, experiences
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#,
+    )
+    .bind(new.captured_at)
+    .bind(&new.source_file)
+    .bind(&new.source_format)
+    .bind(&new.file_sha256)
+    .bind(&new.content_sha256)
+    .bind(&new.headline)
+    .bind(Json(&new.skills))
+    .bind(Json(&new.experiences))
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+/// 全バージョンを captured_at の古い順に
+pub async fn list_own_profile_versions(
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<WantedlyOwnProfileVersion>, WantedlyOwnProfileError> {
+    let sql = format!(
+        "SELECT {OWN_PROFILE_VERSION_COLUMNS} FROM wantedly_own_profile_versions ORDER BY captured_at, id"
+    );
+    let rows = sqlx::query_as::<_, WantedlyOwnProfileVersion>(&sql)
+        .fetch_all(executor)
+        .await?;
+
+    Ok(rows)
+}
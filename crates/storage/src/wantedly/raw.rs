@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgConnection, PgExecutor, PgPool};
 use thiserror::Error;
+use utoipa::ToSchema;
 
 #[derive(Debug, Error)]
 pub enum WantedlyProfileViewRawError {
@@ -17,7 +18,10 @@ pub enum WantedlyProfileViewRawError {
 }
 
 /// db-shema: wantedly_profile_view_raw
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+///
+/// 暗号化された行は読み出し時に復号した値を持つ（viewer_user_id はブラインドインデックスのまま）
+/// raw_json_compacted_at が入っている行の raw_json は保持期間ポリシーで削った後の node
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct WantedlyProfileViewRaw {
     pub id: i64,
     pub viewer_user_id: String,
@@ -27,8 +31,22 @@ pub struct WantedlyProfileViewRaw {
     pub viewed_at: DateTime<Utc>,
     pub raw_json: Value,
     pub created_at: DateTime<Utc>,
+    pub raw_json_compacted_at: Option<DateTime<Utc>>,
 }
 
+/// WantedlyProfileViewRaw として読む列（テーブル別名 r、暗号化カラムは復号する）
+pub(crate) const PROFILE_VIEW_RAW_COLUMNS: &str = r#"
+    r.id,
+    r.viewer_user_id,
+    r.viewer_company_page_url,
+    COALESCE(r.viewer_company_name_raw, pii_decrypt(r.viewer_company_name_raw_enc))
+        AS viewer_company_name_raw,
+    r.viewed_at_raw,
+    r.viewed_at,
+    COALESCE(r.raw_json, pii_decrypt(r.raw_json_enc)::jsonb) AS raw_json,
+    r.created_at,
+    r.raw_json_compacted_at"#;
+
 #[derive(Debug, Clone)]
 pub struct NewWantedlyProfileViewRaw {
     pub viewer_user_id: String,
@@ -47,13 +65,28 @@ pub async fn insert_profile_view_raw_strict(
         r#"
         INSERT INTO wantedly_profile_view_raw (
             viewer_user_id,
+            viewer_user_id_enc,
             viewer_company_page_url,
             viewer_company_name_raw,
+            viewer_company_name_raw_enc,
             viewed_at_raw,
             viewed_at,
-            raw_json
+            raw_json,
+            raw_json_enc,
+            pii_key_id
+        )
+        VALUES (
+            pii_blind_index($1),
+            pii_encrypt($1),
+            $2,
+            pii_plaintext($3),
+            pii_encrypt($3),
+            $4,
+            $5,
+            CASE WHEN pii_key() IS NULL THEN $6::jsonb END,
+            pii_encrypt($6::jsonb::text),
+            pii_key_id()
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
         RETURNING id
         "#,
         new.viewer_user_id,
@@ -77,19 +110,45 @@ pub async fn upsert_profile_view_raw(
         r#"
         INSERT INTO wantedly_profile_view_raw (
             viewer_user_id,
+            viewer_user_id_enc,
             viewer_company_page_url,
             viewer_company_name_raw,
+            viewer_company_name_raw_enc,
             viewed_at_raw,
             viewed_at,
-            raw_json
+            raw_json,
+            raw_json_enc,
+            pii_key_id
+        )
+        VALUES (
+            pii_blind_index($1),
+            pii_encrypt($1),
+            $2,
+            pii_plaintext($3),
+            pii_encrypt($3),
+            $4,
+            $5,
+            CASE WHEN pii_key() IS NULL THEN $6::jsonb END,
+            pii_encrypt($6::jsonb::text),
+            pii_key_id()
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
         ON CONFLICT (viewer_user_id, viewed_at)
         DO UPDATE SET
-            viewer_company_page_url = EXCLUDED.viewer_company_page_url,
-            viewer_company_name_raw = EXCLUDED.viewer_company_name_raw,
-            viewed_at_raw           = EXCLUDED.viewed_at_raw,
-            raw_json                = EXCLUDED.raw_json
+            viewer_user_id_enc          = EXCLUDED.viewer_user_id_enc,
+            viewer_company_page_url     = EXCLUDED.viewer_company_page_url,
+            viewer_company_name_raw     = EXCLUDED.viewer_company_name_raw,
+            viewer_company_name_raw_enc = EXCLUDED.viewer_company_name_raw_enc,
+            viewed_at_raw               = EXCLUDED.viewed_at_raw,
+            pii_key_id                  = EXCLUDED.pii_key_id,
+            -- 保持期間を過ぎて削った行は、再取り込みでもフルの node に戻さない
+            raw_json = CASE
+                WHEN wantedly_profile_view_raw.raw_json_compacted_at IS NULL THEN EXCLUDED.raw_json
+                WHEN pii_key() IS NULL THEN wantedly_compact_raw_json($6::jsonb)
+            END,
+            raw_json_enc = CASE
+                WHEN wantedly_profile_view_raw.raw_json_compacted_at IS NULL THEN EXCLUDED.raw_json_enc
+                ELSE pii_encrypt(wantedly_compact_raw_json($6::jsonb)::text)
+            END
         RETURNING id
         "#,
         new.viewer_user_id,
@@ -104,3 +163,54 @@ pub async fn upsert_profile_view_raw(
 
     Ok(id)
 }
+
+/// 正規化を直列にするトランザクション単位のロック（コミット・ロールバックで外れる）
+pub async fn lock_profile_view_normalization(
+    conn: &mut PgConnection,
+) -> Result<(), WantedlyProfileViewRawError> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('wantedly_profile_view_normalize'))")
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// 正規化用に、閲覧ユーザー（ブラインドインデックス）ごと・閲覧日時の古い順で全件返す
+///
+/// 読んだ行は FOR SHARE でロックするので、トランザクションが終わるまで取り込みの上書き・削除（purge）・
+/// 再暗号化はこれらの行を変えられない。正規化の書き込みと同じトランザクションで呼ぶ。
+pub async fn list_profile_views_raw_ordered(
+    conn: &mut PgConnection,
+) -> Result<Vec<WantedlyProfileViewRaw>, WantedlyProfileViewRawError> {
+    let rows = sqlx::query_as::<_, WantedlyProfileViewRaw>(&format!(
+        r#"
+        SELECT {PROFILE_VIEW_RAW_COLUMNS}
+        FROM wantedly_profile_view_raw r
+        ORDER BY r.viewer_user_id, r.viewed_at, r.id
+        FOR SHARE OF r
+        "#
+    ))
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows)
+}
+
+/// id をまとめて引く（見つからない id は結果に含まれない）
+pub async fn list_profile_views_raw_by_ids(
+    executor: impl PgExecutor<'_>,
+    ids: &[i64],
+) -> Result<Vec<WantedlyProfileViewRaw>, WantedlyProfileViewRawError> {
+    let rows = sqlx::query_as::<_, WantedlyProfileViewRaw>(&format!(
+        r#"
+        SELECT {PROFILE_VIEW_RAW_COLUMNS}
+        FROM wantedly_profile_view_raw r
+        WHERE r.id = ANY($1)
+        "#
+    ))
+    .bind(ids)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,286 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyRepeatViewError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 複数の日（JST）に閲覧した閲覧者
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyRepeatViewerRow {
+    pub viewer_id: i64,
+    pub viewer_user_id: String,
+    pub current_company_slug: Option<String>,
+    pub view_days: i64,
+    pub first_view_date: NaiveDate,
+    pub second_view_date: Option<NaiveDate>,
+    pub last_view_date: NaiveDate,
+    /// 初回から 2 日目の閲覧までの日数
+    pub days_to_return: Option<i32>,
+}
+
+/// 複数の日（JST）に閲覧のあった会社
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyRepeatCompanyRow {
+    pub company_id: i64,
+    pub company_slug: String,
+    pub view_days: i64,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    /// この会社から複数の日に閲覧した閲覧者の数
+    pub repeat_viewers: i64,
+    pub first_view_date: NaiveDate,
+    pub last_view_date: NaiveDate,
+}
+
+/// 初回閲覧週（月曜始まり）ごとのコホート
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyViewerCohortRow {
+    pub cohort_week: NaiveDate,
+    pub viewers: i64,
+    /// 初回の後に別の日にも閲覧した閲覧者の数
+    pub returned_viewers: i64,
+    pub avg_days_to_return: Option<f64>,
+    pub median_days_to_return: Option<f64>,
+}
+
+/// コホートの週ごとの閲覧者数（week_offset = 0 が初回閲覧週）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyCohortRetentionRow {
+    pub cohort_week: NaiveDate,
+    pub week_offset: i32,
+    pub viewers: i64,
+}
+
+/// from〜to（JST の暦日）の閲覧のうち、min_view_days 日以上閲覧した閲覧者
+pub async fn list_repeat_viewers(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    min_view_days: i64,
+    limit: i64,
+) -> Result<Vec<WantedlyRepeatViewerRow>, WantedlyRepeatViewError> {
+    let rows = sqlx::query_as::<_, WantedlyRepeatViewerRow>(
+        r#"
+        WITH days AS (
+            SELECT DISTINCT
+                i.viewer_id,
+                (i.impressed_at AT TIME ZONE 'Asia/Tokyo')::date AS day
+            FROM wantedly_impressions i
+            WHERE i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+              AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        ),
+        ranked AS (
+            SELECT
+                viewer_id,
+                day,
+                ROW_NUMBER() OVER (PARTITION BY viewer_id ORDER BY day) AS rn
+            FROM days
+        ),
+        per_viewer AS (
+            SELECT
+                viewer_id,
+                COUNT(*) AS view_days,
+                MIN(day) AS first_view_date,
+                MAX(day) FILTER (WHERE rn = 2) AS second_view_date,
+                MAX(day) AS last_view_date
+            FROM ranked
+            GROUP BY viewer_id
+            HAVING COUNT(*) >= $3
+        )
+        SELECT
+            v.id AS viewer_id,
+            COALESCE(
+                (
+                    SELECT pii_decrypt(r.viewer_user_id_enc)
+                    FROM wantedly_profile_view_raw r
+                    WHERE r.viewer_user_id = v.source_user_id
+                    LIMIT 1
+                ),
+                v.source_user_id
+            ) AS viewer_user_id,
+            c.company_slug AS current_company_slug,
+            p.view_days,
+            p.first_view_date,
+            p.second_view_date,
+            p.last_view_date,
+            p.second_view_date - p.first_view_date AS days_to_return
+        FROM per_viewer p
+        JOIN wantedly_viewers v ON v.id = p.viewer_id
+        LEFT JOIN wantedly_companies c ON c.id = v.company_id
+        ORDER BY p.view_days DESC, p.last_view_date DESC, v.id
+        LIMIT $4
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .bind(min_view_days)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// from〜to の閲覧のうち、min_view_days 日以上閲覧のあった会社
+pub async fn list_repeat_companies(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    min_view_days: i64,
+    limit: i64,
+) -> Result<Vec<WantedlyRepeatCompanyRow>, WantedlyRepeatViewError> {
+    let rows = sqlx::query_as::<_, WantedlyRepeatCompanyRow>(
+        r#"
+        WITH views AS (
+            SELECT
+                i.company_id_at_view AS company_id,
+                i.viewer_id,
+                (i.impressed_at AT TIME ZONE 'Asia/Tokyo')::date AS day
+            FROM wantedly_impressions i
+            WHERE i.company_id_at_view IS NOT NULL
+              AND i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+              AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        ),
+        viewer_days AS (
+            SELECT company_id, viewer_id, COUNT(DISTINCT day) AS view_days
+            FROM views
+            GROUP BY company_id, viewer_id
+        ),
+        per_company AS (
+            SELECT
+                company_id,
+                COUNT(DISTINCT day) AS view_days,
+                COUNT(*) AS views,
+                COUNT(DISTINCT viewer_id) AS distinct_viewers,
+                MIN(day) AS first_view_date,
+                MAX(day) AS last_view_date
+            FROM views
+            GROUP BY company_id
+            HAVING COUNT(DISTINCT day) >= $3
+        )
+        SELECT
+            c.id AS company_id,
+            c.company_slug,
+            p.view_days,
+            p.views,
+            p.distinct_viewers,
+            (
+                SELECT COUNT(*)
+                FROM viewer_days vd
+                WHERE vd.company_id = p.company_id AND vd.view_days >= 2
+            ) AS repeat_viewers,
+            p.first_view_date,
+            p.last_view_date
+        FROM per_company p
+        JOIN wantedly_companies c ON c.id = p.company_id
+        ORDER BY p.view_days DESC, p.views DESC, c.id
+        LIMIT $4
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .bind(min_view_days)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 初回閲覧日が from〜to の閲覧者を初回閲覧週でまとめ、戻ってきた割合と日数を集計する
+///
+/// 戻ってきたかどうかは期間の後の閲覧も含めて判定する。
+pub async fn list_viewer_cohorts(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<WantedlyViewerCohortRow>, WantedlyRepeatViewError> {
+    let rows = sqlx::query_as::<_, WantedlyViewerCohortRow>(
+        r#"
+        WITH days AS (
+            SELECT DISTINCT
+                i.viewer_id,
+                (i.impressed_at AT TIME ZONE 'Asia/Tokyo')::date AS day
+            FROM wantedly_impressions i
+        ),
+        ranked AS (
+            SELECT
+                viewer_id,
+                day,
+                ROW_NUMBER() OVER (PARTITION BY viewer_id ORDER BY day) AS rn
+            FROM days
+        ),
+        per_viewer AS (
+            SELECT
+                viewer_id,
+                MIN(day) AS first_day,
+                MAX(day) FILTER (WHERE rn = 2) AS second_day
+            FROM ranked
+            GROUP BY viewer_id
+        )
+        SELECT
+            date_trunc('week', first_day::timestamp)::date AS cohort_week,
+            COUNT(*) AS viewers,
+            COUNT(second_day) AS returned_viewers,
+            AVG(second_day - first_day)::float8 AS avg_days_to_return,
+            (percentile_cont(0.5) WITHIN GROUP (ORDER BY second_day - first_day))::float8
+                AS median_days_to_return
+        FROM per_viewer
+        WHERE first_day BETWEEN $1 AND $2
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// list_viewer_cohorts と同じコホートの、初回閲覧週から何週目に閲覧があったか
+pub async fn list_viewer_cohort_retention(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<WantedlyCohortRetentionRow>, WantedlyRepeatViewError> {
+    let rows = sqlx::query_as::<_, WantedlyCohortRetentionRow>(
+        r#"
+        WITH days AS (
+            SELECT DISTINCT
+                i.viewer_id,
+                (i.impressed_at AT TIME ZONE 'Asia/Tokyo')::date AS day
+            FROM wantedly_impressions i
+        ),
+        cohort AS (
+            SELECT
+                viewer_id,
+                date_trunc('week', MIN(day)::timestamp)::date AS cohort_week
+            FROM days
+            GROUP BY viewer_id
+            HAVING MIN(day) BETWEEN $1 AND $2
+        )
+        SELECT
+            c.cohort_week,
+            ((d.day - c.cohort_week) / 7)::int AS week_offset,
+            COUNT(DISTINCT d.viewer_id) AS viewers
+        FROM cohort c
+        JOIN days d ON d.viewer_id = c.viewer_id
+        GROUP BY 1, 2
+        ORDER BY 1, 2
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WantedlyRawRetentionError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// raw_json を削った（または削れる）行数と、raw_json / raw_json_enc のカラムサイズ（バイト）
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct WantedlyRawJsonCompaction {
+    pub rows: i64,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+impl WantedlyRawJsonCompaction {
+    pub fn reclaimed_bytes(&self) -> i64 {
+        self.bytes_before - self.bytes_after
+    }
+}
+
+/// viewed_at が cutoff より古く、正規化済み・未圧縮の行を最大 limit 件だけ削る
+///
+/// 削るのは現在のセッション鍵で保存された行だけ（鍵の違う行は rotate-pii-key で揃えてから）。
+pub async fn compact_profile_view_raw_json(
+    executor: impl PgExecutor<'_>,
+    cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<WantedlyRawJsonCompaction, WantedlyRawRetentionError> {
+    let compaction = sqlx::query_as::<_, WantedlyRawJsonCompaction>(
+        r#"
+        WITH target AS (
+            SELECT
+                r.id,
+                COALESCE(pg_column_size(r.raw_json), 0)
+                    + COALESCE(pg_column_size(r.raw_json_enc), 0) AS bytes_before
+            FROM wantedly_profile_view_raw r
+            WHERE r.raw_json_compacted_at IS NULL
+              AND r.viewed_at < $1
+              AND r.pii_key_id IS NOT DISTINCT FROM pii_key_id()
+              AND EXISTS (
+                  SELECT 1 FROM wantedly_impressions i WHERE i.raw_profile_view_id = r.id
+              )
+            ORDER BY r.id
+            LIMIT $2
+            FOR UPDATE OF r
+        ),
+        updated AS (
+            UPDATE wantedly_profile_view_raw r
+            SET
+                raw_json = wantedly_compact_raw_json(r.raw_json),
+                raw_json_enc = pii_encrypt(
+                    wantedly_compact_raw_json(pii_decrypt(r.raw_json_enc)::jsonb)::text
+                ),
+                raw_json_compacted_at = NOW()
+            FROM target t
+            WHERE r.id = t.id
+            RETURNING
+                t.bytes_before,
+                COALESCE(pg_column_size(r.raw_json), 0)
+                    + COALESCE(pg_column_size(r.raw_json_enc), 0) AS bytes_after
+        )
+        SELECT
+            COUNT(*) AS rows,
+            COALESCE(SUM(bytes_before), 0)::int8 AS bytes_before,
+            COALESCE(SUM(bytes_after), 0)::int8 AS bytes_after
+        FROM updated
+        "#,
+    )
+    .bind(cutoff)
+    .bind(limit)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(compaction)
+}
+
+/// compact_profile_view_raw_json の対象になる行の見積もり（書き込みはしない）
+pub async fn estimate_profile_view_raw_json_compaction(
+    executor: impl PgExecutor<'_>,
+    cutoff: DateTime<Utc>,
+) -> Result<WantedlyRawJsonCompaction, WantedlyRawRetentionError> {
+    let compaction = sqlx::query_as::<_, WantedlyRawJsonCompaction>(
+        r#"
+        SELECT
+            COUNT(*) AS rows,
+            COALESCE(SUM(
+                COALESCE(pg_column_size(r.raw_json), 0)
+                    + COALESCE(pg_column_size(r.raw_json_enc), 0)
+            ), 0)::int8 AS bytes_before,
+            COALESCE(SUM(
+                COALESCE(pg_column_size(wantedly_compact_raw_json(r.raw_json)), 0)
+                    + COALESCE(pg_column_size(pii_encrypt(
+                        wantedly_compact_raw_json(pii_decrypt(r.raw_json_enc)::jsonb)::text
+                    )), 0)
+            ), 0)::int8 AS bytes_after
+        FROM wantedly_profile_view_raw r
+        WHERE r.raw_json_compacted_at IS NULL
+          AND r.viewed_at < $1
+          AND r.pii_key_id IS NOT DISTINCT FROM pii_key_id()
+          AND EXISTS (
+              SELECT 1 FROM wantedly_impressions i WHERE i.raw_profile_view_id = r.id
+          )
+        "#,
+    )
+    .bind(cutoff)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(compaction)
+}
+
+/// wantedly_profile_view_raw のディスク使用量（TOAST・インデックス込み）
+pub async fn profile_view_raw_table_bytes(
+    executor: impl PgExecutor<'_>,
+) -> Result<i64, WantedlyRawRetentionError> {
+    let bytes =
+        sqlx::query_scalar("SELECT pg_total_relation_size('wantedly_profile_view_raw'::regclass)")
+            .fetch_one(executor)
+            .await?;
+
+    Ok(bytes)
+}
+
+/// 削った行の領域を再利用できるようにする（ファイルサイズ自体は縮まない）
+pub async fn vacuum_profile_view_raw(
+    executor: impl PgExecutor<'_>,
+) -> Result<(), WantedlyRawRetentionError> {
+    sqlx::query("VACUUM (ANALYZE) wantedly_profile_view_raw")
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
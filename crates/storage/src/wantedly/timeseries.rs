@@ -0,0 +1,164 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyTimeSeriesError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 集計の粒度（週は月曜始まり）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WantedlyTimeBucket {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl WantedlyTimeBucket {
+    /// date_trunc / interval に渡す単位
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WantedlyTimeBucket::Day => "day",
+            WantedlyTimeBucket::Week => "week",
+            WantedlyTimeBucket::Month => "month",
+        }
+    }
+}
+
+/// 1 区間の閲覧数（閲覧のない区間も 0 で返す。両端の区間は期間で切り詰める）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WantedlyViewBucket {
+    pub bucket_start: NaiveDate,
+    pub bucket_end: NaiveDate,
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub distinct_companies: i64,
+}
+
+/// 曜日ごとの閲覧数（iso_weekday は 1 = 月曜 〜 7 = 日曜）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct WantedlyWeekdayViews {
+    pub iso_weekday: i32,
+    pub views: i64,
+    pub distinct_viewers: i64,
+}
+
+/// 期間全体の閲覧数（閲覧者・会社は期間内で重複を除く）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct WantedlyViewTotals {
+    pub views: i64,
+    pub distinct_viewers: i64,
+    pub distinct_companies: i64,
+}
+
+/// from〜to（JST の暦日、両端を含む）の閲覧数を bucket ごとに集計する
+pub async fn list_view_time_series(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+    bucket: WantedlyTimeBucket,
+) -> Result<Vec<WantedlyViewBucket>, WantedlyTimeSeriesError> {
+    let rows = sqlx::query_as::<_, WantedlyViewBucket>(
+        r#"
+        WITH series AS (
+            SELECT s::date AS bucket
+            FROM generate_series(
+                date_trunc($3, $1::date::timestamp),
+                date_trunc($3, $2::date::timestamp),
+                ('1 ' || $3)::interval
+            ) AS s
+        ),
+        views AS (
+            SELECT
+                date_trunc($3, i.impressed_at AT TIME ZONE 'Asia/Tokyo')::date AS bucket,
+                i.viewer_id,
+                i.company_id_at_view
+            FROM wantedly_impressions i
+            WHERE i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+              AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        )
+        SELECT
+            GREATEST(s.bucket, $1::date) AS bucket_start,
+            LEAST((s.bucket + ('1 ' || $3)::interval)::date - 1, $2::date) AS bucket_end,
+            COUNT(v.bucket) AS views,
+            COUNT(DISTINCT v.viewer_id) AS distinct_viewers,
+            COUNT(DISTINCT v.company_id_at_view) AS distinct_companies
+        FROM series s
+        LEFT JOIN views v ON v.bucket = s.bucket
+        GROUP BY s.bucket
+        ORDER BY s.bucket
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .bind(bucket.as_str())
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// from〜to の閲覧数を JST の曜日ごとに集計する（7 行、閲覧のない曜日も 0 で返す）
+pub async fn list_views_by_weekday(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<WantedlyWeekdayViews>, WantedlyTimeSeriesError> {
+    let rows = sqlx::query_as::<_, WantedlyWeekdayViews>(
+        r#"
+        WITH views AS (
+            SELECT
+                EXTRACT(ISODOW FROM i.impressed_at AT TIME ZONE 'Asia/Tokyo')::int AS iso_weekday,
+                i.viewer_id
+            FROM wantedly_impressions i
+            WHERE i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+              AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        )
+        SELECT
+            d AS iso_weekday,
+            COUNT(v.iso_weekday) AS views,
+            COUNT(DISTINCT v.viewer_id) AS distinct_viewers
+        FROM generate_series(1, 7) AS d
+        LEFT JOIN views v ON v.iso_weekday = d
+        GROUP BY d
+        ORDER BY d
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// from〜to（JST の暦日、両端を含む）の閲覧数の合計
+pub async fn summarize_views(
+    executor: impl PgExecutor<'_>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<WantedlyViewTotals, WantedlyTimeSeriesError> {
+    let row = sqlx::query_as::<_, WantedlyViewTotals>(
+        r#"
+        SELECT
+            COUNT(*) AS views,
+            COUNT(DISTINCT i.viewer_id) AS distinct_viewers,
+            COUNT(DISTINCT i.company_id_at_view) AS distinct_companies
+        FROM wantedly_impressions i
+        WHERE i.impressed_at >= ($1::date)::timestamp AT TIME ZONE 'Asia/Tokyo'
+          AND i.impressed_at < ($2::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(row)
+}
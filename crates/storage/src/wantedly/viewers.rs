@@ -1,12 +1,143 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgExecutor};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+#[derive(Debug, Error)]
+pub enum WantedlyViewerError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
 
 /// db-shema: wantedly_viewers
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct WantedlyViewer {
     pub id: i64,
     pub source_user_id: String,
     pub company_id: Option<i64>,
     pub created_at: DateTime<Utc>,
 }
+
+/// 会社（閲覧時点の所属）ごとの閲覧者と、その閲覧者からの閲覧数
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyCompanyViewerRow {
+    pub company_id: i64,
+    pub viewer_id: i64,
+    pub views: i64,
+    pub first_viewed_at: DateTime<Utc>,
+    pub last_viewed_at: DateTime<Utc>,
+}
+
+pub async fn upsert_viewer(
+    executor: impl PgExecutor<'_>,
+    source_user_id: &str,
+) -> Result<i64, WantedlyViewerError> {
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO wantedly_viewers (source_user_id)
+        VALUES ($1)
+        ON CONFLICT (source_user_id)
+        DO UPDATE SET source_user_id = EXCLUDED.source_user_id
+        RETURNING id
+        "#,
+    )
+    .bind(source_user_id)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+/// 現在の所属会社（最新の所属履歴の会社）を更新する
+pub async fn update_viewer_current_company(
+    executor: impl PgExecutor<'_>,
+    viewer_id: i64,
+    company_id: Option<i64>,
+) -> Result<(), WantedlyViewerError> {
+    sqlx::query(
+        r#"
+        UPDATE wantedly_viewers
+        SET company_id = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(viewer_id)
+    .bind(company_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// 元の userId で引く（暗号化時はブラインドインデックスに変換して比較する）
+pub async fn find_viewer_by_source_user_id(
+    executor: impl PgExecutor<'_>,
+    source_user_id: &str,
+) -> Result<Option<WantedlyViewer>, WantedlyViewerError> {
+    let viewer = sqlx::query_as::<_, WantedlyViewer>(
+        r#"
+        SELECT id, source_user_id, company_id, created_at
+        FROM wantedly_viewers
+        WHERE source_user_id = pii_blind_index($1)
+        "#,
+    )
+    .bind(source_user_id)
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(viewer)
+}
+
+/// id をまとめて引く（見つからない id は結果に含まれない）
+pub async fn list_viewers_by_ids(
+    executor: impl PgExecutor<'_>,
+    ids: &[i64],
+) -> Result<Vec<WantedlyViewer>, WantedlyViewerError> {
+    let rows = sqlx::query_as::<_, WantedlyViewer>(
+        r#"
+        SELECT id, source_user_id, company_id, created_at
+        FROM wantedly_viewers
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(ids)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+/// 会社ごとに、最後の閲覧が新しい順で最大 limit 人の閲覧者
+pub async fn list_company_viewers_by_company_ids(
+    executor: impl PgExecutor<'_>,
+    company_ids: &[i64],
+    limit: i64,
+) -> Result<Vec<WantedlyCompanyViewerRow>, WantedlyViewerError> {
+    let rows = sqlx::query_as::<_, WantedlyCompanyViewerRow>(
+        r#"
+        SELECT v.company_id, v.viewer_id, v.views, v.first_viewed_at, v.last_viewed_at
+        FROM UNNEST($1::bigint[]) AS k(company_id)
+        CROSS JOIN LATERAL (
+            SELECT
+                i.company_id_at_view AS company_id,
+                i.viewer_id,
+                COUNT(*) AS views,
+                MIN(i.impressed_at) AS first_viewed_at,
+                MAX(i.impressed_at) AS last_viewed_at
+            FROM wantedly_impressions i
+            WHERE i.company_id_at_view = k.company_id
+            GROUP BY i.company_id_at_view, i.viewer_id
+            ORDER BY MAX(i.impressed_at) DESC, i.viewer_id
+            LIMIT $2
+        ) v
+        ORDER BY v.company_id, v.last_viewed_at DESC, v.viewer_id
+        "#,
+    )
+    .bind(company_ids)
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
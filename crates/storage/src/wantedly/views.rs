@@ -0,0 +1,267 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor, Postgres, QueryBuilder};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::wantedly::{
+    ViewerAffiliationKind, WantedlyImpression, WantedlyProfileViewRaw,
+    raw::PROFILE_VIEW_RAW_COLUMNS,
+};
+
+#[derive(Debug, Error)]
+pub enum WantedlyViewQueryError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+}
+
+/// 閲覧日時の並び順（同時刻は id で並べる）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WantedlySortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// キーセットページングの位置（直前のページの最後の行の閲覧日時と id）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WantedlyKeysetCursor {
+    pub at: DateTime<Utc>,
+    pub id: i64,
+}
+
+/// インプレッション / raw 一覧の絞り込み条件（日付は JST の暦日、両端を含む）
+///
+/// 条件のある項目だけを WHERE に積むので、会社・閲覧者で絞ると
+/// wantedly_impressions_company_time_idx / viewer_time_idx がそのまま使われる。
+#[derive(Debug, Clone, Default)]
+pub struct WantedlyViewQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub company_slug: Option<String>,
+    pub viewer_user_id: Option<String>,
+    pub affiliation_kind: Option<ViewerAffiliationKind>,
+    pub order: WantedlySortOrder,
+    pub after: Option<WantedlyKeysetCursor>,
+}
+
+/// 最近の閲覧 1 件（閲覧者・会社・所属の表示用の値つき）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WantedlyRecentViewRow {
+    pub impression_id: i64,
+    pub impressed_at: DateTime<Utc>,
+    pub viewer_id: i64,
+    pub viewer_user_id: String,
+    pub company_slug: Option<String>,
+    pub company_name: Option<String>,
+    pub affiliation_kind: Option<ViewerAffiliationKind>,
+    pub affiliation_short_description: Option<String>,
+}
+
+pub async fn list_impressions_page(
+    executor: impl PgExecutor<'_>,
+    query: &WantedlyViewQuery,
+    limit: i64,
+) -> Result<Vec<WantedlyImpression>, WantedlyViewQueryError> {
+    let mut qb = QueryBuilder::<Postgres>::new(
+        r#"
+        SELECT
+            i.id,
+            i.viewer_id,
+            i.company_id_at_view,
+            i.impressed_at,
+            i.raw_profile_view_id,
+            i.created_at,
+            i.affiliation_id
+        FROM wantedly_impressions i
+        WHERE TRUE"#,
+    );
+    push_impression_filters(&mut qb, query);
+    push_keyset(&mut qb, query, "i.impressed_at", "i.id");
+    qb.push(" LIMIT ").push_bind(limit);
+
+    let rows = qb
+        .build_query_as::<WantedlyImpression>()
+        .fetch_all(executor)
+        .await?;
+
+    Ok(rows)
+}
+
+/// 絞り込み条件に合うインプレッションの総数（カーソルは無視する）
+pub async fn count_impressions(
+    executor: impl PgExecutor<'_>,
+    query: &WantedlyViewQuery,
+) -> Result<i64, WantedlyViewQueryError> {
+    let mut qb =
+        QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM wantedly_impressions i WHERE TRUE");
+    push_impression_filters(&mut qb, query);
+
+    let count = qb.build_query_scalar().fetch_one(executor).await?;
+
+    Ok(count)
+}
+
+pub async fn list_profile_views_raw_page(
+    executor: impl PgExecutor<'_>,
+    query: &WantedlyViewQuery,
+    limit: i64,
+) -> Result<Vec<WantedlyProfileViewRaw>, WantedlyViewQueryError> {
+    let mut qb = QueryBuilder::<Postgres>::new("SELECT ");
+    qb.push(PROFILE_VIEW_RAW_COLUMNS)
+        .push(" FROM wantedly_profile_view_raw r WHERE TRUE");
+    push_raw_filters(&mut qb, query);
+    push_keyset(&mut qb, query, "r.viewed_at", "r.id");
+    qb.push(" LIMIT ").push_bind(limit);
+
+    let rows = qb
+        .build_query_as::<WantedlyProfileViewRaw>()
+        .fetch_all(executor)
+        .await?;
+
+    Ok(rows)
+}
+
+/// 絞り込み条件に合う raw 行の総数（カーソルは無視する）
+pub async fn count_profile_views_raw(
+    executor: impl PgExecutor<'_>,
+    query: &WantedlyViewQuery,
+) -> Result<i64, WantedlyViewQueryError> {
+    let mut qb = QueryBuilder::<Postgres>::new(
+        "SELECT COUNT(*) FROM wantedly_profile_view_raw r WHERE TRUE",
+    );
+    push_raw_filters(&mut qb, query);
+
+    let count = qb.build_query_scalar().fetch_one(executor).await?;
+
+    Ok(count)
+}
+
+/// 新しい順に limit 件の閲覧
+pub async fn list_recent_views(
+    executor: impl PgExecutor<'_>,
+    limit: i64,
+) -> Result<Vec<WantedlyRecentViewRow>, WantedlyViewQueryError> {
+    let rows = sqlx::query_as::<_, WantedlyRecentViewRow>(
+        r#"
+        SELECT
+            i.id AS impression_id,
+            i.impressed_at,
+            i.viewer_id,
+            COALESCE(pii_decrypt(r.viewer_user_id_enc), v.source_user_id) AS viewer_user_id,
+            c.company_slug,
+            ca.name AS company_name,
+            a.kind AS affiliation_kind,
+            COALESCE(a.short_description, pii_decrypt(a.short_description_enc))
+                AS affiliation_short_description
+        FROM wantedly_impressions i
+        JOIN wantedly_viewers v ON v.id = i.viewer_id
+        JOIN wantedly_profile_view_raw r ON r.id = i.raw_profile_view_id
+        LEFT JOIN wantedly_companies c ON c.id = i.company_id_at_view
+        LEFT JOIN wantedly_company_attributes ca ON ca.company_id = c.id
+        LEFT JOIN wantedly_viewer_affiliations a ON a.id = i.affiliation_id
+        ORDER BY i.impressed_at DESC, i.id DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows)
+}
+
+fn push_date_range(qb: &mut QueryBuilder<'_, Postgres>, query: &WantedlyViewQuery, column: &str) {
+    if let Some(from) = query.from {
+        qb.push(format_args!(" AND {column} >= ("))
+            .push_bind(from)
+            .push("::date)::timestamp AT TIME ZONE 'Asia/Tokyo'");
+    }
+    if let Some(to) = query.to {
+        qb.push(format_args!(" AND {column} < ("))
+            .push_bind(to)
+            .push("::date + 1)::timestamp AT TIME ZONE 'Asia/Tokyo'");
+    }
+}
+
+fn push_impression_filters(qb: &mut QueryBuilder<'_, Postgres>, query: &WantedlyViewQuery) {
+    push_date_range(qb, query, "i.impressed_at");
+
+    if let Some(slug) = &query.company_slug {
+        qb.push(
+            " AND i.company_id_at_view = (SELECT id FROM wantedly_companies WHERE company_slug = ",
+        )
+        .push_bind(slug.clone())
+        .push(")");
+    }
+    if let Some(user_id) = &query.viewer_user_id {
+        qb.push(
+            " AND i.viewer_id = (SELECT id FROM wantedly_viewers WHERE source_user_id = pii_blind_index(",
+        )
+        .push_bind(user_id.clone())
+        .push("))");
+    }
+    if let Some(kind) = query.affiliation_kind {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM wantedly_viewer_affiliations a WHERE a.id = i.affiliation_id AND a.kind = ",
+        )
+        .push_bind(kind)
+        .push(")");
+    }
+}
+
+fn push_raw_filters(qb: &mut QueryBuilder<'_, Postgres>, query: &WantedlyViewQuery) {
+    push_date_range(qb, query, "r.viewed_at");
+
+    if let Some(slug) = &query.company_slug {
+        qb.push(
+            r#" AND EXISTS (
+                SELECT 1
+                FROM wantedly_impressions i
+                JOIN wantedly_companies c ON c.id = i.company_id_at_view
+                WHERE i.raw_profile_view_id = r.id AND c.company_slug = "#,
+        )
+        .push_bind(slug.clone())
+        .push(")");
+    }
+    if let Some(user_id) = &query.viewer_user_id {
+        qb.push(" AND r.viewer_user_id = pii_blind_index(")
+            .push_bind(user_id.clone())
+            .push(")");
+    }
+    if let Some(kind) = query.affiliation_kind {
+        qb.push(
+            r#" AND EXISTS (
+                SELECT 1
+                FROM wantedly_impressions i
+                JOIN wantedly_viewer_affiliations a ON a.id = i.affiliation_id
+                WHERE i.raw_profile_view_id = r.id AND a.kind = "#,
+        )
+        .push_bind(kind)
+        .push(")");
+    }
+}
+
+fn push_keyset(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    query: &WantedlyViewQuery,
+    at_column: &str,
+    id_column: &str,
+) {
+    let (op, dir) = match query.order {
+        WantedlySortOrder::Asc => (">", "ASC"),
+        WantedlySortOrder::Desc => ("<", "DESC"),
+    };
+
+    if let Some(after) = query.after {
+        qb.push(format_args!(" AND ({at_column}, {id_column}) {op} ("))
+            .push_bind(after.at)
+            .push(", ")
+            .push_bind(after.id)
+            .push(")");
+    }
+    qb.push(format_args!(
+        " ORDER BY {at_column} {dir}, {id_column} {dir}"
+    ));
+}
@@ -1,10 +1,20 @@
 #!/usr/bin/env bash
 set -euo pipefail
 
+# SKIP_BACKUP=1 で退避せずに破棄する
+backup_path="local_data/backups/reset-$(date +%Y%m%d%H%M%S).tar.gz"
+if [[ "${SKIP_BACKUP:-0}" != "1" ]] && docker compose ps --status running --services | grep -qx postgres; then
+    cargo run -p rust-server -- backup --output "$backup_path"
+fi
+
 cargo clean
 
 docker compose down -v
 
 docker compose up -d postgres
 
-sqlx migrate run --source apps/rust-server/migrations
\ No newline at end of file
+sqlx migrate run --source apps/rust-server/migrations
+
+if [[ -f "$backup_path" ]]; then
+    cargo run -p rust-server -- restore --input "$backup_path"
+fi